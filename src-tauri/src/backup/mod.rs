@@ -0,0 +1,539 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration as StdDuration, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{async_runtime, AppHandle, Manager};
+use thiserror::Error;
+
+use crate::datetime::{cmp_instants, to_utc_string};
+use crate::storage::{
+    is_read_only, load_json, prepare_dir, write_atomic, EXCLUSIVE_LOCK_FILENAME, LOCK_FILENAME,
+};
+
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(30 * 60);
+const STATE_FILENAME: &str = "backup.json";
+const SEARCH_INDEX_FILENAME: &str = "search-index.json";
+/// 语音模型（含 Core ML 编码器）所在目录，相对数据目录。
+const MODELS_DIR: &str = "speech/models/";
+const OBJECTS_DIR: &str = "objects";
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("task join error: {0}")]
+    Join(String),
+    #[error("尚未设置备份目录")]
+    NotConfigured,
+    #[error("备份目录不能位于应用数据目录内")]
+    InvalidTarget,
+    #[error("未找到指定的备份：{0}")]
+    SnapshotNotFound(String),
+    #[error("备份数据不完整，缺少文件：{0}")]
+    MissingObject(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub target_dir: Option<String>,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_dir: None,
+            interval_hours: default_interval_hours(),
+        }
+    }
+}
+
+fn default_interval_hours() -> u32 {
+    24
+}
+
+/// 记录上次备份时各文件的大小、修改时间与哈希，未变化的文件无需重新读取。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct FileFingerprint {
+    size: u64,
+    modified_ms: u64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BackupState {
+    #[serde(default)]
+    settings: BackupSettings,
+    #[serde(default)]
+    last_backup_at: Option<String>,
+    #[serde(default)]
+    last_error: Option<String>,
+    #[serde(default)]
+    fingerprints: HashMap<String, FileFingerprint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupStatus {
+    pub settings: BackupSettings,
+    pub last_backup_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSummary {
+    pub id: String,
+    pub created_at: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub new_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupFile {
+    path: String,
+    hash: String,
+    size: u64,
+}
+
+/// 每次备份写一份完整清单，文件内容按哈希存放在 objects 下并在各次备份间共享。
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    #[serde(flatten)]
+    summary: BackupSummary,
+    files: Vec<BackupFile>,
+}
+
+pub struct BackupManager {
+    data_dir: PathBuf,
+    state_file: PathBuf,
+    state: Arc<async_runtime::Mutex<BackupState>>,
+}
+
+impl BackupManager {
+    pub fn new(app: &AppHandle) -> Result<Self, BackupError> {
+        let data_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&data_dir)?;
+        let state_file = data_dir.join(STATE_FILENAME);
+        let state = load_json(&state_file, BackupState::default);
+        Ok(Self {
+            data_dir,
+            state_file,
+            state: Arc::new(async_runtime::Mutex::new(state)),
+        })
+    }
+
+    /// 启动后台任务，按设置的间隔执行增量备份。
+    pub fn spawn_schedule_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            loop {
+                app.state::<BackupManager>().run_if_due().await;
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    /// 到了备份时间就执行一次，定时任务和休眠唤醒后都会调用。
+    pub async fn run_if_due(&self) {
+        if self.is_due().await {
+            if let Err(err) = self.run_backup().await {
+                log::warn!("scheduled backup failed: {err}");
+            }
+        }
+    }
+
+    async fn is_due(&self) -> bool {
+        let guard = self.state.lock().await;
+        if is_read_only() || !guard.settings.enabled || guard.settings.target_dir.is_none() {
+            return false;
+        }
+        let interval = Duration::hours(i64::from(guard.settings.interval_hours.max(1)));
+        guard
+            .last_backup_at
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .is_none_or(|last| Local::now().signed_duration_since(last) >= interval)
+    }
+
+    pub async fn status(&self) -> BackupStatus {
+        let guard = self.state.lock().await;
+        BackupStatus {
+            settings: guard.settings.clone(),
+            last_backup_at: guard.last_backup_at.clone(),
+            last_error: guard.last_error.clone(),
+        }
+    }
+
+    pub async fn set_settings(
+        &self,
+        settings: BackupSettings,
+    ) -> Result<BackupStatus, BackupError> {
+        if let Some(target) = settings.target_dir.as_deref() {
+            self.validate_target(Path::new(target))?;
+        }
+        {
+            let mut guard = self.state.lock().await;
+            if guard.settings.target_dir != settings.target_dir {
+                guard.fingerprints.clear();
+            }
+            guard.settings = settings;
+            self.persist(&guard)?;
+        }
+        Ok(self.status().await)
+    }
+
+    pub async fn run_backup(&self) -> Result<BackupSummary, BackupError> {
+        let mut guard = self.state.lock().await;
+        let target = PathBuf::from(
+            guard
+                .settings
+                .target_dir
+                .clone()
+                .ok_or(BackupError::NotConfigured)?,
+        );
+        self.validate_target(&target)?;
+
+        let data_dir = self.data_dir.clone();
+        let fingerprints = std::mem::take(&mut guard.fingerprints);
+        let result =
+            async_runtime::spawn_blocking(move || write_backup(&data_dir, &target, fingerprints))
+                .await
+                .map_err(|e| BackupError::Join(e.to_string()))?;
+
+        match result {
+            Ok((summary, fingerprints)) => {
+                guard.fingerprints = fingerprints;
+                guard.last_backup_at = Some(summary.created_at.clone());
+                guard.last_error = None;
+                self.persist(&guard)?;
+                Ok(summary)
+            }
+            Err(err) => {
+                guard.last_error = Some(err.to_string());
+                self.persist(&guard)?;
+                Err(err)
+            }
+        }
+    }
+
+    pub async fn list_backups(&self) -> Result<Vec<BackupSummary>, BackupError> {
+        let target = self.target_dir().await?;
+        let snapshots_dir = target.join(SNAPSHOTS_DIR);
+        if !snapshots_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut summaries = Vec::new();
+        for entry in fs::read_dir(&snapshots_dir)?.flatten() {
+            let Ok(content) = fs::read(entry.path()) else {
+                continue;
+            };
+            if let Ok(manifest) = serde_json::from_slice::<BackupManifest>(&content) {
+                summaries.push(manifest.summary);
+            }
+        }
+        summaries.sort_by(|a, b| cmp_instants(&b.created_at, &a.created_at));
+        Ok(summaries)
+    }
+
+    /// 按清单还原完整数据；清单之外的数据文件会被删除，调用方需随后重启应用。
+    pub async fn restore(&self, snapshot_id: &str) -> Result<(), BackupError> {
+        let target = self.target_dir().await?;
+        let mut guard = self.state.lock().await;
+        let data_dir = self.data_dir.clone();
+        let snapshot_id = snapshot_id.to_string();
+        async_runtime::spawn_blocking(move || restore_backup(&data_dir, &target, &snapshot_id))
+            .await
+            .map_err(|e| BackupError::Join(e.to_string()))??;
+        guard.fingerprints.clear();
+        self.persist(&guard)?;
+        Ok(())
+    }
+
+    async fn target_dir(&self) -> Result<PathBuf, BackupError> {
+        let guard = self.state.lock().await;
+        guard
+            .settings
+            .target_dir
+            .as_ref()
+            .map(PathBuf::from)
+            .ok_or(BackupError::NotConfigured)
+    }
+
+    fn validate_target(&self, target: &Path) -> Result<(), BackupError> {
+        if target.starts_with(&self.data_dir) {
+            return Err(BackupError::InvalidTarget);
+        }
+        Ok(())
+    }
+
+    fn persist(&self, state: &BackupState) -> Result<(), BackupError> {
+        write_atomic(&self.state_file, &serde_json::to_vec_pretty(state)?)?;
+        Ok(())
+    }
+}
+
+/// 模型文件可重新下载、搜索索引可重建，备份时跳过。
+fn is_backed_up(relative: &str) -> bool {
+    relative != STATE_FILENAME
+        && relative != LOCK_FILENAME
+        && relative != EXCLUSIVE_LOCK_FILENAME
+        && relative != SEARCH_INDEX_FILENAME
+        && !relative.starts_with(MODELS_DIR)
+        && !relative.ends_with(".tmp")
+        && !relative.ends_with(".part")
+}
+
+fn collect_files(data_dir: &Path) -> Result<Vec<(String, PathBuf)>, BackupError> {
+    let mut files = Vec::new();
+    let mut pending = vec![data_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(data_dir) else {
+                continue;
+            };
+            let relative = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if is_backed_up(&relative) {
+                files.push((relative, path));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+fn object_path(target: &Path, hash: &str) -> PathBuf {
+    target.join(OBJECTS_DIR).join(&hash[..2]).join(hash)
+}
+
+/// 边复制边计算哈希，保证对象内容与哈希一致；对象已存在时丢弃临时文件。
+fn store_object(target: &Path, source: &Path) -> Result<(String, bool), BackupError> {
+    let objects_dir = target.join(OBJECTS_DIR);
+    fs::create_dir_all(&objects_dir)?;
+    let temp_path = objects_dir.join(format!("{}.tmp", uuid::Uuid::new_v4()));
+
+    let mut hasher = Sha256::new();
+    {
+        let mut reader = File::open(source)?;
+        let mut writer = File::create(&temp_path)?;
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            writer.write_all(&buffer[..read])?;
+        }
+        writer.flush()?;
+    }
+    let hash = format!("{:x}", hasher.finalize());
+
+    let destination = object_path(target, &hash);
+    if destination.exists() {
+        fs::remove_file(&temp_path)?;
+        return Ok((hash, false));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&temp_path, &destination)?;
+    Ok((hash, true))
+}
+
+fn write_backup(
+    data_dir: &Path,
+    target: &Path,
+    previous: HashMap<String, FileFingerprint>,
+) -> Result<(BackupSummary, HashMap<String, FileFingerprint>), BackupError> {
+    let mut fingerprints = HashMap::new();
+    let mut files = Vec::new();
+    let mut total_bytes = 0;
+    let mut new_bytes = 0;
+
+    for (relative, path) in collect_files(data_dir)? {
+        let metadata = fs::metadata(&path)?;
+        let size = metadata.len();
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+
+        let unchanged = previous.get(&relative).filter(|fingerprint| {
+            fingerprint.size == size
+                && fingerprint.modified_ms == modified_ms
+                && object_path(target, &fingerprint.hash).exists()
+        });
+        let hash = match unchanged {
+            Some(fingerprint) => fingerprint.hash.clone(),
+            None => {
+                let (hash, stored) = store_object(target, &path)?;
+                if stored {
+                    new_bytes += size;
+                }
+                hash
+            }
+        };
+
+        total_bytes += size;
+        fingerprints.insert(
+            relative.clone(),
+            FileFingerprint {
+                size,
+                modified_ms,
+                hash: hash.clone(),
+            },
+        );
+        files.push(BackupFile {
+            path: relative,
+            hash,
+            size,
+        });
+    }
+
+    let now = Local::now();
+    let summary = BackupSummary {
+        id: now.format("%Y%m%dT%H%M%S").to_string(),
+        created_at: to_utc_string(&now),
+        file_count: files.len(),
+        total_bytes,
+        new_bytes,
+    };
+    let snapshots_dir = target.join(SNAPSHOTS_DIR);
+    fs::create_dir_all(&snapshots_dir)?;
+    let manifest = BackupManifest {
+        summary: summary.clone(),
+        files,
+    };
+    // 清单写到一半时 list_backups 会跳过它，这次备份就丢了
+    write_atomic(
+        &snapshots_dir.join(format!("{}.json", summary.id)),
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    Ok((summary, fingerprints))
+}
+
+fn restore_backup(data_dir: &Path, target: &Path, snapshot_id: &str) -> Result<(), BackupError> {
+    if snapshot_id.contains(['/', '\\']) || snapshot_id.contains("..") {
+        return Err(BackupError::SnapshotNotFound(snapshot_id.to_string()));
+    }
+    let manifest_path = target
+        .join(SNAPSHOTS_DIR)
+        .join(format!("{snapshot_id}.json"));
+    if !manifest_path.exists() {
+        return Err(BackupError::SnapshotNotFound(snapshot_id.to_string()));
+    }
+    let manifest: BackupManifest = serde_json::from_slice(&fs::read(&manifest_path)?)?;
+
+    for file in &manifest.files {
+        if file.path.split('/').any(|part| part == "..")
+            || !object_path(target, &file.hash).exists()
+        {
+            return Err(BackupError::MissingObject(file.path.clone()));
+        }
+    }
+
+    let restored: HashSet<&str> = manifest
+        .files
+        .iter()
+        .map(|file| file.path.as_str())
+        .collect();
+    for (relative, path) in collect_files(data_dir)? {
+        if !restored.contains(relative.as_str()) {
+            fs::remove_file(path)?;
+        }
+    }
+
+    for file in &manifest.files {
+        let destination = data_dir.join(&file.path);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(object_path(target, &file.hash), &destination)?;
+    }
+
+    let search_index = data_dir.join(SEARCH_INDEX_FILENAME);
+    if search_index.exists() {
+        fs::remove_file(search_index)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_backup_status(
+    backup: tauri::State<'_, BackupManager>,
+) -> Result<BackupStatus, String> {
+    Ok(backup.status().await)
+}
+
+#[tauri::command]
+pub async fn set_backup_settings(
+    backup: tauri::State<'_, BackupManager>,
+    settings: BackupSettings,
+) -> Result<BackupStatus, String> {
+    backup
+        .set_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_backup_now(
+    backup: tauri::State<'_, BackupManager>,
+) -> Result<BackupSummary, String> {
+    backup.run_backup().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    backup: tauri::State<'_, BackupManager>,
+) -> Result<Vec<BackupSummary>, String> {
+    backup.list_backups().await.map_err(|e| e.to_string())
+}
+
+/// 还原完成后重启应用，让各模块重新加载数据。
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    backup: tauri::State<'_, BackupManager>,
+    snapshot_id: String,
+) -> Result<(), String> {
+    backup
+        .restore(&snapshot_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    app.restart();
+}
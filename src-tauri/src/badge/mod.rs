@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use tauri::{async_runtime, AppHandle, Manager};
+
+use crate::speech::SpeechManager;
+use crate::todos::TodoStore;
+
+/// 待办会随时间到期，没有变化时也定期重新计算。
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 把应用图标角标（macOS 程序坞、Linux Unity 启动器）设为到期待办数加待处理的转写数，
+/// 为零时清除角标。其他平台不支持角标时只记录日志。
+pub async fn refresh(app: &AppHandle) {
+    let (Some(todos), Some(speech)) = (
+        app.try_state::<TodoStore>(),
+        app.try_state::<SpeechManager>(),
+    ) else {
+        return;
+    };
+    let count = todos.overdue_count().await + speech.pending_job_count().await;
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let badge = (count > 0).then_some(count as i64);
+    if let Err(err) = window.set_badge_count(badge) {
+        log::debug!("failed to set badge count: {err}");
+    }
+}
+
+pub fn spawn_refresh_loop(app: AppHandle) {
+    async_runtime::spawn(async move {
+        loop {
+            refresh(&app).await;
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,297 @@
+use std::{fs, io, path::PathBuf};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+
+use crate::calendar::CalendarManager;
+use crate::datetime::now_utc;
+use crate::habits::{Habit, HabitStore};
+use crate::links::{EntityLink, LinkStore};
+use crate::notes::{Note, NoteStore};
+use crate::speech::{
+    ImportSessionsReport, RetentionPolicy, SmartCollection, SpeechError, SpeechManager,
+    SpeechSessionBackup,
+};
+use crate::storage::write_atomic;
+use crate::todos::{TodoChangeKind, TodoStore, TodoTask};
+
+const BUNDLE_MAGIC: &[u8; 8] = b"KKBUNDL1";
+const BUNDLE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MIN_PASSPHRASE_LEN: usize = 6;
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Speech(#[from] SpeechError),
+    #[error("encryption error: {0}")]
+    Crypto(String),
+    #[error("密码至少需要 {MIN_PASSPHRASE_LEN} 个字符")]
+    WeakPassphrase,
+    #[error("不是有效的数据包文件")]
+    InvalidBundle,
+    #[error("密码错误或数据包已损坏")]
+    DecryptionFailed,
+    #[error("数据包版本 {0} 过新，请先升级应用")]
+    UnsupportedVersion(u32),
+}
+
+/// 前端持有的数据（界面设置等）以原样 JSON 存入数据包，导入时再交还给前端。
+#[derive(Debug, Serialize, Deserialize)]
+struct AppBundle {
+    version: u32,
+    created_at: String,
+    sessions: Vec<SpeechSessionBackup>,
+    #[serde(default)]
+    collections: Vec<SmartCollection>,
+    #[serde(default)]
+    retention_policies: Vec<RetentionPolicy>,
+    #[serde(default)]
+    calendar_subscriptions: Vec<String>,
+    #[serde(default)]
+    links: Vec<EntityLink>,
+    #[serde(default)]
+    todos: Vec<TodoTask>,
+    #[serde(default)]
+    notes: Vec<Note>,
+    #[serde(default)]
+    habits: Vec<Habit>,
+    #[serde(default)]
+    client_data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportAppBundleReport {
+    pub path: String,
+    pub session_count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportAppBundleReport {
+    pub created_at: String,
+    pub sessions: ImportSessionsReport,
+    pub collections_added: usize,
+    pub retention_policies_added: usize,
+    pub links_added: usize,
+    pub todos_added: usize,
+    pub notes_added: usize,
+    pub habits_added: usize,
+    pub calendar_subscriptions: usize,
+    pub client_data: serde_json::Value,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], BundleError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BundleError::Crypto(e.to_string()))?;
+    Ok(key)
+}
+
+/// 文件格式：魔数 + salt + nonce + AES-256-GCM 密文，密钥由 Argon2 从密码派生。
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, BundleError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| BundleError::Crypto(e.to_string()))?;
+
+    let mut output =
+        Vec::with_capacity(BUNDLE_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(BUNDLE_MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, BundleError> {
+    let header_len = BUNDLE_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header_len || !data.starts_with(BUNDLE_MAGIC) {
+        return Err(BundleError::InvalidBundle);
+    }
+    let (salt, rest) = data[BUNDLE_MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| BundleError::DecryptionFailed)
+}
+
+async fn export_bundle(
+    app: &AppHandle,
+    path: PathBuf,
+    passphrase: &str,
+    client_data: serde_json::Value,
+) -> Result<ExportAppBundleReport, BundleError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(BundleError::WeakPassphrase);
+    }
+
+    let speech = app.state::<SpeechManager>();
+    let bundle = AppBundle {
+        version: BUNDLE_VERSION,
+        created_at: now_utc(),
+        sessions: speech.export_sessions_data().await?,
+        collections: speech.smart_collections().await,
+        retention_policies: speech.list_retention_policies().await,
+        calendar_subscriptions: app.state::<CalendarManager>().status().await.subscriptions,
+        links: app.state::<LinkStore>().all().await,
+        todos: app.state::<TodoStore>().all().await,
+        notes: app.state::<NoteStore>().all().await,
+        habits: app.state::<HabitStore>().all().await,
+        client_data,
+    };
+    let session_count = bundle.sessions.len();
+    let encrypted = encrypt(&serde_json::to_vec(&bundle)?, passphrase)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // 导出中断时不留下无法解密的半个文件
+    write_atomic(&path, &encrypted)?;
+
+    Ok(ExportAppBundleReport {
+        path: path.to_string_lossy().into_owned(),
+        session_count,
+        size_bytes: encrypted.len() as u64,
+    })
+}
+
+async fn import_bundle(
+    app: &AppHandle,
+    path: PathBuf,
+    passphrase: &str,
+) -> Result<ImportAppBundleReport, BundleError> {
+    let plaintext = decrypt(&fs::read(&path)?, passphrase)?;
+    let bundle: AppBundle = serde_json::from_slice(&plaintext)?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(bundle.version));
+    }
+
+    let speech = app.state::<SpeechManager>();
+    let calendar = app.state::<CalendarManager>();
+    let sessions = speech.import_sessions_data(app, bundle.sessions).await?;
+    let collections_added = speech.merge_smart_collections(bundle.collections).await?;
+    let retention_policies_added = speech
+        .merge_retention_policies(bundle.retention_policies)
+        .await?;
+    let links_added = match app.state::<LinkStore>().merge(bundle.links).await {
+        Ok(added) => added,
+        Err(err) => {
+            log::warn!("failed to import links from bundle: {err}");
+            0
+        }
+    };
+
+    let todos = app.state::<TodoStore>();
+    let todos_added = match todos.merge(bundle.todos).await {
+        Ok(added) if added.is_empty() => 0,
+        Ok(added) => {
+            let count = added.len();
+            todos
+                .notify_changed(app, TodoChangeKind::Saved, added)
+                .await;
+            count
+        }
+        Err(err) => {
+            log::warn!("failed to import todos from bundle: {err}");
+            0
+        }
+    };
+    let notes = app.state::<NoteStore>();
+    let notes_added = match notes.merge(bundle.notes).await {
+        Ok(added) => {
+            if added > 0 {
+                notes.notify_changed(app).await;
+            }
+            added
+        }
+        Err(err) => {
+            log::warn!("failed to import notes from bundle: {err}");
+            0
+        }
+    };
+    let habits = app.state::<HabitStore>();
+    let habits_added = match habits.merge(bundle.habits).await {
+        Ok(added) => {
+            if added > 0 {
+                habits.notify_changed(app).await;
+            }
+            added
+        }
+        Err(err) => {
+            log::warn!("failed to import habits from bundle: {err}");
+            0
+        }
+    };
+
+    let mut subscriptions = calendar.status().await.subscriptions;
+    let before = subscriptions.len();
+    for url in bundle.calendar_subscriptions {
+        if !subscriptions.contains(&url) {
+            subscriptions.push(url);
+        }
+    }
+    if subscriptions.len() != before {
+        if let Err(err) = calendar.set_subscriptions(subscriptions.clone()).await {
+            log::warn!("failed to import calendar subscriptions from bundle: {err}");
+        }
+    }
+
+    Ok(ImportAppBundleReport {
+        created_at: bundle.created_at,
+        sessions,
+        collections_added,
+        retention_policies_added,
+        links_added,
+        todos_added,
+        notes_added,
+        habits_added,
+        calendar_subscriptions: subscriptions.len(),
+        client_data: bundle.client_data,
+    })
+}
+
+#[tauri::command]
+pub async fn export_app_bundle(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    client_data: Option<serde_json::Value>,
+) -> Result<ExportAppBundleReport, String> {
+    export_bundle(
+        &app,
+        PathBuf::from(path),
+        &passphrase,
+        client_data.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_app_bundle(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> Result<ImportAppBundleReport, String> {
+    import_bundle(&app, PathBuf::from(path), &passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
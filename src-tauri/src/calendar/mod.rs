@@ -0,0 +1,375 @@
+use std::{fs, io, path::PathBuf, sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use thiserror::Error;
+
+use crate::datetime::{cmp_instants, now_utc};
+use crate::network::is_offline;
+use crate::reminders::{ReminderAction, ReminderEngine, ReminderSource, ReminderSpec};
+use crate::storage::{load_json, prepare_dir};
+
+const REFRESH_INTERVAL: StdDuration = StdDuration::from_secs(30 * 60);
+const REMINDER_LEAD: Duration = Duration::minutes(10);
+const REMINDER_HORIZON: Duration = Duration::days(14);
+
+#[derive(Debug, Error)]
+pub enum CalendarError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("日历订阅地址无效：{0}")]
+    InvalidUrl(String),
+    #[error("已开启离线模式，无法刷新日历订阅")]
+    Offline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+impl CalendarEvent {
+    fn contains(&self, instant: DateTime<Utc>) -> bool {
+        let (Ok(start), Ok(end)) = (
+            DateTime::parse_from_rfc3339(&self.start),
+            DateTime::parse_from_rfc3339(&self.end),
+        ) else {
+            return false;
+        };
+        instant >= start && instant < end
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CalendarCache {
+    subscriptions: Vec<String>,
+    events: Vec<CalendarEvent>,
+    last_refreshed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarSubscriptionStatus {
+    pub subscriptions: Vec<String>,
+    pub event_count: usize,
+    pub last_refreshed: Option<String>,
+}
+
+pub struct CalendarManager {
+    cache_file: PathBuf,
+    cache: Arc<async_runtime::Mutex<CalendarCache>>,
+    http: Client,
+}
+
+impl CalendarManager {
+    pub fn new(app: &AppHandle) -> Result<Self, CalendarError> {
+        let base_dir = app.path().app_local_data_dir()?.join("calendar");
+        prepare_dir(&base_dir)?;
+        let cache_file = base_dir.join("subscriptions.json");
+        let cache = load_json(&cache_file, CalendarCache::default);
+
+        Ok(Self {
+            cache_file,
+            cache: Arc::new(async_runtime::Mutex::new(cache)),
+            http: Client::new(),
+        })
+    }
+
+    /// 启动后台任务，定期刷新所有订阅；离线模式下跳过，沿用缓存的事件。
+    pub fn spawn_refresh_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            loop {
+                app.state::<CalendarManager>().refresh_and_sync(&app).await;
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+            }
+        });
+    }
+
+    /// 刷新订阅并更新日程提醒；离线模式下只用缓存的事件更新提醒。
+    pub(crate) async fn refresh_and_sync(&self, app: &AppHandle) {
+        if !is_offline() {
+            if let Err(err) = self.refresh().await {
+                log::warn!("failed to refresh calendar subscriptions: {err}");
+            }
+        }
+        self.sync_reminders(app).await;
+    }
+
+    pub async fn status(&self) -> CalendarSubscriptionStatus {
+        let guard = self.cache.lock().await;
+        CalendarSubscriptionStatus {
+            subscriptions: guard.subscriptions.clone(),
+            event_count: guard.events.len(),
+            last_refreshed: guard.last_refreshed.clone(),
+        }
+    }
+
+    pub async fn set_subscriptions(
+        &self,
+        urls: Vec<String>,
+    ) -> Result<CalendarSubscriptionStatus, CalendarError> {
+        let mut subscriptions = Vec::with_capacity(urls.len());
+        for url in urls {
+            let url = url.trim().replacen("webcal://", "https://", 1);
+            if url.is_empty() {
+                continue;
+            }
+            if reqwest::Url::parse(&url).is_err() {
+                return Err(CalendarError::InvalidUrl(url));
+            }
+            if !subscriptions.contains(&url) {
+                subscriptions.push(url);
+            }
+        }
+
+        {
+            let mut guard = self.cache.lock().await;
+            guard.subscriptions = subscriptions;
+            guard.events.clear();
+            guard.last_refreshed = None;
+            self.persist(&guard)?;
+        }
+        if !is_offline() {
+            self.refresh().await?;
+        }
+        Ok(self.status().await)
+    }
+
+    pub async fn refresh(&self) -> Result<CalendarSubscriptionStatus, CalendarError> {
+        let subscriptions = self.cache.lock().await.subscriptions.clone();
+        if subscriptions.is_empty() {
+            return Ok(self.status().await);
+        }
+        if is_offline() {
+            return Err(CalendarError::Offline);
+        }
+
+        let mut events = Vec::new();
+        for url in &subscriptions {
+            let response = self.http.get(url).send().await?.error_for_status()?;
+            let body = response.text().await?;
+            events.extend(parse_ics_events(&body));
+        }
+        events.sort_by(|a, b| cmp_instants(&a.start, &b.start));
+
+        {
+            let mut guard = self.cache.lock().await;
+            guard.events = events;
+            guard.last_refreshed = Some(now_utc());
+            self.persist(&guard)?;
+        }
+        Ok(self.status().await)
+    }
+
+    pub async fn event_at(&self, instant: DateTime<Utc>) -> Option<CalendarEvent> {
+        let guard = self.cache.lock().await;
+        guard
+            .events
+            .iter()
+            .find(|event| event.contains(instant))
+            .cloned()
+    }
+
+    pub async fn events_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<CalendarEvent> {
+        let guard = self.cache.lock().await;
+        guard
+            .events
+            .iter()
+            .filter(|event| {
+                let start = DateTime::parse_from_rfc3339(&event.start);
+                let end = DateTime::parse_from_rfc3339(&event.end);
+                matches!((start, end), (Ok(start), Ok(end)) if start < to && end > from)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// 未来两周内的日程在开始前提醒；日程结束后仍未提醒（如一直休眠）就不再提醒。
+    pub(crate) async fn sync_reminders(&self, app: &AppHandle) {
+        let Some(engine) = app.try_state::<ReminderEngine>() else {
+            return;
+        };
+        let now = Utc::now();
+        let specs = self
+            .events_between(now, now + REMINDER_HORIZON)
+            .await
+            .into_iter()
+            .filter_map(|event| {
+                let start = DateTime::parse_from_rfc3339(&event.start).ok()?;
+                let end = DateTime::parse_from_rfc3339(&event.end).ok()?;
+                let body = match &event.location {
+                    Some(location) => format!("{}（{location}）", event.summary),
+                    None => event.summary.clone(),
+                };
+                Some(ReminderSpec {
+                    entity_id: format!("{}@{}", event.uid, event.start),
+                    title: "日程即将开始".into(),
+                    body,
+                    fire_at: start.with_timezone(&Utc) - REMINDER_LEAD,
+                    repeat: None,
+                    expires_at: Some(end.with_timezone(&Utc)),
+                    actions: vec![ReminderAction::Snooze],
+                })
+            })
+            .collect();
+        if let Err(err) = engine.sync_source(ReminderSource::Calendar, specs).await {
+            log::warn!("failed to sync calendar reminders: {err}");
+        }
+    }
+
+    fn persist(&self, cache: &CalendarCache) -> Result<(), CalendarError> {
+        fs::write(&self.cache_file, serde_json::to_vec_pretty(cache)?)?;
+        Ok(())
+    }
+}
+
+fn unescape_ics_text(value: &str) -> String {
+    value
+        .replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// 解析 DTSTART/DTEND 的值；带 TZID 的时间按本地时间处理。
+fn parse_ics_datetime(params: &str, value: &str) -> Option<DateTime<Utc>> {
+    if params.contains("VALUE=DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|value| value.with_timezone(&Utc));
+    }
+    if let Some(value) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn parse_attendee(params: &str, value: &str) -> String {
+    params
+        .split(';')
+        .find_map(|param| param.strip_prefix("CN="))
+        .map(|name| name.trim_matches('"').to_string())
+        .unwrap_or_else(|| {
+            value
+                .trim_start_matches("mailto:")
+                .trim_start_matches("MAILTO:")
+                .to_string()
+        })
+}
+
+/// 只解析单次事件（不展开 RRULE），足以覆盖会议标题匹配的场景。
+fn parse_ics_events(content: &str) -> Vec<CalendarEvent> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        if let Some(continuation) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(raw.trim_end_matches('\r').to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<(CalendarEvent, Option<DateTime<Utc>>, Option<DateTime<Utc>>)> = None;
+    for line in lines {
+        if line == "BEGIN:VEVENT" {
+            current = Some((
+                CalendarEvent {
+                    uid: String::new(),
+                    summary: String::new(),
+                    start: String::new(),
+                    end: String::new(),
+                    attendees: Vec::new(),
+                    location: None,
+                },
+                None,
+                None,
+            ));
+            continue;
+        }
+        if line == "END:VEVENT" {
+            if let Some((mut event, Some(start), end)) = current.take() {
+                let end = end.unwrap_or(start + chrono::Duration::hours(1));
+                event.start = start.to_rfc3339();
+                event.end = end.to_rfc3339();
+                events.push(event);
+            }
+            continue;
+        }
+        let Some((event, start, end)) = current.as_mut() else {
+            continue;
+        };
+        let Some((name_part, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name_part.split_once(';').unwrap_or((name_part, ""));
+        match name {
+            "UID" => event.uid = value.to_string(),
+            "SUMMARY" => event.summary = unescape_ics_text(value),
+            "LOCATION" => event.location = Some(unescape_ics_text(value)),
+            "DTSTART" => *start = parse_ics_datetime(params, value),
+            "DTEND" => *end = parse_ics_datetime(params, value),
+            "ATTENDEE" => event.attendees.push(parse_attendee(params, value)),
+            _ => {}
+        }
+    }
+    events
+}
+
+#[tauri::command]
+pub async fn set_calendar_subscriptions(
+    app: AppHandle,
+    calendar: tauri::State<'_, CalendarManager>,
+    urls: Vec<String>,
+) -> Result<CalendarSubscriptionStatus, String> {
+    let status = calendar
+        .set_subscriptions(urls)
+        .await
+        .map_err(|e| e.to_string())?;
+    calendar.sync_reminders(&app).await;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn refresh_calendar_subscriptions(
+    app: AppHandle,
+    calendar: tauri::State<'_, CalendarManager>,
+) -> Result<CalendarSubscriptionStatus, String> {
+    let status = calendar.refresh().await.map_err(|e| e.to_string())?;
+    calendar.sync_reminders(&app).await;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_calendar_status(
+    calendar: tauri::State<'_, CalendarManager>,
+) -> Result<CalendarSubscriptionStatus, String> {
+    Ok(calendar.status().await)
+}
@@ -0,0 +1,287 @@
+use std::{io, path::PathBuf, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::datetime::{now_utc, parse_instant, to_utc_string};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::widgets::{self, COUNTDOWN_WIDGET_LABEL};
+use crate::TRAY_ID;
+
+const COUNTDOWNS_FILENAME: &str = "countdowns.json";
+const COUNTDOWN_CHANGED_EVENT: &str = "countdown://changed";
+const COUNTDOWN_EXPIRED_EVENT: &str = "countdown://expired";
+const TICK_INTERVAL: StdDuration = StdDuration::from_secs(1);
+const DEFAULT_TOOLTIP: &str = "Kk";
+
+#[derive(Debug, Error)]
+pub enum CountdownError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未找到指定的倒计时：{0}")]
+    NotFound(String),
+    #[error("倒计时无效：{0}")]
+    Invalid(String),
+}
+
+/// 倒计时到 `target_at`（UTC）；到期后保留在列表中直到用户删除。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Countdown {
+    pub id: String,
+    pub name: String,
+    pub target_at: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub expired: bool,
+}
+
+impl Countdown {
+    fn target(&self) -> Option<DateTime<Utc>> {
+        parse_instant(&self.target_at)
+    }
+}
+
+/// 剩余时间，超过一小时只显示到分钟。
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    match (days, hours) {
+        (0, 0) => format!("{minutes:02}:{:02}", seconds % 60),
+        (0, _) => format!("{hours} 小时 {minutes} 分"),
+        _ => format!("{days} 天 {hours} 小时"),
+    }
+}
+
+pub struct CountdownStore {
+    countdowns_file: PathBuf,
+    countdowns: async_runtime::Mutex<Vec<Countdown>>,
+}
+
+impl CountdownStore {
+    pub fn new(app: &AppHandle) -> Result<Self, CountdownError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let countdowns_file = base_dir.join(COUNTDOWNS_FILENAME);
+        let countdowns = load_json(&countdowns_file, Vec::new);
+        Ok(Self {
+            countdowns_file,
+            countdowns: async_runtime::Mutex::new(countdowns),
+        })
+    }
+
+    pub async fn all(&self) -> Vec<Countdown> {
+        let guard = self.countdowns.lock().await;
+        guard.clone()
+    }
+
+    /// `target_at` 与 `duration_seconds` 二选一：前者用于纪念日、发布日等固定时刻，后者用于计时器。
+    pub async fn create(
+        &self,
+        name: String,
+        target_at: Option<String>,
+        duration_seconds: Option<u64>,
+    ) -> Result<Countdown, CountdownError> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(CountdownError::Invalid("名称不能为空".into()));
+        }
+        let now = Utc::now();
+        let target = match (target_at.as_deref(), duration_seconds) {
+            (Some(value), None) => parse_instant(value)
+                .ok_or_else(|| CountdownError::Invalid(format!("无法解析时间：{value}")))?,
+            (None, Some(seconds)) => i64::try_from(seconds)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .map(|duration| now + duration)
+                .ok_or_else(|| CountdownError::Invalid("时长过长".into()))?,
+            _ => {
+                return Err(CountdownError::Invalid(
+                    "需要指定结束时间或时长中的一个".into(),
+                ))
+            }
+        };
+        if target <= now {
+            return Err(CountdownError::Invalid("结束时间必须晚于现在".into()));
+        }
+        let countdown = Countdown {
+            id: Uuid::new_v4().to_string(),
+            name,
+            target_at: to_utc_string(&target),
+            created_at: now_utc(),
+            expired: false,
+        };
+        let mut guard = self.countdowns.lock().await;
+        guard.push(countdown.clone());
+        self.persist(&guard)?;
+        Ok(countdown)
+    }
+
+    pub async fn delete(&self, countdown_id: &str) -> Result<(), CountdownError> {
+        let mut guard = self.countdowns.lock().await;
+        let before = guard.len();
+        guard.retain(|countdown| countdown.id != countdown_id);
+        if guard.len() == before {
+            return Err(CountdownError::NotFound(countdown_id.to_string()));
+        }
+        self.persist(&guard)
+    }
+
+    fn persist(&self, countdowns: &[Countdown]) -> Result<(), CountdownError> {
+        write_atomic(
+            &self.countdowns_file,
+            &serde_json::to_vec_pretty(countdowns)?,
+        )?;
+        Ok(())
+    }
+
+    /// 标记刚到期的倒计时并返回它们，同时给出托盘提示文字（最近一个未到期的倒计时）。
+    async fn tick(&self, now: DateTime<Utc>) -> Result<(Vec<Countdown>, String), CountdownError> {
+        let mut guard = self.countdowns.lock().await;
+        let mut expired = Vec::new();
+        for countdown in guard.iter_mut().filter(|countdown| !countdown.expired) {
+            if countdown.target().map_or(true, |target| target <= now) {
+                countdown.expired = true;
+                expired.push(countdown.clone());
+            }
+        }
+        if !expired.is_empty() {
+            self.persist(&guard)?;
+        }
+        let tooltip = guard
+            .iter()
+            .filter(|countdown| !countdown.expired)
+            .filter_map(|countdown| Some((countdown.target()?, countdown)))
+            .min_by_key(|(target, _)| *target)
+            .map_or_else(
+                || DEFAULT_TOOLTIP.to_string(),
+                |(target, countdown)| {
+                    format!(
+                        "{DEFAULT_TOOLTIP} · {} 还剩 {}",
+                        countdown.name,
+                        format_remaining(target - now)
+                    )
+                },
+            );
+        Ok((expired, tooltip))
+    }
+
+    /// 启动后台任务：每秒检查到期的倒计时并弹出通知，托盘提示显示最近的一个倒计时。
+    pub fn spawn_tick_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            let mut last_tooltip = DEFAULT_TOOLTIP.to_string();
+            loop {
+                let store = app.state::<CountdownStore>();
+                match store.tick(Utc::now()).await {
+                    Ok((expired, tooltip)) => {
+                        for countdown in &expired {
+                            notify_expired(&app, countdown);
+                        }
+                        if !expired.is_empty() {
+                            store.notify_changed(&app).await;
+                        }
+                        // 托盘在 setup 末尾才创建，创建前不记录，之后再补上
+                        if tooltip != last_tooltip {
+                            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                                let _ = tray.set_tooltip(Some(&tooltip));
+                                last_tooltip = tooltip;
+                            }
+                        }
+                    }
+                    Err(err) => log::warn!("failed to update countdowns: {err}"),
+                }
+                tokio::time::sleep(TICK_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn notify_changed(&self, app: &AppHandle) {
+        let _ = app.emit(COUNTDOWN_CHANGED_EVENT, self.all().await);
+    }
+}
+
+fn notify_expired(app: &AppHandle, countdown: &Countdown) {
+    if let Err(err) = app
+        .notification()
+        .builder()
+        .title("倒计时结束")
+        .body(&countdown.name)
+        .show()
+    {
+        log::warn!(
+            "failed to show countdown notification {}: {err}",
+            countdown.id
+        );
+    }
+    let _ = app.emit(COUNTDOWN_EXPIRED_EVENT, countdown);
+}
+
+#[tauri::command]
+pub async fn list_countdowns(
+    countdowns: tauri::State<'_, CountdownStore>,
+) -> Result<Vec<Countdown>, String> {
+    Ok(countdowns.all().await)
+}
+
+#[tauri::command]
+pub async fn create_countdown(
+    app: AppHandle,
+    countdowns: tauri::State<'_, CountdownStore>,
+    name: String,
+    target_at: Option<String>,
+    duration_seconds: Option<u64>,
+) -> Result<Countdown, String> {
+    let countdown = countdowns
+        .create(name, target_at, duration_seconds)
+        .await
+        .map_err(|e| e.to_string())?;
+    countdowns.notify_changed(&app).await;
+    Ok(countdown)
+}
+
+#[tauri::command]
+pub async fn delete_countdown(
+    app: AppHandle,
+    countdowns: tauri::State<'_, CountdownStore>,
+    id: String,
+) -> Result<(), String> {
+    countdowns.delete(&id).await.map_err(|e| e.to_string())?;
+    countdowns.notify_changed(&app).await;
+    Ok(())
+}
+
+/// 打开置顶的倒计时小组件，已打开时只聚焦。
+#[tauri::command]
+pub fn open_countdown_widget(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(COUNTDOWN_WIDGET_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let window = tauri::WebviewWindowBuilder::new(
+        &app,
+        COUNTDOWN_WIDGET_LABEL,
+        tauri::WebviewUrl::App("/countdown/widget".into()),
+    )
+    .title("Countdown Widget")
+    .inner_size(320.0, 220.0)
+    .min_inner_size(260.0, 160.0)
+    .resizable(true)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+    widgets::restore_dock(&window.as_ref().window());
+
+    window.show().map_err(|e| e.to_string())?;
+    Ok(())
+}
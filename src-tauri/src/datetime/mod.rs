@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Local, Locale, SecondsFormat, TimeZone, Utc};
+use serde::Deserialize;
+
+const FALLBACK_LOCALE: Locale = Locale::zh_CN;
+
+/// 持久化时间戳统一使用 UTC 的 RFC 3339 格式，避免时区和夏令时变化影响比较。
+pub fn now_utc() -> String {
+    to_utc_string(&Utc::now())
+}
+
+pub fn to_utc_string<Tz: TimeZone>(instant: &DateTime<Tz>) -> String {
+    instant
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub fn parse_instant(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|instant| instant.with_timezone(&Utc))
+}
+
+/// 按时间先后比较两个时间戳，无法解析的视为最早。
+pub fn cmp_instants(a: &str, b: &str) -> Ordering {
+    parse_instant(a).cmp(&parse_instant(b))
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampStyle {
+    #[default]
+    DateTime,
+    Date,
+    Time,
+}
+
+impl TimestampStyle {
+    fn pattern(self) -> &'static str {
+        match self {
+            TimestampStyle::DateTime => "%x %X",
+            TimestampStyle::Date => "%x",
+            TimestampStyle::Time => "%X",
+        }
+    }
+}
+
+/// 接受 `zh-CN`、`en_US.UTF-8` 这类写法；未指定或无法识别时使用系统语言。
+fn resolve_locale(requested: Option<&str>) -> Locale {
+    let system = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|value| !value.is_empty()));
+    requested
+        .map(str::to_string)
+        .into_iter()
+        .chain(system)
+        .find_map(|name| {
+            let name = name.split(['.', '@']).next().unwrap_or_default();
+            Locale::try_from(name.replace('-', "_").as_str()).ok()
+        })
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+/// 按用户语言把 RFC 3339 时间戳格式化为本地时区的显示文本；无法解析的返回 null。
+#[tauri::command]
+pub async fn format_timestamps(
+    timestamps: Vec<String>,
+    locale: Option<String>,
+    style: Option<TimestampStyle>,
+) -> Result<Vec<Option<String>>, String> {
+    let locale = resolve_locale(locale.as_deref());
+    let pattern = style.unwrap_or_default().pattern();
+    Ok(timestamps
+        .iter()
+        .map(|value| {
+            parse_instant(value).map(|instant| {
+                instant
+                    .with_timezone(&Local)
+                    .format_localized(pattern, locale)
+                    .to_string()
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,374 @@
+use std::{collections::BTreeSet, io, path::PathBuf};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::datetime::{now_utc, parse_instant};
+use crate::reminders::{
+    ReminderAction, ReminderEngine, ReminderRepeat, ReminderSource, ReminderSpec,
+};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::todos::streaks;
+
+const HABITS_FILENAME: &str = "habits.json";
+const HABIT_CHANGED_EVENT: &str = "habit://changed";
+
+#[derive(Debug, Error)]
+pub enum HabitError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未找到指定的习惯：{0}")]
+    NotFound(String),
+    #[error("习惯设置无效：{0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HabitFrequency {
+    #[default]
+    Daily,
+    /// 每周完成 `target_per_week` 次即算达成，周一为一周的开始。
+    Weekly,
+}
+
+/// 习惯与待办分开存储：没有截止时间，按天打卡。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Habit {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub frequency: HabitFrequency,
+    #[serde(default = "default_target")]
+    pub target_per_week: u32,
+    /// 本地时间 HH:MM，当期尚未达成时每天在该时刻提醒。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder_time: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    /// 打卡日期（本地，YYYY-MM-DD），只通过打卡命令修改。
+    #[serde(default)]
+    pub check_ins: BTreeSet<NaiveDate>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+fn default_target() -> u32 {
+    1
+}
+
+/// 习惯及按频率计算的连续达成情况；每周习惯的连续次数按周计。
+#[derive(Debug, Serialize)]
+pub struct HabitSummary {
+    pub habit: Habit,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// 当期（今天或本周）的打卡次数。
+    pub period_count: u32,
+    pub period_done: bool,
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+impl Habit {
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.frequency {
+            HabitFrequency::Daily => date,
+            HabitFrequency::Weekly => week_start(date),
+        }
+    }
+
+    fn period_count(&self, today: NaiveDate) -> u32 {
+        let start = self.period_start(today);
+        self.check_ins
+            .range(start..=today)
+            .count()
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    fn target(&self) -> u32 {
+        match self.frequency {
+            HabitFrequency::Daily => 1,
+            HabitFrequency::Weekly => self.target_per_week.clamp(1, 7),
+        }
+    }
+
+    fn summary(&self, today: NaiveDate) -> HabitSummary {
+        let (current_streak, longest_streak) = match self.frequency {
+            HabitFrequency::Daily => streaks(&self.check_ins, today, Duration::days(1)),
+            HabitFrequency::Weekly => {
+                let done_weeks = self
+                    .check_ins
+                    .iter()
+                    .map(|day| week_start(*day))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .filter(|week| {
+                        self.check_ins
+                            .range(*week..*week + Duration::days(7))
+                            .count()
+                            >= self.target() as usize
+                    })
+                    .collect();
+                streaks(&done_weeks, week_start(today), Duration::days(7))
+            }
+        };
+        let period_count = self.period_count(today);
+        HabitSummary {
+            habit: self.clone(),
+            current_streak,
+            longest_streak,
+            period_count,
+            period_done: period_count >= self.target(),
+        }
+    }
+
+    /// 每天在 `reminder_time` 提醒，时刻以创建当天的本地时间为基准注册到提醒引擎。
+    fn reminder_spec(&self) -> Option<ReminderSpec> {
+        if self.archived {
+            return None;
+        }
+        let time = NaiveTime::parse_from_str(self.reminder_time.as_deref()?, "%H:%M").ok()?;
+        let date = parse_instant(&self.created_at).map_or_else(
+            || Local::now().date_naive(),
+            |created| created.with_timezone(&Local).date_naive(),
+        );
+        let fire_at = Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()?
+            .with_timezone(&Utc);
+        Some(ReminderSpec {
+            entity_id: self.id.clone(),
+            title: "习惯打卡提醒".into(),
+            body: self.name.clone(),
+            fire_at,
+            repeat: Some(ReminderRepeat::Daily),
+            expires_at: None,
+            actions: vec![ReminderAction::CheckIn, ReminderAction::Snooze],
+        })
+    }
+}
+
+pub struct HabitStore {
+    habits_file: PathBuf,
+    habits: async_runtime::Mutex<Vec<Habit>>,
+}
+
+impl HabitStore {
+    pub fn new(app: &AppHandle) -> Result<Self, HabitError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let habits_file = base_dir.join(HABITS_FILENAME);
+        let habits = load_json(&habits_file, Vec::new);
+        Ok(Self {
+            habits_file,
+            habits: async_runtime::Mutex::new(habits),
+        })
+    }
+
+    pub async fn all(&self) -> Vec<Habit> {
+        let guard = self.habits.lock().await;
+        guard.clone()
+    }
+
+    pub async fn summaries(&self) -> Vec<HabitSummary> {
+        let today = Local::now().date_naive();
+        let guard = self.habits.lock().await;
+        guard.iter().map(|habit| habit.summary(today)).collect()
+    }
+
+    /// 没有 id 或 id 不存在的视为新建；已存在的保留创建时间和打卡记录。
+    pub async fn save(&self, mut habit: Habit) -> Result<Habit, HabitError> {
+        habit.name = habit.name.trim().to_string();
+        if habit.name.is_empty() {
+            return Err(HabitError::Invalid("名称不能为空".into()));
+        }
+        if let Some(time) = habit.reminder_time.as_deref() {
+            if NaiveTime::parse_from_str(time, "%H:%M").is_err() {
+                return Err(HabitError::Invalid(format!(
+                    "提醒时间格式应为 HH:MM：{time}"
+                )));
+            }
+        }
+        let now = now_utc();
+        habit.updated_at = now.clone();
+        let mut guard = self.habits.lock().await;
+        match guard.iter_mut().find(|existing| existing.id == habit.id) {
+            Some(existing) if !habit.id.is_empty() => {
+                habit.created_at = existing.created_at.clone();
+                habit.check_ins = existing.check_ins.clone();
+                *existing = habit.clone();
+            }
+            _ => {
+                if habit.id.is_empty() {
+                    habit.id = Uuid::new_v4().to_string();
+                }
+                habit.created_at = now;
+                habit.check_ins.clear();
+                guard.push(habit.clone());
+            }
+        }
+        self.persist(&guard)?;
+        Ok(habit)
+    }
+
+    /// 合并数据包中的习惯，已存在的 id 保持不变，返回新增数量。
+    pub async fn merge(&self, habits: Vec<Habit>) -> Result<usize, HabitError> {
+        let mut guard = self.habits.lock().await;
+        let before = guard.len();
+        for habit in habits {
+            if !habit.id.is_empty() && !guard.iter().any(|existing| existing.id == habit.id) {
+                guard.push(habit);
+            }
+        }
+        let added = guard.len() - before;
+        if added > 0 {
+            self.persist(&guard)?;
+        }
+        Ok(added)
+    }
+
+    pub async fn delete(&self, habit_id: &str) -> Result<(), HabitError> {
+        let mut guard = self.habits.lock().await;
+        let before = guard.len();
+        guard.retain(|habit| habit.id != habit_id);
+        if guard.len() == before {
+            return Err(HabitError::NotFound(habit_id.to_string()));
+        }
+        self.persist(&guard)
+    }
+
+    /// `checked` 为 false 时取消当天的打卡；未来的日期不能打卡。
+    pub async fn set_check_in(
+        &self,
+        habit_id: &str,
+        date: NaiveDate,
+        checked: bool,
+    ) -> Result<HabitSummary, HabitError> {
+        let today = Local::now().date_naive();
+        if date > today {
+            return Err(HabitError::Invalid("不能为未来的日期打卡".into()));
+        }
+        let mut guard = self.habits.lock().await;
+        let habit = guard
+            .iter_mut()
+            .find(|habit| habit.id == habit_id)
+            .ok_or_else(|| HabitError::NotFound(habit_id.to_string()))?;
+        if checked {
+            habit.check_ins.insert(date);
+        } else {
+            habit.check_ins.remove(&date);
+        }
+        habit.updated_at = now_utc();
+        let summary = habit.summary(today);
+        self.persist(&guard)?;
+        Ok(summary)
+    }
+
+    fn persist(&self, habits: &[Habit]) -> Result<(), HabitError> {
+        write_atomic(&self.habits_file, &serde_json::to_vec_pretty(habits)?)?;
+        Ok(())
+    }
+
+    /// 把习惯的每日提醒同步到提醒引擎，每次习惯变化后调用。
+    pub(crate) async fn sync_reminders(&self, app: &AppHandle) {
+        let Some(engine) = app.try_state::<ReminderEngine>() else {
+            return;
+        };
+        let specs = {
+            let guard = self.habits.lock().await;
+            guard.iter().filter_map(Habit::reminder_spec).collect()
+        };
+        if let Err(err) = engine.sync_source(ReminderSource::Habit, specs).await {
+            log::warn!("failed to sync habit reminders: {err}");
+        }
+    }
+
+    /// 当期已经达成的习惯不再提醒。
+    pub(crate) async fn reminder_due(&self, habit_id: &str) -> bool {
+        let today = Local::now().date_naive();
+        let guard = self.habits.lock().await;
+        guard.iter().any(|habit| {
+            habit.id == habit_id && !habit.archived && habit.period_count(today) < habit.target()
+        })
+    }
+
+    pub(crate) async fn check_in_from_reminder(
+        &self,
+        app: &AppHandle,
+        habit_id: &str,
+    ) -> Result<(), HabitError> {
+        self.set_check_in(habit_id, Local::now().date_naive(), true)
+            .await?;
+        self.notify_changed(app).await;
+        Ok(())
+    }
+
+    pub(crate) async fn notify_changed(&self, app: &AppHandle) {
+        let _ = app.emit(HABIT_CHANGED_EVENT, self.summaries().await);
+        self.sync_reminders(app).await;
+    }
+}
+
+#[tauri::command]
+pub async fn list_habits(
+    habits: tauri::State<'_, HabitStore>,
+) -> Result<Vec<HabitSummary>, String> {
+    Ok(habits.summaries().await)
+}
+
+#[tauri::command]
+pub async fn save_habit(
+    app: AppHandle,
+    habits: tauri::State<'_, HabitStore>,
+    habit: Habit,
+) -> Result<Habit, String> {
+    let saved = habits.save(habit).await.map_err(|e| e.to_string())?;
+    habits.notify_changed(&app).await;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_habit(
+    app: AppHandle,
+    habits: tauri::State<'_, HabitStore>,
+    id: String,
+) -> Result<(), String> {
+    habits.delete(&id).await.map_err(|e| e.to_string())?;
+    habits.notify_changed(&app).await;
+    Ok(())
+}
+
+/// 打卡或取消打卡，`date` 缺省为今天（本地日期）。
+#[tauri::command]
+pub async fn check_in_habit(
+    app: AppHandle,
+    habits: tauri::State<'_, HabitStore>,
+    id: String,
+    date: Option<NaiveDate>,
+    checked: Option<bool>,
+) -> Result<HabitSummary, String> {
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+    let summary = habits
+        .set_check_in(&id, date, checked.unwrap_or(true))
+        .await
+        .map_err(|e| e.to_string())?;
+    habits.notify_changed(&app).await;
+    Ok(summary)
+}
@@ -0,0 +1,470 @@
+use std::{fs, io, path::PathBuf};
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::datetime::{now_utc, parse_instant};
+use crate::links::{EntityKind, EntityLink, EntityRef, LinkError, LinkStore};
+use crate::speech::{SpeechError, SpeechManager, SpeechSession};
+use crate::storage::{is_read_only, load_json, prepare_dir, write_atomic};
+use crate::todos::{TodoChangeKind, TodoError, TodoStore, TodoTask};
+
+const JOURNAL_FILENAME: &str = "journal.json";
+/// 被删除条目的文件（会话录音、待办附件）按操作 id 暂存在这里，撤销时移回。
+const STASH_DIRNAME: &str = "journal";
+const OPERATION_UNDONE_EVENT: &str = "journal://undone";
+const OPERATION_REDONE_EVENT: &str = "journal://redone";
+/// 超过这个时间的操作不能再撤销，暂存的文件随之清理。
+const UNDO_WINDOW_MINUTES: i64 = 30;
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum JournalError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error(transparent)]
+    Speech(#[from] SpeechError),
+    #[error(transparent)]
+    Todo(#[from] TodoError),
+    #[error(transparent)]
+    Link(#[from] LinkError),
+    #[error("没有可以撤销的操作")]
+    NothingToUndo,
+    #[error("没有可以重做的操作")]
+    NothingToRedo,
+}
+
+/// 可撤销的操作，保存撤销所需的全部数据。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalOperation {
+    DeleteSession {
+        index: usize,
+        session: SpeechSession,
+        /// 随会话一起移除的链接。
+        links: Vec<EntityLink>,
+    },
+    DeleteTodo {
+        index: usize,
+        todo: TodoTask,
+        /// 随待办一起移除的链接。
+        #[serde(default)]
+        links: Vec<EntityLink>,
+    },
+    /// 批量修改待办（如顺延逾期待办），记录修改前的版本。
+    EditTodos { before: Vec<TodoTask> },
+    /// 整体替换待办列表，记录替换前的列表。
+    ReplaceTodos { before: Vec<TodoTask> },
+}
+
+impl JournalOperation {
+    fn summary(&self) -> String {
+        match self {
+            JournalOperation::DeleteSession { session, .. } => {
+                format!("删除会话“{}”", session.title)
+            }
+            JournalOperation::DeleteTodo { todo, .. } => format!("删除待办“{}”", todo.title),
+            JournalOperation::EditTodos { before } => format!("修改 {} 个待办", before.len()),
+            JournalOperation::ReplaceTodos { .. } => "替换待办列表".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    /// 供撤销提示显示，如“删除会话“周会””。
+    pub summary: String,
+    pub operation: JournalOperation,
+    pub recorded_at: String,
+}
+
+impl JournalEntry {
+    fn is_expired(&self) -> bool {
+        !parse_instant(&self.recorded_at)
+            .is_some_and(|at| Utc::now() - at <= Duration::minutes(UNDO_WINDOW_MINUTES))
+    }
+}
+
+/// 可撤销操作的日志，按时间顺序保存，重启后仍可在时限内撤销。
+pub struct OperationJournal {
+    journal_file: PathBuf,
+    stash_dir: PathBuf,
+    entries: async_runtime::Mutex<Vec<JournalEntry>>,
+    /// 撤销过的操作，最近撤销的在末尾；只保存在内存中，记录新操作时清空。
+    /// 删除类操作重做时按 id 再删一次，批量修改和替换记录的是撤销前的待办。
+    redo: async_runtime::Mutex<Vec<JournalEntry>>,
+}
+
+impl OperationJournal {
+    pub fn new(app: &AppHandle) -> Result<Self, JournalError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let journal_file = base_dir.join(JOURNAL_FILENAME);
+        let stash_dir = base_dir.join(STASH_DIRNAME);
+        prepare_dir(&stash_dir)?;
+        let loaded: Vec<JournalEntry> = load_json(&journal_file, Vec::new);
+        let mut journal = Self {
+            journal_file,
+            stash_dir,
+            entries: async_runtime::Mutex::new(Vec::new()),
+            redo: async_runtime::Mutex::new(Vec::new()),
+        };
+        // 只读模式下不清理文件，过期的记录在 `undoable` 和 `undo_last` 中被忽略
+        let entries = if is_read_only() {
+            loaded
+        } else {
+            let loaded_count = loaded.len();
+            let entries = journal.prune(loaded);
+            if entries.len() != loaded_count {
+                journal.persist(&entries)?;
+            }
+            journal.remove_orphaned_stashes(&entries);
+            entries
+        };
+        *journal.entries.get_mut() = entries;
+        Ok(journal)
+    }
+
+    fn stash_path(&self, entry_id: &str) -> PathBuf {
+        self.stash_dir.join(entry_id)
+    }
+
+    fn remove_stash(&self, entry_id: &str) {
+        let path = self.stash_path(entry_id);
+        if path.exists() {
+            if let Err(err) = fs::remove_dir_all(&path) {
+                log::warn!("failed to remove journal stash {entry_id}: {err}");
+            }
+        }
+    }
+
+    /// 日志写入前崩溃时会留下没有对应记录的暂存目录。
+    fn remove_orphaned_stashes(&self, entries: &[JournalEntry]) {
+        let Ok(dirs) = fs::read_dir(&self.stash_dir) else {
+            return;
+        };
+        for dir in dirs.flatten() {
+            let name = dir.file_name().to_string_lossy().into_owned();
+            if !entries.iter().any(|entry| entry.id == name) {
+                self.remove_stash(&name);
+            }
+        }
+    }
+
+    /// 去掉过期和超出数量上限的记录，并清理它们暂存的文件。
+    fn prune(&self, entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+        let overflow = entries.len().saturating_sub(MAX_ENTRIES);
+        let mut kept = Vec::with_capacity(entries.len());
+        for (position, entry) in entries.into_iter().enumerate() {
+            if position < overflow || entry.is_expired() {
+                self.remove_stash(&entry.id);
+            } else {
+                kept.push(entry);
+            }
+        }
+        kept
+    }
+
+    fn persist(&self, entries: &[JournalEntry]) -> Result<(), JournalError> {
+        write_atomic(&self.journal_file, &serde_json::to_vec_pretty(entries)?)?;
+        Ok(())
+    }
+
+    /// 记录用户的新操作，之前撤销的操作不能再重做。
+    async fn record_entry(&self, id: String, operation: JournalOperation) {
+        self.push_entry(id, operation).await;
+        self.redo.lock().await.clear();
+    }
+
+    async fn push_entry(&self, id: String, operation: JournalOperation) {
+        let entry = JournalEntry {
+            id,
+            summary: operation.summary(),
+            operation,
+            recorded_at: now_utc(),
+        };
+        let mut guard = self.entries.lock().await;
+        let mut entries = guard.clone();
+        entries.push(entry);
+        let entries = self.prune(entries);
+        // 日志写不进去只是无法撤销，不影响已经完成的操作
+        match self.persist(&entries) {
+            Ok(()) => *guard = entries,
+            Err(err) => log::warn!("failed to record operation in journal: {err}"),
+        }
+    }
+
+    /// 记录一次不涉及文件的操作，如批量修改待办。
+    pub async fn record(&self, operation: JournalOperation) {
+        self.record_entry(Uuid::new_v4().to_string(), operation)
+            .await;
+    }
+
+    /// 删除会话并记入日志，会话目录暂存以便撤销；返回一并移除的链接。
+    pub async fn delete_session(
+        &self,
+        speech: &SpeechManager,
+        links: &LinkStore,
+        session_id: &str,
+    ) -> Result<Vec<EntityLink>, JournalError> {
+        let entry_id = Uuid::new_v4().to_string();
+        let (operation, removed) = self
+            .stash_session(speech, links, &entry_id, session_id)
+            .await?;
+        if let Some(operation) = operation {
+            self.record_entry(entry_id, operation).await;
+        }
+        Ok(removed)
+    }
+
+    /// 删除会话，会话目录暂存在 `entry_id` 名下；会话不存在时没有可撤销的操作。
+    async fn stash_session(
+        &self,
+        speech: &SpeechManager,
+        links: &LinkStore,
+        entry_id: &str,
+        session_id: &str,
+    ) -> Result<(Option<JournalOperation>, Vec<EntityLink>), JournalError> {
+        let deleted = speech
+            .delete_session(session_id, &self.stash_path(entry_id))
+            .await?;
+        let removed = links
+            .remove_entity(&EntityRef {
+                kind: EntityKind::Session,
+                id: session_id.to_string(),
+            })
+            .await?;
+        let operation = deleted.map(|(index, session)| JournalOperation::DeleteSession {
+            index,
+            session,
+            links: removed.clone(),
+        });
+        Ok((operation, removed))
+    }
+
+    /// 删除待办并记入日志，附件暂存以便撤销；返回一并移除的链接。
+    pub async fn delete_todo(
+        &self,
+        todos: &TodoStore,
+        links: &LinkStore,
+        todo_id: &str,
+    ) -> Result<Vec<EntityLink>, JournalError> {
+        let entry_id = Uuid::new_v4().to_string();
+        let (operation, removed) = self.stash_todo(todos, links, &entry_id, todo_id).await?;
+        self.record_entry(entry_id, operation).await;
+        Ok(removed)
+    }
+
+    /// 删除待办，附件暂存在 `entry_id` 名下。
+    async fn stash_todo(
+        &self,
+        todos: &TodoStore,
+        links: &LinkStore,
+        entry_id: &str,
+        todo_id: &str,
+    ) -> Result<(JournalOperation, Vec<EntityLink>), JournalError> {
+        let (index, todo) = todos.delete(todo_id, &self.stash_path(entry_id)).await?;
+        let removed = links
+            .remove_entity(&EntityRef {
+                kind: EntityKind::Todo,
+                id: todo_id.to_string(),
+            })
+            .await?;
+        let operation = JournalOperation::DeleteTodo {
+            index,
+            todo,
+            links: removed.clone(),
+        };
+        Ok((operation, removed))
+    }
+
+    /// 可以重做的操作，最近撤销的在前。
+    pub async fn redoable(&self) -> Vec<JournalEntry> {
+        let guard = self.redo.lock().await;
+        guard
+            .iter()
+            .rev()
+            .filter(|entry| !entry.is_expired())
+            .cloned()
+            .collect()
+    }
+
+    /// 时限内仍可撤销的操作，最近的在前。
+    pub async fn undoable(&self) -> Vec<JournalEntry> {
+        let guard = self.entries.lock().await;
+        guard
+            .iter()
+            .rev()
+            .filter(|entry| !entry.is_expired())
+            .cloned()
+            .collect()
+    }
+
+    /// 撤销最近一次操作；撤销失败时保留记录，可以重试。
+    pub async fn undo_last(&self, app: &AppHandle) -> Result<JournalEntry, JournalError> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.prune(guard.clone());
+        let entry = entries.last().cloned().ok_or(JournalError::NothingToUndo)?;
+        let stash = self.stash_path(&entry.id);
+        let redo_operation = match entry.operation.clone() {
+            JournalOperation::DeleteSession {
+                index,
+                session,
+                links,
+            } => {
+                app.state::<SpeechManager>()
+                    .restore_session(index, session, &stash)
+                    .await?;
+                app.state::<LinkStore>().merge(links).await?;
+                entry.operation.clone()
+            }
+            JournalOperation::DeleteTodo { index, todo, links } => {
+                let todos = app.state::<TodoStore>();
+                let id = todo.id.clone();
+                todos.restore(index, todo, &stash).await?;
+                app.state::<LinkStore>().merge(links).await?;
+                todos
+                    .notify_changed(app, TodoChangeKind::Saved, vec![id])
+                    .await;
+                entry.operation.clone()
+            }
+            JournalOperation::EditTodos { before } => {
+                let todos = app.state::<TodoStore>();
+                let after = current_versions(&todos, &before).await;
+                let ids = todos.restore_versions(before).await?;
+                todos.notify_changed(app, TodoChangeKind::Saved, ids).await;
+                JournalOperation::EditTodos { before: after }
+            }
+            JournalOperation::ReplaceTodos { before } => {
+                let todos = app.state::<TodoStore>();
+                let after = todos.all().await;
+                let ids = before.iter().map(|todo| todo.id.clone()).collect();
+                todos.replace(before).await?;
+                todos
+                    .notify_changed(app, TodoChangeKind::Replaced, ids)
+                    .await;
+                JournalOperation::ReplaceTodos { before: after }
+            }
+        };
+        let mut remaining = entries;
+        remaining.pop();
+        self.persist(&remaining)?;
+        *guard = remaining;
+        drop(guard);
+        self.remove_stash(&entry.id);
+        self.redo.lock().await.push(JournalEntry {
+            id: Uuid::new_v4().to_string(),
+            summary: entry.summary.clone(),
+            operation: redo_operation,
+            recorded_at: now_utc(),
+        });
+        let _ = app.emit(OPERATION_UNDONE_EVENT, &entry);
+        Ok(entry)
+    }
+
+    /// 重做最近一次撤销的操作，重做后可以再次撤销。
+    pub async fn redo_last(&self, app: &AppHandle) -> Result<JournalEntry, JournalError> {
+        let mut redo = self.redo.lock().await;
+        redo.retain(|entry| !entry.is_expired());
+        let entry = redo.last().cloned().ok_or(JournalError::NothingToRedo)?;
+        let entry_id = Uuid::new_v4().to_string();
+        match entry.operation.clone() {
+            JournalOperation::DeleteSession { session, .. } => {
+                let (operation, _) = self
+                    .stash_session(
+                        &app.state::<SpeechManager>(),
+                        &app.state::<LinkStore>(),
+                        &entry_id,
+                        &session.id,
+                    )
+                    .await?;
+                if let Some(operation) = operation {
+                    self.push_entry(entry_id, operation).await;
+                }
+            }
+            JournalOperation::DeleteTodo { todo, .. } => {
+                let todos = app.state::<TodoStore>();
+                let id = todo.id.clone();
+                let (operation, _) = self
+                    .stash_todo(&todos, &app.state::<LinkStore>(), &entry_id, &id)
+                    .await?;
+                self.push_entry(entry_id, operation).await;
+                todos
+                    .notify_changed(app, TodoChangeKind::Deleted, vec![id])
+                    .await;
+            }
+            JournalOperation::EditTodos { before: after } => {
+                let todos = app.state::<TodoStore>();
+                let before = current_versions(&todos, &after).await;
+                let ids = todos.restore_versions(after).await?;
+                self.push_entry(entry_id, JournalOperation::EditTodos { before })
+                    .await;
+                todos.notify_changed(app, TodoChangeKind::Saved, ids).await;
+            }
+            JournalOperation::ReplaceTodos { before: after } => {
+                let todos = app.state::<TodoStore>();
+                let before = todos.all().await;
+                let ids = after.iter().map(|todo| todo.id.clone()).collect();
+                todos.replace(after).await?;
+                self.push_entry(entry_id, JournalOperation::ReplaceTodos { before })
+                    .await;
+                todos
+                    .notify_changed(app, TodoChangeKind::Replaced, ids)
+                    .await;
+            }
+        }
+        redo.pop();
+        let _ = app.emit(OPERATION_REDONE_EVENT, &entry);
+        Ok(entry)
+    }
+}
+
+/// `versions` 中各待办当前的版本，已被删除的跳过。
+async fn current_versions(todos: &TodoStore, versions: &[TodoTask]) -> Vec<TodoTask> {
+    todos
+        .all()
+        .await
+        .into_iter()
+        .filter(|todo| versions.iter().any(|version| version.id == todo.id))
+        .collect()
+}
+
+/// 撤销最近一次删除会话、删除待办或批量修改待办的操作，超过 30 分钟的操作不能撤销。
+#[tauri::command]
+pub async fn undo_last_operation(
+    app: AppHandle,
+    journal: tauri::State<'_, OperationJournal>,
+) -> Result<JournalEntry, String> {
+    journal.undo_last(&app).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_undoable_operations(
+    journal: tauri::State<'_, OperationJournal>,
+) -> Result<Vec<JournalEntry>, String> {
+    Ok(journal.undoable().await)
+}
+
+/// 重做最近一次撤销的操作；撤销后又做了新的操作时不能再重做。
+#[tauri::command]
+pub async fn redo_last_operation(
+    app: AppHandle,
+    journal: tauri::State<'_, OperationJournal>,
+) -> Result<JournalEntry, String> {
+    journal.redo_last(&app).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_redoable_operations(
+    journal: tauri::State<'_, OperationJournal>,
+) -> Result<Vec<JournalEntry>, String> {
+    Ok(journal.redoable().await)
+}
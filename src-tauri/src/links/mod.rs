@@ -0,0 +1,239 @@
+use std::{io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::datetime::now_utc;
+use crate::notes::NoteStore;
+use crate::speech::SpeechManager;
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::todos::TodoStore;
+
+#[derive(Debug, Error)]
+pub enum LinkError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("不能将条目链接到自身")]
+    SelfLink,
+    #[error("链接目标不存在：{0}")]
+    MissingEntity(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Session,
+    Todo,
+    Note,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct EntityRef {
+    pub kind: EntityKind,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityLink {
+    pub id: String,
+    pub from: EntityRef,
+    pub to: EntityRef,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub created_at: String,
+}
+
+pub struct LinkStore {
+    links_file: PathBuf,
+    links: async_runtime::Mutex<Vec<EntityLink>>,
+}
+
+impl LinkStore {
+    pub fn new(app: &AppHandle) -> Result<Self, LinkError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let links_file = base_dir.join("links.json");
+        let links = load_json(&links_file, Vec::new);
+        Ok(Self {
+            links_file,
+            links: async_runtime::Mutex::new(links),
+        })
+    }
+
+    pub async fn create(
+        &self,
+        from: EntityRef,
+        to: EntityRef,
+        label: Option<String>,
+    ) -> Result<EntityLink, LinkError> {
+        if from == to {
+            return Err(LinkError::SelfLink);
+        }
+
+        let mut guard = self.links.lock().await;
+        if let Some(existing) = guard.iter().find(|link| link.from == from && link.to == to) {
+            return Ok(existing.clone());
+        }
+
+        let link = EntityLink {
+            id: Uuid::new_v4().to_string(),
+            from,
+            to,
+            label: label
+                .map(|label| label.trim().to_string())
+                .filter(|label| !label.is_empty()),
+            created_at: now_utc(),
+        };
+        guard.push(link.clone());
+        self.persist(&guard)?;
+        Ok(link)
+    }
+
+    pub async fn delete(&self, link_id: &str) -> Result<bool, LinkError> {
+        let mut guard = self.links.lock().await;
+        let before = guard.len();
+        guard.retain(|link| link.id != link_id);
+        let removed = guard.len() != before;
+        if removed {
+            self.persist(&guard)?;
+        }
+        Ok(removed)
+    }
+
+    pub async fn outgoing(&self, entity: &EntityRef) -> Vec<EntityLink> {
+        let guard = self.links.lock().await;
+        guard
+            .iter()
+            .filter(|link| &link.from == entity)
+            .cloned()
+            .collect()
+    }
+
+    pub async fn backlinks(&self, entity: &EntityRef) -> Vec<EntityLink> {
+        let guard = self.links.lock().await;
+        guard
+            .iter()
+            .filter(|link| &link.to == entity)
+            .cloned()
+            .collect()
+    }
+
+    /// 删除实体时一并移除指向或来自它的链接，返回被移除的链接以便提示。
+    pub async fn remove_entity(&self, entity: &EntityRef) -> Result<Vec<EntityLink>, LinkError> {
+        let mut guard = self.links.lock().await;
+        let (removed, kept): (Vec<EntityLink>, Vec<EntityLink>) = guard
+            .drain(..)
+            .partition(|link| &link.from == entity || &link.to == entity);
+        *guard = kept;
+        if !removed.is_empty() {
+            self.persist(&guard)?;
+        }
+        Ok(removed)
+    }
+
+    pub async fn all(&self) -> Vec<EntityLink> {
+        let guard = self.links.lock().await;
+        guard.clone()
+    }
+
+    /// 合并导入的链接，已存在的 id 保持不变，返回新增数量。
+    pub async fn merge(&self, links: Vec<EntityLink>) -> Result<usize, LinkError> {
+        let mut guard = self.links.lock().await;
+        let before = guard.len();
+        for link in links {
+            if !guard.iter().any(|existing| existing.id == link.id) {
+                guard.push(link);
+            }
+        }
+        let added = guard.len() - before;
+        if added > 0 {
+            self.persist(&guard)?;
+        }
+        Ok(added)
+    }
+
+    fn persist(&self, links: &[EntityLink]) -> Result<(), LinkError> {
+        write_atomic(&self.links_file, &serde_json::to_vec_pretty(links)?)?;
+        Ok(())
+    }
+}
+
+async fn ensure_entity_exists(
+    speech: &SpeechManager,
+    todos: &TodoStore,
+    notes: &NoteStore,
+    entity: &EntityRef,
+) -> Result<(), LinkError> {
+    let exists = match entity.kind {
+        EntityKind::Session => speech.has_session(&entity.id).await,
+        EntityKind::Todo => todos.has_todo(&entity.id).await,
+        EntityKind::Note => notes.has_note(&entity.id).await,
+    };
+    if !exists {
+        return Err(LinkError::MissingEntity(entity.id.clone()));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_link(
+    links: tauri::State<'_, LinkStore>,
+    speech: tauri::State<'_, SpeechManager>,
+    todos: tauri::State<'_, TodoStore>,
+    notes: tauri::State<'_, NoteStore>,
+    from: EntityRef,
+    to: EntityRef,
+    label: Option<String>,
+) -> Result<EntityLink, String> {
+    for entity in [&from, &to] {
+        ensure_entity_exists(&speech, &todos, &notes, entity)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    links
+        .create(from, to, label)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_link(
+    links: tauri::State<'_, LinkStore>,
+    link_id: String,
+) -> Result<bool, String> {
+    links.delete(&link_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_links(
+    links: tauri::State<'_, LinkStore>,
+    entity: EntityRef,
+) -> Result<Vec<EntityLink>, String> {
+    Ok(links.outgoing(&entity).await)
+}
+
+#[tauri::command]
+pub async fn list_backlinks(
+    links: tauri::State<'_, LinkStore>,
+    entity: EntityRef,
+) -> Result<Vec<EntityLink>, String> {
+    Ok(links.backlinks(&entity).await)
+}
+
+/// 清理指向某个条目的全部链接，返回被移除的链接；删除会话、待办和笔记时已自动清理。
+#[tauri::command]
+pub async fn remove_entity_links(
+    links: tauri::State<'_, LinkStore>,
+    entity: EntityRef,
+) -> Result<Vec<EntityLink>, String> {
+    links
+        .remove_entity(&entity)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,12 +1,94 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod backup;
+mod badge;
+mod bundle;
+mod calendar;
+mod countdowns;
+mod datetime;
+mod habits;
+mod journal;
+mod links;
+mod migrations;
+mod network;
+mod notes;
+mod notifications;
+mod panels;
+mod power;
+mod reminders;
+mod reports;
+mod search;
+mod settings;
 mod speech;
+mod status;
+mod storage;
+mod theme;
+mod timeline;
+mod timezones;
+mod todos;
+mod widgets;
+mod window_prefs;
 
+use backup::{
+    get_backup_status, list_backups, restore_backup, run_backup_now, set_backup_settings,
+    BackupManager,
+};
+use bundle::{export_app_bundle, import_app_bundle};
+use calendar::{
+    get_calendar_status, refresh_calendar_subscriptions, set_calendar_subscriptions,
+    CalendarManager,
+};
+use countdowns::{
+    create_countdown, delete_countdown, list_countdowns, open_countdown_widget, CountdownStore,
+};
+use datetime::format_timestamps;
+use habits::{check_in_habit, delete_habit, list_habits, save_habit, HabitStore};
+use journal::{
+    list_redoable_operations, list_undoable_operations, redo_last_operation, undo_last_operation,
+    OperationJournal,
+};
+use links::{create_link, delete_link, list_backlinks, list_links, remove_entity_links, LinkStore};
+use network::{get_network_settings, set_offline_mode};
+#[cfg(desktop)]
+use notes::register_quick_note;
+use notes::{
+    add_quick_note, cancel_voice_note, delete_note, finish_voice_note, list_notes, open_quick_note,
+    pin_note, start_voice_note, NoteStore,
+};
+use notifications::handle_notification_action;
+use panels::{
+    attach_panel, detach_panel, get_detached_panel, list_detached_panels, update_panel_state,
+    PanelRegistry,
+};
+use reminders::{list_reminders, run_reminder_action, ReminderEngine};
+use reports::{generate_weekly_report, get_weekly_report};
+use search::{get_search_index_status, rebuild_search_index, search_everything, SearchIndexer};
+use settings::{
+    apply_preset_from_url, export_settings, import_settings, list_trusted_preset_keys,
+    remove_trusted_preset_key,
+};
 use speech::{
-    cancel_transcription, delete_speech_session, ensure_speech_model, export_speech_sessions,
-    import_speech_sessions, list_speech_sessions, open_speech_session_folder, transcribe_audio,
-    update_speech_session, SpeechManager,
+    analyze_audio, analyze_session_audio, arm_recording, autosave_transcript_draft,
+    benchmark_speech_model, cancel_model_download, cancel_transcription, check_model_update,
+    delete_retention_policy, delete_smart_collection, delete_speech_model, delete_speech_session,
+    diarize_speech_session, disarm_recording, discard_transcript_draft,
+    discard_untranscribed_recording, ensure_speech_model, evaluate_smart_collection,
+    export_segments_csv, export_session_json, export_speech_sessions, get_decoding_settings,
+    get_hf_token_status, get_model_registry, get_model_source, get_recording_settings,
+    get_recording_status, get_title_template, get_transcript_draft, get_transcription_backends,
+    import_speech_model, import_speech_sessions, list_retention_policies, list_sessions_grouped,
+    list_smart_collections, list_speech_languages, list_speech_models, list_speech_sessions,
+    list_transcript_drafts, list_untranscribed_recordings, open_speech_session_folder,
+    pause_model_download, pause_recording, preload_speech_model, recommend_speech_model,
+    rename_speaker, resume_model_download, run_retention_policies, save_retention_policy,
+    save_smart_collection, set_decoding_settings, set_hf_token, set_model_source,
+    set_recording_settings, set_speech_model, set_speech_storage_dir, set_title_template,
+    set_transcription_backend, start_recording, stop_recording, transcribe_audio,
+    transcribe_recording, transcribe_untranscribed_recording, update_speech_session,
+    validate_segments, SpeechManager, SpeechRecorder,
 };
+use status::{get_app_status, AppStatus};
+use storage::{get_storage_health, is_read_only, take_load_failures, StorageGuard};
 use tauri::{
     image::Image,
     menu::{MenuBuilder, MenuItemBuilder},
@@ -14,6 +96,28 @@ use tauri::{
     Manager,
 };
 use tauri_plugin_log::{fern::colors::ColoredLevelConfig, Target, TargetKind};
+use theme::{get_app_theme, set_app_theme, ThemeStore};
+use timeline::get_timeline;
+use timezones::{
+    convert_time, get_world_clock, list_timezones, list_world_cities, save_world_cities,
+    TimezoneStore,
+};
+#[cfg(desktop)]
+use todos::register_quick_capture;
+use todos::{
+    attach_todo_file, attach_voice_memo, delete_todo, delete_todo_template, get_current_wifi_ssid,
+    get_todo_attachment_path, get_todo_board, get_todo_stats, instantiate_template,
+    list_todo_templates, list_todos, move_todo, parse_todo_input, plan_today,
+    remove_todo_attachment, replace_todos, reschedule_overdue, save_todo, save_todo_columns,
+    save_todo_template, schedule_todo_in_timezone, snooze_todo, undo_quick_capture, TodoStore,
+};
+use widgets::{get_widget_appearance, set_widget_appearance, WidgetStore, TODO_WIDGET_LABEL};
+use window_prefs::{
+    get_window_prefs, set_window_opacity, set_window_pin, set_window_zoom, WindowPrefsStore,
+};
+
+/// 倒计时会更新托盘提示，通过这个 id 找到托盘图标。
+const TRAY_ID: &str = "kk-tray";
 
 fn to_boxed_error<E>(err: E) -> Box<dyn std::error::Error>
 where
@@ -24,7 +128,7 @@ where
 
 #[tauri::command]
 fn open_todo_widget(app_handle: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("todo-widget") {
+    if let Some(window) = app_handle.get_webview_window(TODO_WIDGET_LABEL) {
         window.show().map_err(|e| e.to_string())?;
         window.set_focus().map_err(|e| e.to_string())?;
         return Ok(());
@@ -32,7 +136,7 @@ fn open_todo_widget(app_handle: tauri::AppHandle) -> Result<(), String> {
 
     let window = tauri::WebviewWindowBuilder::new(
         &app_handle,
-        "todo-widget",
+        TODO_WIDGET_LABEL,
         tauri::WebviewUrl::App("/todo/widget".into()),
     )
     .title("Todo Widget")
@@ -40,9 +144,11 @@ fn open_todo_widget(app_handle: tauri::AppHandle) -> Result<(), String> {
     .min_inner_size(520.0, 350.0)
     .resizable(true)
     .decorations(false)
+    .transparent(true)
     .always_on_top(true)
     .build()
     .map_err(|e| e.to_string())?;
+    widgets::restore_dock(&window.as_ref().window());
 
     window.show().map_err(|e| e.to_string())?;
     window.set_focus().map_err(|e| e.to_string())?;
@@ -52,10 +158,91 @@ fn open_todo_widget(app_handle: tauri::AppHandle) -> Result<(), String> {
 
 fn main() {
     tauri::Builder::default()
+        .on_page_load(|webview, payload| {
+            theme::on_page_load(webview, payload);
+            window_prefs::on_page_load(webview, payload);
+        })
+        .on_window_event(|window, event| {
+            widgets::on_window_event(window, event);
+            panels::on_window_event(window, event);
+        })
         .setup(|app| {
+            // whisper.cpp / ggml 默认写 stderr，打包后看不到，转发到 log 以便排查模型加载失败
+            whisper_rs::install_logging_hooks();
             let handle = app.handle();
-            let manager = SpeechManager::new(&handle).map_err(to_boxed_error)?;
+            let mut status = AppStatus::detect(&handle);
+            // 先锁住数据目录再迁移和加载数据，另一个实例正在使用时只读启动
+            let storage = StorageGuard::new(&handle).map_err(to_boxed_error)?;
+            if let Some(reason) = storage.conflict() {
+                log::error!("data directory is locked by another instance, starting read-only");
+                status.enter_read_only(reason);
+            }
+            if !is_read_only() {
+                if let Err(err) = migrations::run_pending(&handle) {
+                    log::error!("data migration failed, starting read-only: {err}");
+                    status.enter_read_only(err.to_string());
+                }
+            }
+            network::load_settings(&handle);
+            let indexer = SearchIndexer::new(&handle).map_err(to_boxed_error)?;
+            let manager = SpeechManager::new(&handle, indexer.handle()).map_err(to_boxed_error)?;
+            let seed_index = indexer.needs_rebuild();
+            let search_index = indexer.handle();
+            manager.notify_untranscribed(handle);
+            app.manage(indexer);
             app.manage(manager);
+            app.manage(SpeechRecorder::default());
+            app.manage(PanelRegistry::default());
+            app.manage(ReminderEngine::new(&handle).map_err(to_boxed_error)?);
+            app.manage(CalendarManager::new(&handle).map_err(to_boxed_error)?);
+            app.manage(LinkStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(TodoStore::new(&handle, search_index.clone()).map_err(to_boxed_error)?);
+            app.manage(OperationJournal::new(&handle).map_err(to_boxed_error)?);
+            app.manage(HabitStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(TimezoneStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(CountdownStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(NoteStore::new(&handle, search_index).map_err(to_boxed_error)?);
+            app.manage(BackupManager::new(&handle).map_err(to_boxed_error)?);
+            app.manage(storage);
+            app.manage(WidgetStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(ThemeStore::new(&handle).map_err(to_boxed_error)?);
+            app.manage(WindowPrefsStore::new(&handle).map_err(to_boxed_error)?);
+            // 数据文件损坏时以空数据运行，只读避免覆盖原文件
+            let load_failures = take_load_failures();
+            if !load_failures.is_empty() {
+                status.enter_read_only(load_failures.join("；"));
+            }
+            app.manage(status);
+            theme::apply_to_all(handle);
+            CalendarManager::spawn_refresh_loop(handle.clone());
+            SpeechManager::spawn_retention_loop(handle.clone());
+            BackupManager::spawn_schedule_loop(handle.clone());
+            StorageGuard::spawn_heartbeat_loop(handle.clone());
+            TodoStore::spawn_trigger_watcher(handle.clone());
+            CountdownStore::spawn_tick_loop(handle.clone());
+            ReminderEngine::spawn_wake_loop(handle.clone());
+            badge::spawn_refresh_loop(handle.clone());
+            power::spawn_resume_watcher(handle.clone());
+            #[cfg(mobile)]
+            notifications::register_action_types(&handle);
+            #[cfg(desktop)]
+            {
+                handle
+                    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+                    .map_err(to_boxed_error)?;
+                register_quick_capture(&handle);
+                register_quick_note(&handle);
+            }
+
+            if seed_index {
+                let handle = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let documents = search::collect_documents(&handle).await;
+                    if let Err(err) = handle.state::<SearchIndexer>().rebuild(documents).await {
+                        log::error!("failed to build search index: {err}");
+                    }
+                });
+            }
 
             let show_main_item =
                 MenuItemBuilder::with_id("show-main", "显示主窗口").build(app).map_err(to_boxed_error)?;
@@ -71,7 +258,7 @@ fn main() {
             let tray_icon_image = Image::from_bytes(include_bytes!("../icons/32x32.png"))
                 .map_err(to_boxed_error)?;
 
-            let tray_builder = TrayIconBuilder::with_id("kk-tray")
+            let tray_builder = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(tray_icon_image.clone())
                 .menu(&tray_menu)
                 .tooltip("Kk")
@@ -117,7 +304,158 @@ fn main() {
             cancel_transcription,
             open_speech_session_folder,
             export_speech_sessions,
-            import_speech_sessions
+            import_speech_sessions,
+            search_everything,
+            get_search_index_status,
+            rebuild_search_index,
+            list_smart_collections,
+            save_smart_collection,
+            delete_smart_collection,
+            evaluate_smart_collection,
+            generate_weekly_report,
+            get_weekly_report,
+            set_calendar_subscriptions,
+            refresh_calendar_subscriptions,
+            get_calendar_status,
+            create_link,
+            delete_link,
+            list_links,
+            list_backlinks,
+            remove_entity_links,
+            get_timeline,
+            list_untranscribed_recordings,
+            transcribe_untranscribed_recording,
+            discard_untranscribed_recording,
+            analyze_audio,
+            list_retention_policies,
+            save_retention_policy,
+            delete_retention_policy,
+            run_retention_policies,
+            export_app_bundle,
+            import_app_bundle,
+            get_backup_status,
+            set_backup_settings,
+            run_backup_now,
+            list_backups,
+            restore_backup,
+            get_storage_health,
+            set_speech_model,
+            get_app_status,
+            get_model_source,
+            set_model_source,
+            analyze_session_audio,
+            get_model_registry,
+            delete_speech_model,
+            cancel_model_download,
+            get_decoding_settings,
+            set_decoding_settings,
+            list_speech_models,
+            recommend_speech_model,
+            pause_model_download,
+            resume_model_download,
+            import_speech_model,
+            get_title_template,
+            set_title_template,
+            format_timestamps,
+            preload_speech_model,
+            list_sessions_grouped,
+            get_transcription_backends,
+            set_transcription_backend,
+            export_settings,
+            import_settings,
+            apply_preset_from_url,
+            list_trusted_preset_keys,
+            remove_trusted_preset_key,
+            benchmark_speech_model,
+            list_todos,
+            save_todo,
+            delete_todo,
+            replace_todos,
+            get_todo_stats,
+            check_model_update,
+            snooze_todo,
+            reschedule_overdue,
+            attach_todo_file,
+            attach_voice_memo,
+            remove_todo_attachment,
+            get_todo_attachment_path,
+            set_speech_storage_dir,
+            list_todo_templates,
+            save_todo_template,
+            delete_todo_template,
+            instantiate_template,
+            get_todo_board,
+            save_todo_columns,
+            move_todo,
+            get_network_settings,
+            set_offline_mode,
+            plan_today,
+            get_current_wifi_ssid,
+            parse_todo_input,
+            undo_quick_capture,
+            list_habits,
+            save_habit,
+            delete_habit,
+            check_in_habit,
+            set_hf_token,
+            get_hf_token_status,
+            get_world_clock,
+            list_world_cities,
+            save_world_cities,
+            convert_time,
+            list_timezones,
+            schedule_todo_in_timezone,
+            list_countdowns,
+            create_countdown,
+            delete_countdown,
+            open_countdown_widget,
+            start_recording,
+            pause_recording,
+            get_recording_status,
+            stop_recording,
+            transcribe_recording,
+            list_notes,
+            add_quick_note,
+            start_voice_note,
+            finish_voice_note,
+            cancel_voice_note,
+            pin_note,
+            delete_note,
+            open_quick_note,
+            list_reminders,
+            run_reminder_action,
+            handle_notification_action,
+            get_app_theme,
+            set_app_theme,
+            diarize_speech_session,
+            rename_speaker,
+            get_widget_appearance,
+            set_widget_appearance,
+            get_window_prefs,
+            set_window_pin,
+            set_window_opacity,
+            list_speech_languages,
+            detach_panel,
+            attach_panel,
+            update_panel_state,
+            get_detached_panel,
+            list_detached_panels,
+            set_window_zoom,
+            autosave_transcript_draft,
+            get_transcript_draft,
+            list_transcript_drafts,
+            discard_transcript_draft,
+            undo_last_operation,
+            list_undoable_operations,
+            validate_segments,
+            export_session_json,
+            export_segments_csv,
+            redo_last_operation,
+            list_redoable_operations,
+            get_recording_settings,
+            set_recording_settings,
+            arm_recording,
+            disarm_recording
         ])
         .plugin(tauri_plugin_fs::init())
         // 暂时禁用 window-state 插件来避免窗口状态冲突
@@ -128,7 +466,10 @@ fn main() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(
             tauri_plugin_log::Builder::default()
-                .targets([Target::new(TargetKind::Webview)])
+                .targets([
+                    Target::new(TargetKind::Webview),
+                    Target::new(TargetKind::LogDir { file_name: None }),
+                ])
                 .with_colors(ColoredLevelConfig::default())
                 .build(),
         )
@@ -0,0 +1,304 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use thiserror::Error;
+
+use crate::datetime::{now_utc, parse_instant, to_utc_string};
+use crate::storage::write_atomic;
+
+const SCHEMA_FILENAME: &str = "schema.json";
+const BACKUP_DIR: &str = ".migration-backup";
+const MIGRATION_PROGRESS_EVENT: &str = "migration://progress";
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("数据迁移 {version}（{name}）失败，已回滚：{message}")]
+    Failed {
+        version: u32,
+        name: &'static str,
+        message: String,
+    },
+    #[error("数据版本 {0} 高于当前应用支持的版本，请升级应用")]
+    TooNew(u32),
+}
+
+/// 一次存储结构变更。`down` 用于回滚文件布局等无法通过 JSON 快照恢复的改动。
+struct Migration {
+    version: u32,
+    name: &'static str,
+    up: fn(&Path) -> io::Result<()>,
+    down: Option<fn(&Path) -> io::Result<()>>,
+}
+
+/// 按版本号递增排列，只能追加，不能修改已发布的迁移。
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "move_speech_models",
+        up: move_speech_models_up,
+        down: Some(move_speech_models_down),
+    },
+    Migration {
+        version: 2,
+        name: "utc_session_timestamps",
+        up: utc_session_timestamps_up,
+        down: None,
+    },
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SchemaState {
+    version: u32,
+    #[serde(default)]
+    history: Vec<AppliedMigration>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AppliedMigration {
+    version: u32,
+    name: String,
+    applied_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    Running,
+    Completed,
+    Failed,
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationProgressEvent {
+    pub current: usize,
+    pub total: usize,
+    pub version: u32,
+    pub name: String,
+    pub status: MigrationStatus,
+    pub message: Option<String>,
+}
+
+fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// 在 setup 中执行所有未应用的迁移；任一步失败时按相反顺序回滚并恢复 JSON 快照。
+pub fn run_pending(app: &AppHandle) -> Result<u32, MigrationError> {
+    let data_dir = app.path().app_local_data_dir()?;
+    let schema_file = data_dir.join(SCHEMA_FILENAME);
+    let mut state: SchemaState = if schema_file.exists() {
+        serde_json::from_slice(&fs::read(&schema_file)?)?
+    } else {
+        SchemaState::default()
+    };
+    if state.version > latest_version() {
+        return Err(MigrationError::TooNew(state.version));
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > state.version)
+        .collect();
+    if pending.is_empty() {
+        return Ok(state.version);
+    }
+
+    let backup_dir = data_dir.join(BACKUP_DIR);
+    snapshot_json_files(&data_dir, &backup_dir)?;
+
+    let total = pending.len();
+    let emit = |current: usize, migration: &Migration, status, message: Option<String>| {
+        let _ = app.emit(
+            MIGRATION_PROGRESS_EVENT,
+            MigrationProgressEvent {
+                current,
+                total,
+                version: migration.version,
+                name: migration.name.to_string(),
+                status,
+                message,
+            },
+        );
+    };
+
+    for (index, migration) in pending.iter().enumerate() {
+        emit(index + 1, migration, MigrationStatus::Running, None);
+        if let Err(err) = (migration.up)(&data_dir) {
+            log::error!(
+                "migration {} ({}) failed: {err}",
+                migration.version,
+                migration.name
+            );
+            emit(
+                index + 1,
+                migration,
+                MigrationStatus::Failed,
+                Some(err.to_string()),
+            );
+
+            for applied in pending[..=index].iter().rev() {
+                if let Some(down) = applied.down {
+                    if let Err(down_err) = down(&data_dir) {
+                        log::error!(
+                            "rollback of migration {} failed: {down_err}",
+                            applied.version
+                        );
+                    }
+                }
+            }
+            restore_json_files(&backup_dir, &data_dir)?;
+            let _ = fs::remove_dir_all(&backup_dir);
+            emit(index + 1, migration, MigrationStatus::RolledBack, None);
+
+            return Err(MigrationError::Failed {
+                version: migration.version,
+                name: migration.name,
+                message: err.to_string(),
+            });
+        }
+
+        state.version = migration.version;
+        state.history.push(AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: now_utc(),
+        });
+        emit(index + 1, migration, MigrationStatus::Completed, None);
+    }
+
+    write_atomic(&schema_file, &serde_json::to_vec_pretty(&state)?)?;
+    let _ = fs::remove_dir_all(&backup_dir);
+    Ok(state.version)
+}
+
+fn json_files(data_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![data_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|name| name != BACKUP_DIR) {
+                    pending.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn snapshot_json_files(data_dir: &Path, backup_dir: &Path) -> io::Result<()> {
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir)?;
+    }
+    for path in json_files(data_dir)? {
+        let Ok(relative) = path.strip_prefix(data_dir) else {
+            continue;
+        };
+        let target = backup_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&path, target)?;
+    }
+    Ok(())
+}
+
+fn restore_json_files(backup_dir: &Path, data_dir: &Path) -> io::Result<()> {
+    if !backup_dir.exists() {
+        return Ok(());
+    }
+    for path in json_files(backup_dir)? {
+        let Ok(relative) = path.strip_prefix(backup_dir) else {
+            continue;
+        };
+        let target = data_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&path, target)?;
+    }
+    Ok(())
+}
+
+const LEGACY_MODEL_PATH: &str = "speech/ggml-small.bin";
+const MODELS_MODEL_PATH: &str = "speech/models/ggml-small.bin";
+
+/// 早期版本把模型直接放在 speech 目录下，现统一放到 speech/models。
+fn move_speech_models_up(data_dir: &Path) -> io::Result<()> {
+    let legacy = data_dir.join(LEGACY_MODEL_PATH);
+    let target = data_dir.join(MODELS_MODEL_PATH);
+    if legacy.exists() && !target.exists() {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(legacy, target)?;
+    }
+    Ok(())
+}
+
+fn move_speech_models_down(data_dir: &Path) -> io::Result<()> {
+    let legacy = data_dir.join(LEGACY_MODEL_PATH);
+    let target = data_dir.join(MODELS_MODEL_PATH);
+    if target.exists() && !legacy.exists() {
+        fs::rename(target, legacy)?;
+    }
+    Ok(())
+}
+
+const SESSIONS_PATH: &str = "speech/sessions.json";
+const UNTRANSCRIBED_DIR: &str = "speech/untranscribed";
+
+/// 早期版本按本地时区写入 `created_at`，统一改写为 UTC，保证字符串排序与时间顺序一致。
+fn utc_session_timestamps_up(data_dir: &Path) -> io::Result<()> {
+    rewrite_created_at(&data_dir.join(SESSIONS_PATH))?;
+    let untranscribed = data_dir.join(UNTRANSCRIBED_DIR);
+    if untranscribed.is_dir() {
+        for entry in fs::read_dir(untranscribed)?.flatten() {
+            rewrite_created_at(&entry.path().join("meta.json"))?;
+        }
+    }
+    Ok(())
+}
+
+fn rewrite_created_at(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut value: serde_json::Value = serde_json::from_slice(&fs::read(path)?)?;
+    let mut changed = false;
+    let records = match &mut value {
+        serde_json::Value::Array(items) => items.iter_mut().collect(),
+        item => vec![item],
+    };
+    for record in records {
+        let Some(created_at) = record.get_mut("created_at") else {
+            continue;
+        };
+        let Some(instant) = created_at.as_str().and_then(parse_instant) else {
+            continue;
+        };
+        let normalized = to_utc_string(&instant);
+        if created_at.as_str() != Some(normalized.as_str()) {
+            *created_at = serde_json::Value::String(normalized);
+            changed = true;
+        }
+    }
+    if changed {
+        write_atomic(path, &serde_json::to_vec_pretty(&value)?)?;
+    }
+    Ok(())
+}
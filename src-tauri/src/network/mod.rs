@@ -0,0 +1,79 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use thiserror::Error;
+
+use crate::storage::write_atomic;
+
+const SETTINGS_FILENAME: &str = "network.json";
+const OFFLINE_CHANGED_EVENT: &str = "network://offline-changed";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Error)]
+pub enum NetworkError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    /// 开启后所有需要联网的功能（模型下载、更新检查、日历订阅、团队预设）直接报错，不发起请求。
+    #[serde(default)]
+    pub offline: bool,
+}
+
+fn settings_file(app: &AppHandle) -> Result<PathBuf, NetworkError> {
+    Ok(app.path().app_local_data_dir()?.join(SETTINGS_FILENAME))
+}
+
+/// 启动时读取离线设置；文件缺失或损坏时按联网处理。
+pub fn load_settings(app: &AppHandle) {
+    let settings = settings_file(app).and_then(|path| {
+        if path.exists() {
+            Ok(serde_json::from_slice::<NetworkSettings>(&fs::read(path)?)?)
+        } else {
+            Ok(NetworkSettings::default())
+        }
+    });
+    match settings {
+        Ok(settings) => OFFLINE.store(settings.offline, Ordering::Relaxed),
+        Err(err) => log::warn!("failed to load network settings: {err}"),
+    }
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+fn current_settings() -> NetworkSettings {
+    NetworkSettings {
+        offline: is_offline(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_network_settings() -> Result<NetworkSettings, String> {
+    Ok(current_settings())
+}
+
+/// 切换离线模式并通知所有窗口；正在进行的下载不受影响，可用取消下载命令中止。
+#[tauri::command]
+pub async fn set_offline_mode(app: AppHandle, offline: bool) -> Result<NetworkSettings, String> {
+    let settings = NetworkSettings { offline };
+    settings_file(&app)
+        .and_then(|path| Ok(write_atomic(&path, &serde_json::to_vec_pretty(&settings)?)?))
+        .map_err(|e| e.to_string())?;
+    OFFLINE.store(offline, Ordering::Relaxed);
+    let _ = app.emit(OFFLINE_CHANGED_EVENT, current_settings());
+    Ok(settings)
+}
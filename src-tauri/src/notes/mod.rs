@@ -0,0 +1,362 @@
+use std::{io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::datetime::now_utc;
+use crate::links::{EntityKind, EntityLink, EntityRef, LinkStore};
+use crate::search::{SearchDocument, SearchEntityKind, SearchIndexHandle};
+use crate::speech::{RecordingStatus, SpeechLanguage, SpeechManager, SpeechRecorder};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+
+const NOTES_FILENAME: &str = "notes.json";
+const NOTE_CHANGED_EVENT: &str = "note://changed";
+const QUICK_NOTE_LABEL: &str = "quick-note";
+pub const QUICK_NOTE_SHORTCUT: &str = "CommandOrControl+Shift+N";
+
+#[derive(Debug, Error)]
+pub enum NoteError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未找到指定的笔记：{0}")]
+    NotFound(String),
+    #[error("笔记内容不能为空")]
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NoteSource {
+    #[default]
+    Typed,
+    /// 按住录音后由语音模型转写。
+    Voice,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub source: NoteSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f32>,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 合并手动选择的标签和正文中的 `#标签`，保持先后顺序并去重。
+fn collect_tags(text: &str, tags: Vec<String>) -> Vec<String> {
+    let inline = text
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(str::to_string);
+    let mut collected: Vec<String> = Vec::new();
+    for tag in tags.into_iter().chain(inline) {
+        let tag = tag.trim().trim_start_matches('#').to_string();
+        if !tag.is_empty() && !collected.contains(&tag) {
+            collected.push(tag);
+        }
+    }
+    collected
+}
+
+pub struct NoteStore {
+    notes_file: PathBuf,
+    notes: async_runtime::Mutex<Vec<Note>>,
+    indexer: SearchIndexHandle,
+}
+
+impl NoteStore {
+    pub fn new(app: &AppHandle, indexer: SearchIndexHandle) -> Result<Self, NoteError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let notes_file = base_dir.join(NOTES_FILENAME);
+        let notes = load_json(&notes_file, Vec::new);
+        Ok(Self {
+            notes_file,
+            notes: async_runtime::Mutex::new(notes),
+            indexer,
+        })
+    }
+
+    pub async fn has_note(&self, note_id: &str) -> bool {
+        let guard = self.notes.lock().await;
+        guard.iter().any(|note| note.id == note_id)
+    }
+
+    /// 置顶的在前，其余按创建时间从新到旧。
+    pub async fn all(&self) -> Vec<Note> {
+        let mut notes = self.notes.lock().await.clone();
+        notes.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
+        notes
+    }
+
+    pub async fn add(
+        &self,
+        text: &str,
+        tags: Vec<String>,
+        source: NoteSource,
+        duration_seconds: Option<f32>,
+    ) -> Result<Note, NoteError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(NoteError::Empty);
+        }
+        let now = now_utc();
+        let note = Note {
+            id: Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            tags: collect_tags(text, tags),
+            source,
+            duration_seconds,
+            pinned: false,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        let mut guard = self.notes.lock().await;
+        guard.push(note.clone());
+        self.persist(&guard)?;
+        Ok(note)
+    }
+
+    pub async fn set_pinned(&self, note_id: &str, pinned: bool) -> Result<Note, NoteError> {
+        let mut guard = self.notes.lock().await;
+        let note = guard
+            .iter_mut()
+            .find(|note| note.id == note_id)
+            .ok_or_else(|| NoteError::NotFound(note_id.to_string()))?;
+        note.pinned = pinned;
+        note.updated_at = now_utc();
+        let note = note.clone();
+        self.persist(&guard)?;
+        Ok(note)
+    }
+
+    pub async fn delete(&self, note_id: &str) -> Result<(), NoteError> {
+        let mut guard = self.notes.lock().await;
+        let before = guard.len();
+        guard.retain(|note| note.id != note_id);
+        if guard.len() == before {
+            return Err(NoteError::NotFound(note_id.to_string()));
+        }
+        self.persist(&guard)
+    }
+
+    /// 合并数据包中的笔记，已存在的 id 保持不变，返回新增数量。
+    pub async fn merge(&self, notes: Vec<Note>) -> Result<usize, NoteError> {
+        let mut guard = self.notes.lock().await;
+        let before = guard.len();
+        for note in notes {
+            if !guard.iter().any(|existing| existing.id == note.id) {
+                guard.push(note);
+            }
+        }
+        let added = guard.len() - before;
+        if added > 0 {
+            self.persist(&guard)?;
+        }
+        Ok(added)
+    }
+
+    fn persist(&self, notes: &[Note]) -> Result<(), NoteError> {
+        write_atomic(&self.notes_file, &serde_json::to_vec_pretty(notes)?)?;
+        self.indexer.sync(
+            SearchEntityKind::Note,
+            notes.iter().map(note_search_document).collect(),
+        );
+        Ok(())
+    }
+
+    pub async fn search_documents(&self) -> Vec<SearchDocument> {
+        let guard = self.notes.lock().await;
+        guard.iter().map(note_search_document).collect()
+    }
+
+    pub(crate) async fn notify_changed(&self, app: &AppHandle) {
+        let _ = app.emit(NOTE_CHANGED_EVENT, self.all().await);
+    }
+}
+
+/// 笔记没有标题，取第一行作为搜索结果的标题。
+fn note_search_document(note: &Note) -> SearchDocument {
+    SearchDocument {
+        kind: SearchEntityKind::Note,
+        id: note.id.clone(),
+        title: note.text.lines().next().unwrap_or_default().to_string(),
+        body: format!("{}\n{}", note.text, note.tags.join(" ")),
+        updated_at: note.updated_at.clone(),
+    }
+}
+
+fn show_quick_note_window(app: &AppHandle) -> Result<(), NoteError> {
+    if let Some(window) = app.get_webview_window(QUICK_NOTE_LABEL) {
+        window.show()?;
+        window.set_focus()?;
+        return Ok(());
+    }
+
+    let window = tauri::WebviewWindowBuilder::new(
+        app,
+        QUICK_NOTE_LABEL,
+        tauri::WebviewUrl::App("/quick-note".into()),
+    )
+    .title("Quick Note")
+    .inner_size(420.0, 240.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .center()
+    .build()?;
+    window.set_focus()?;
+    Ok(())
+}
+
+/// 注册打开速记窗口的全局快捷键，快捷键被其他应用占用时只记录日志。
+#[cfg(desktop)]
+pub fn register_quick_note(app: &AppHandle) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let registered =
+        app.global_shortcut()
+            .on_shortcut(QUICK_NOTE_SHORTCUT, |app, _shortcut, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+                if let Err(err) = show_quick_note_window(app) {
+                    log::error!("failed to open quick note window: {err}");
+                }
+            });
+    if let Err(err) = registered {
+        log::warn!("failed to register quick note shortcut {QUICK_NOTE_SHORTCUT}: {err}");
+    }
+}
+
+#[tauri::command]
+pub async fn list_notes(notes: tauri::State<'_, NoteStore>) -> Result<Vec<Note>, String> {
+    Ok(notes.all().await)
+}
+
+/// 保存文字速记，正文中的 `#标签` 会合并到 `tags`。
+#[tauri::command]
+pub async fn add_quick_note(
+    app: AppHandle,
+    notes: tauri::State<'_, NoteStore>,
+    text: String,
+    tags: Option<Vec<String>>,
+) -> Result<Note, String> {
+    let note = notes
+        .add(&text, tags.unwrap_or_default(), NoteSource::Typed, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    notes.notify_changed(&app).await;
+    Ok(note)
+}
+
+/// 按下录音键时调用，松开时调用 `finish_voice_note`。
+#[tauri::command]
+pub async fn start_voice_note(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+) -> Result<RecordingStatus, String> {
+    recorder.start(&speech).await.map_err(|e| e.to_string())
+}
+
+/// 停止录音并用快速模型转写为速记；录音只用于转写，不保留。
+#[tauri::command]
+pub async fn finish_voice_note(
+    app: AppHandle,
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+    notes: tauri::State<'_, NoteStore>,
+    language: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Note, String> {
+    let language =
+        SpeechLanguage::try_from(language.as_deref().unwrap_or("zh")).map_err(|e| e.to_string())?;
+    let recorded = recorder.stop(&speech).map_err(|e| e.to_string())?;
+    let memo = speech
+        .take_recorded_memo(&recorded.session_id)
+        .map_err(|e| e.to_string())?;
+    let text = speech
+        .transcribe_memo(memo, language)
+        .await
+        .map_err(|e| e.to_string())?;
+    let note = notes
+        .add(
+            &text,
+            tags.unwrap_or_default(),
+            NoteSource::Voice,
+            Some(recorded.duration_seconds as f32),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    notes.notify_changed(&app).await;
+    Ok(note)
+}
+
+/// 松开前取消（如拖出按钮），丢弃已录的音频。
+#[tauri::command]
+pub async fn cancel_voice_note(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+) -> Result<(), String> {
+    let recorded = recorder.stop(&speech).map_err(|e| e.to_string())?;
+    speech
+        .take_recorded_memo(&recorded.session_id)
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn pin_note(
+    app: AppHandle,
+    notes: tauri::State<'_, NoteStore>,
+    id: String,
+    pinned: bool,
+) -> Result<Note, String> {
+    let note = notes
+        .set_pinned(&id, pinned)
+        .await
+        .map_err(|e| e.to_string())?;
+    notes.notify_changed(&app).await;
+    Ok(note)
+}
+
+#[tauri::command]
+pub async fn delete_note(
+    app: AppHandle,
+    notes: tauri::State<'_, NoteStore>,
+    links: tauri::State<'_, LinkStore>,
+    id: String,
+) -> Result<Vec<EntityLink>, String> {
+    notes.delete(&id).await.map_err(|e| e.to_string())?;
+    notes.notify_changed(&app).await;
+    links
+        .remove_entity(&EntityRef {
+            kind: EntityKind::Note,
+            id,
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn open_quick_note(app: AppHandle) -> Result<(), String> {
+    show_quick_note_window(&app).map_err(|e| e.to_string())
+}
@@ -0,0 +1,207 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use thiserror::Error;
+
+use crate::journal::{JournalError, OperationJournal};
+use crate::links::{LinkError, LinkStore};
+use crate::reminders::{self, ReminderAction, ReminderEngine, ReminderError, ReminderSource};
+use crate::speech::{SpeechError, SpeechManager, SpeechSession};
+
+const NOTIFICATION_ACTION_EVENT: &str = "notification://action";
+const TODO_REMINDER_ACTIONS: &str = "reminder-todo";
+const HABIT_REMINDER_ACTIONS: &str = "reminder-habit";
+const CALENDAR_REMINDER_ACTIONS: &str = "reminder-calendar";
+const TRANSCRIPTION_ACTIONS: &str = "transcription";
+/// 通知的 `extra` 中记录操作对象（提醒 id 或转写会话 id）的键。
+pub const TARGET_EXTRA: &str = "target";
+
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未知的通知操作：{0}")]
+    UnknownAction(String),
+    #[error(transparent)]
+    Reminder(#[from] ReminderError),
+    #[error(transparent)]
+    Speech(#[from] SpeechError),
+    #[error(transparent)]
+    Link(#[from] LinkError),
+    #[error(transparent)]
+    Journal(#[from] JournalError),
+}
+
+/// 转写完成通知上的操作。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionAction {
+    Open,
+    Delete,
+}
+
+/// 通知操作处理完成后发给前端，用于跳转到对应页面或刷新列表。
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationActionEvent {
+    pub action_type_id: String,
+    pub action_id: String,
+    pub target: String,
+}
+
+pub fn reminder_action_type(source: ReminderSource) -> &'static str {
+    match source {
+        ReminderSource::Todo => TODO_REMINDER_ACTIONS,
+        ReminderSource::Habit => HABIT_REMINDER_ACTIONS,
+        ReminderSource::Calendar => CALENDAR_REMINDER_ACTIONS,
+    }
+}
+
+/// 向系统注册通知按钮。目前只有移动端的通知插件支持按钮，桌面端显示普通通知，
+/// 操作仍可在应用内通过 `reminder://fired` 等事件完成。
+#[cfg(mobile)]
+pub fn register_action_types(app: &AppHandle) {
+    use serde_json::json;
+    use tauri_plugin_notification::ActionType;
+
+    let action = |id: &str, title: &str, destructive: bool| {
+        json!({
+            "id": id,
+            "title": title,
+            "requiresAuthentication": false,
+            "foreground": id == "open",
+            "destructive": destructive,
+            "input": false,
+        })
+    };
+    let action_type = |id: &str, actions: Vec<serde_json::Value>| {
+        json!({
+            "id": id,
+            "actions": actions,
+            "customDismissAction": false,
+            "allowInCarPlay": false,
+            "hiddenPreviewsShowTitle": false,
+            "hiddenPreviewsShowSubtitle": false,
+        })
+    };
+    let types = json!([
+        action_type(
+            TODO_REMINDER_ACTIONS,
+            vec![
+                action("complete", "完成", false),
+                action("snooze", "稍后提醒", false),
+            ],
+        ),
+        action_type(
+            HABIT_REMINDER_ACTIONS,
+            vec![
+                action("check_in", "打卡", false),
+                action("snooze", "稍后提醒", false),
+            ],
+        ),
+        action_type(
+            CALENDAR_REMINDER_ACTIONS,
+            vec![action("snooze", "稍后提醒", false)],
+        ),
+        action_type(
+            TRANSCRIPTION_ACTIONS,
+            vec![
+                action("open", "打开", false),
+                action("delete", "删除", true)
+            ],
+        ),
+    ]);
+    let registered = serde_json::from_value::<Vec<ActionType>>(types)
+        .map_err(|err| err.to_string())
+        .and_then(|types| {
+            app.notification()
+                .register_action_types(types)
+                .map_err(|err| err.to_string())
+        });
+    if let Err(err) = registered {
+        log::warn!("failed to register notification actions: {err}");
+    }
+}
+
+/// 转写完成时提醒用户；主窗口在前台时用户已经能看到结果，不再弹出通知。
+pub fn notify_transcription_finished(app: &AppHandle, session: &SpeechSession) {
+    let focused = app
+        .get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false);
+    if focused {
+        return;
+    }
+    if let Err(err) = app
+        .notification()
+        .builder()
+        .title("转写完成")
+        .body(&session.title)
+        .action_type_id(TRANSCRIPTION_ACTIONS)
+        .extra(TARGET_EXTRA, &session.id)
+        .show()
+    {
+        log::warn!("failed to show transcription notification: {err}");
+    }
+}
+
+fn parse_action<T: DeserializeOwned>(action_id: &str) -> Result<T, NotificationError> {
+    serde_json::from_value(serde_json::Value::String(action_id.to_string()))
+        .map_err(|_| NotificationError::UnknownAction(action_id.to_string()))
+}
+
+async fn run_action(
+    app: &AppHandle,
+    action_type_id: &str,
+    action_id: &str,
+    target: &str,
+) -> Result<(), NotificationError> {
+    match action_type_id {
+        TODO_REMINDER_ACTIONS | HABIT_REMINDER_ACTIONS | CALENDAR_REMINDER_ACTIONS => {
+            let action: ReminderAction = parse_action(action_id)?;
+            let reminder = app.state::<ReminderEngine>().get(target).await?;
+            reminders::run_action(app, &reminder, action, reminders::DEFAULT_SNOOZE_MINUTES)
+                .await?;
+        }
+        TRANSCRIPTION_ACTIONS => match parse_action(action_id)? {
+            TranscriptionAction::Open => {
+                if let Some(window) = app.get_webview_window("main") {
+                    window.show()?;
+                    window.set_focus()?;
+                }
+            }
+            TranscriptionAction::Delete => {
+                app.state::<OperationJournal>()
+                    .delete_session(
+                        &app.state::<SpeechManager>(),
+                        &app.state::<LinkStore>(),
+                        target,
+                    )
+                    .await?;
+            }
+        },
+        _ => return Err(NotificationError::UnknownAction(action_type_id.to_string())),
+    }
+    Ok(())
+}
+
+/// 前端收到系统通知按钮的回调后原样转发到这里，由后端交给对应模块处理。
+#[tauri::command]
+pub async fn handle_notification_action(
+    app: AppHandle,
+    action_type_id: String,
+    action_id: String,
+    target: String,
+) -> Result<(), String> {
+    run_action(&app, &action_type_id, &action_id, &target)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        NOTIFICATION_ACTION_EVENT,
+        NotificationActionEvent {
+            action_type_id,
+            action_id,
+            target,
+        },
+    );
+    Ok(())
+}
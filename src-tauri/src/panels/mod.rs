@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Window, WindowEvent};
+use thiserror::Error;
+
+use crate::datetime::now_utc;
+use crate::speech::SpeechManager;
+
+const PANEL_LABEL_PREFIX: &str = "panel-";
+const PANELS_CHANGED_EVENT: &str = "panels://changed";
+const PANEL_ATTACHED_EVENT: &str = "panels://attached";
+
+#[derive(Debug, Error)]
+pub enum PanelError {
+    #[error("会话不存在：{0}")]
+    SessionNotFound(String),
+    #[error("面板不存在：{0}")]
+    PanelNotFound(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelKind {
+    SessionDetail,
+    TranscriptEditor,
+}
+
+impl PanelKind {
+    fn slug(self) -> &'static str {
+        match self {
+            PanelKind::SessionDetail => "session",
+            PanelKind::TranscriptEditor => "transcript",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            PanelKind::SessionDetail => "会话详情",
+            PanelKind::TranscriptEditor => "文字稿编辑",
+        }
+    }
+}
+
+/// 分离出去的面板窗口；`state` 是前端交接的界面状态（播放位置、未保存的编辑等），
+/// 新窗口打开时读取，关闭或收回时随 `panels://attached` 交还主窗口。
+#[derive(Debug, Clone, Serialize)]
+pub struct DetachedPanel {
+    pub label: String,
+    pub panel: PanelKind,
+    pub session_id: String,
+    pub state: Value,
+    pub opened_at: String,
+}
+
+#[derive(Default)]
+pub struct PanelRegistry {
+    panels: Mutex<HashMap<String, DetachedPanel>>,
+}
+
+impl PanelRegistry {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, DetachedPanel>> {
+        self.panels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn list(&self) -> Vec<DetachedPanel> {
+        let mut panels: Vec<DetachedPanel> = self.lock().values().cloned().collect();
+        panels.sort_by(|a, b| a.opened_at.cmp(&b.opened_at));
+        panels
+    }
+
+    pub fn get(&self, label: &str) -> Option<DetachedPanel> {
+        self.lock().get(label).cloned()
+    }
+
+    fn insert(&self, panel: DetachedPanel) {
+        self.lock().insert(panel.label.clone(), panel);
+    }
+
+    fn update_state(&self, label: &str, state: Value) -> Result<DetachedPanel, PanelError> {
+        let mut guard = self.lock();
+        let panel = guard
+            .get_mut(label)
+            .ok_or_else(|| PanelError::PanelNotFound(label.to_string()))?;
+        panel.state = state;
+        Ok(panel.clone())
+    }
+
+    fn remove(&self, label: &str) -> Option<DetachedPanel> {
+        self.lock().remove(label)
+    }
+}
+
+fn panel_label(panel: PanelKind, session_id: &str) -> String {
+    format!("{PANEL_LABEL_PREFIX}{}-{session_id}", panel.slug())
+}
+
+fn notify_changed(app: &AppHandle, registry: &PanelRegistry) {
+    let _ = app.emit(PANELS_CHANGED_EVENT, registry.list());
+}
+
+/// 面板从登记表中移除后把最后的状态交还主窗口。
+fn hand_back(app: &AppHandle, registry: &PanelRegistry, panel: DetachedPanel) {
+    let _ = app.emit(PANEL_ATTACHED_EVENT, &panel);
+    notify_changed(app, registry);
+}
+
+/// 面板窗口被直接关闭时同样交还状态。
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if !matches!(event, WindowEvent::Destroyed) || !window.label().starts_with(PANEL_LABEL_PREFIX) {
+        return;
+    }
+    let app = window.app_handle();
+    let Some(registry) = app.try_state::<PanelRegistry>() else {
+        return;
+    };
+    if let Some(panel) = registry.remove(window.label()) {
+        hand_back(app, &registry, panel);
+    }
+}
+
+/// 把会话详情或文字稿编辑器分离到独立窗口，同一面板已分离时更新状态并聚焦。
+#[tauri::command]
+pub async fn detach_panel(
+    app: AppHandle,
+    speech: tauri::State<'_, SpeechManager>,
+    registry: tauri::State<'_, PanelRegistry>,
+    panel: PanelKind,
+    session_id: String,
+    state: Option<Value>,
+) -> Result<DetachedPanel, String> {
+    if !speech.has_session(&session_id).await {
+        return Err(PanelError::SessionNotFound(session_id).to_string());
+    }
+    let label = panel_label(panel, &session_id);
+    let state = state.unwrap_or(Value::Null);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        let detached = registry
+            .update_state(&label, state)
+            .map_err(|e| e.to_string())?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(detached);
+    }
+
+    let detached = DetachedPanel {
+        label: label.clone(),
+        panel,
+        session_id: session_id.clone(),
+        state,
+        opened_at: now_utc(),
+    };
+    // 先登记再建窗口，新窗口加载后即可读到交接的状态
+    registry.insert(detached.clone());
+    let built = tauri::WebviewWindowBuilder::new(
+        &app,
+        &label,
+        tauri::WebviewUrl::App(
+            format!("/speech/panel?panel={}&session={session_id}", panel.slug()).into(),
+        ),
+    )
+    .title(panel.title())
+    .inner_size(720.0, 640.0)
+    .min_inner_size(420.0, 360.0)
+    .resizable(true)
+    .build();
+    if let Err(err) = built {
+        registry.remove(&label);
+        return Err(err.to_string());
+    }
+    notify_changed(&app, &registry);
+    Ok(detached)
+}
+
+/// 收回面板：关闭窗口并通过 `panels://attached` 交还最后的状态。
+#[tauri::command]
+pub fn attach_panel(
+    app: AppHandle,
+    registry: tauri::State<'_, PanelRegistry>,
+    label: String,
+) -> Result<DetachedPanel, String> {
+    let panel = registry
+        .remove(&label)
+        .ok_or_else(|| PanelError::PanelNotFound(label.clone()).to_string())?;
+    if let Some(window) = app.get_webview_window(&label) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    hand_back(&app, &registry, panel.clone());
+    Ok(panel)
+}
+
+/// 分离窗口中的界面状态变化时调用，收回时交还的是最后一次保存的状态。
+#[tauri::command]
+pub fn update_panel_state(
+    registry: tauri::State<'_, PanelRegistry>,
+    label: String,
+    state: Value,
+) -> Result<DetachedPanel, String> {
+    registry
+        .update_state(&label, state)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_detached_panel(
+    registry: tauri::State<'_, PanelRegistry>,
+    label: String,
+) -> Result<Option<DetachedPanel>, String> {
+    Ok(registry.get(&label))
+}
+
+#[tauri::command]
+pub fn list_detached_panels(
+    registry: tauri::State<'_, PanelRegistry>,
+) -> Result<Vec<DetachedPanel>, String> {
+    Ok(registry.list())
+}
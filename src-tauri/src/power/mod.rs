@@ -0,0 +1,61 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+
+use crate::backup::BackupManager;
+use crate::badge;
+use crate::calendar::CalendarManager;
+use crate::datetime::to_utc_string;
+use crate::reminders;
+use crate::storage::StorageGuard;
+
+const SYSTEM_RESUMED_EVENT: &str = "system://resumed";
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(10);
+/// 两次检查之间的系统时间比预期多出这么久，就认为期间系统休眠过。
+const SUSPEND_THRESHOLD: Duration = Duration::seconds(30);
+
+/// 系统从休眠中唤醒，前端的番茄钟等计时器据此按系统时间重新计算。
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemResumedEvent {
+    pub suspended_at: String,
+    pub resumed_at: String,
+    pub slept_seconds: i64,
+}
+
+/// 启动后台任务检测休眠和唤醒。各平台的休眠通知接口不同，这里比较两次检查之间的系统时间：
+/// 进程在休眠期间不会运行，唤醒后会发现系统时间跳过了一大段。
+pub fn spawn_resume_watcher(app: AppHandle) {
+    async_runtime::spawn(async move {
+        let expected = Duration::from_std(CHECK_INTERVAL).unwrap_or(Duration::zero());
+        let mut last_check = Utc::now();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let now = Utc::now();
+            if now - last_check - expected > SUSPEND_THRESHOLD {
+                on_resume(&app, last_check, now).await;
+            }
+            last_check = now;
+        }
+    });
+}
+
+/// 唤醒后立即重新计算所有定时任务，而不是等各自的定时器（可能已经漂移）到期。
+async fn on_resume(app: &AppHandle, suspended_at: DateTime<Utc>, resumed_at: DateTime<Utc>) {
+    let slept_seconds = (resumed_at - suspended_at).num_seconds();
+    log::info!("system resumed after about {slept_seconds}s, recomputing timers");
+    let _ = app.emit(
+        SYSTEM_RESUMED_EVENT,
+        SystemResumedEvent {
+            suspended_at: to_utc_string(&suspended_at),
+            resumed_at: to_utc_string(&resumed_at),
+            slept_seconds,
+        },
+    );
+    app.state::<StorageGuard>().refresh_heartbeat().await;
+    reminders::recompute(app).await;
+    app.state::<BackupManager>().run_if_due().await;
+    app.state::<CalendarManager>().refresh_and_sync(app).await;
+    badge::refresh(app).await;
+}
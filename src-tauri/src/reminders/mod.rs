@@ -0,0 +1,453 @@
+use std::{io, path::PathBuf, sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use thiserror::Error;
+use tokio::sync::Notify;
+
+use crate::calendar::CalendarManager;
+use crate::datetime::{parse_instant, to_utc_string};
+use crate::habits::{HabitError, HabitStore};
+use crate::notifications::{reminder_action_type, TARGET_EXTRA};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::todos::{TodoError, TodoStore};
+
+const REMINDERS_FILENAME: &str = "reminders.json";
+const REMINDER_FIRED_EVENT: &str = "reminder://fired";
+/// 即使没有临近的提醒也定期醒来按系统时间检查，休眠唤醒后最多延迟这么久。
+const MAX_SLEEP: StdDuration = StdDuration::from_secs(30);
+/// 超过这个时间才触发的提醒标记为错过。
+const LATE_AFTER: Duration = Duration::minutes(2);
+pub(crate) const DEFAULT_SNOOZE_MINUTES: u32 = 10;
+
+#[derive(Debug, Error)]
+pub enum ReminderError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未找到指定的提醒：{0}")]
+    NotFound(String),
+    #[error("该提醒不支持此操作")]
+    UnsupportedAction,
+    #[error(transparent)]
+    Todo(#[from] TodoError),
+    #[error(transparent)]
+    Habit(#[from] HabitError),
+}
+
+/// 注册提醒的模块，每个模块整体同步自己的提醒。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderSource {
+    Todo,
+    Habit,
+    Calendar,
+}
+
+impl ReminderSource {
+    fn key(self) -> &'static str {
+        match self {
+            ReminderSource::Todo => "todo",
+            ReminderSource::Habit => "habit",
+            ReminderSource::Calendar => "calendar",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderRepeat {
+    /// 每天同一本地时间，跨夏令时也不漂移。
+    Daily,
+}
+
+/// 通知上提供的操作，由 `run_reminder_action` 交给对应模块处理。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderAction {
+    Complete,
+    CheckIn,
+    Snooze,
+}
+
+/// 模块注册的一条提醒。
+#[derive(Debug, Clone)]
+pub struct ReminderSpec {
+    pub entity_id: String,
+    pub title: String,
+    pub body: String,
+    pub fire_at: DateTime<Utc>,
+    pub repeat: Option<ReminderRepeat>,
+    /// 过了这个时间仍未触发（如电脑一直休眠）就不再提醒，例如会议已经结束。
+    pub expires_at: Option<DateTime<Utc>>,
+    pub actions: Vec<ReminderAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub source: ReminderSource,
+    pub entity_id: String,
+    pub title: String,
+    pub body: String,
+    /// 模块注册的时间，用于判断同步时提醒是否被修改过。
+    pub scheduled_at: String,
+    /// 下一次触发的时间，重复和稍后提醒只改这个字段。
+    pub next_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<ReminderRepeat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<ReminderAction>,
+    #[serde(default)]
+    pub fired: bool,
+}
+
+impl Reminder {
+    fn next(&self) -> Option<DateTime<Utc>> {
+        parse_instant(&self.next_at)
+    }
+
+    fn pending(&self) -> bool {
+        !self.fired && self.next().is_some()
+    }
+
+    /// 重复提醒推进到 `now` 之后的下一次，休眠期间错过的多次只补一次；
+    /// 时刻取自注册时间，稍后提醒不会改变之后每天的提醒时刻。
+    fn advance_past(&mut self, now: DateTime<Utc>) {
+        let Some(ReminderRepeat::Daily) = self.repeat else {
+            return;
+        };
+        let (Some(scheduled), Some(mut next)) = (parse_instant(&self.scheduled_at), self.next())
+        else {
+            return;
+        };
+        let time = scheduled.with_timezone(&Local).time();
+        let mut date = next.with_timezone(&Local).date_naive();
+        while next <= now {
+            // 夏令时跳过的时刻没有对应的本地时间，顺延到下一天
+            if let Some(instant) = Local.from_local_datetime(&date.and_time(time)).earliest() {
+                next = instant.with_timezone(&Utc);
+            }
+            let Some(following) = date.succ_opt() else {
+                break;
+            };
+            date = following;
+        }
+        self.next_at = to_utc_string(&next);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReminderFiredEvent {
+    pub reminder: Reminder,
+    /// 比预定时间晚了较久（通常是休眠或关机期间错过的）。
+    pub late: bool,
+}
+
+pub struct ReminderEngine {
+    reminders_file: PathBuf,
+    reminders: async_runtime::Mutex<Vec<Reminder>>,
+    wake: Arc<Notify>,
+}
+
+impl ReminderEngine {
+    pub fn new(app: &AppHandle) -> Result<Self, ReminderError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let reminders_file = base_dir.join(REMINDERS_FILENAME);
+        let reminders = load_json(&reminders_file, Vec::new);
+        Ok(Self {
+            reminders_file,
+            reminders: async_runtime::Mutex::new(reminders),
+            wake: Arc::new(Notify::new()),
+        })
+    }
+
+    pub async fn all(&self) -> Vec<Reminder> {
+        let guard = self.reminders.lock().await;
+        guard.clone()
+    }
+
+    /// 用 `specs` 替换 `source` 的全部提醒；时间和重复方式未变的保留触发状态和稍后提醒的时间。
+    pub async fn sync_source(
+        &self,
+        source: ReminderSource,
+        specs: Vec<ReminderSpec>,
+    ) -> Result<(), ReminderError> {
+        let now = Utc::now();
+        let mut guard = self.reminders.lock().await;
+        let mut previous: Vec<Reminder> = Vec::new();
+        guard.retain(|reminder| {
+            if reminder.source == source {
+                previous.push(reminder.clone());
+                false
+            } else {
+                true
+            }
+        });
+        for spec in specs {
+            let id = format!("{}:{}", source.key(), spec.entity_id);
+            let scheduled_at = to_utc_string(&spec.fire_at);
+            let kept = previous.iter().find(|reminder| {
+                reminder.id == id
+                    && reminder.scheduled_at == scheduled_at
+                    && reminder.repeat == spec.repeat
+            });
+            let mut reminder = Reminder {
+                id,
+                source,
+                entity_id: spec.entity_id,
+                title: spec.title,
+                body: spec.body,
+                next_at: kept.map_or_else(|| scheduled_at.clone(), |r| r.next_at.clone()),
+                scheduled_at,
+                repeat: spec.repeat,
+                expires_at: spec.expires_at.as_ref().map(to_utc_string),
+                actions: spec.actions,
+                fired: kept.is_some_and(|r| r.fired),
+            };
+            if kept.is_none() {
+                // 新建的重复提醒从下一次开始，不补发今天已经过去的时间
+                reminder.advance_past(now);
+            }
+            guard.push(reminder);
+        }
+        self.persist(&guard)?;
+        drop(guard);
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    /// 稍后再提醒，只改变下一次触发时间，模块重新同步时保留。
+    pub async fn snooze(&self, reminder_id: &str, minutes: u32) -> Result<Reminder, ReminderError> {
+        let mut guard = self.reminders.lock().await;
+        let reminder = guard
+            .iter_mut()
+            .find(|reminder| reminder.id == reminder_id)
+            .ok_or_else(|| ReminderError::NotFound(reminder_id.to_string()))?;
+        reminder.next_at = to_utc_string(&(Utc::now() + Duration::minutes(i64::from(minutes))));
+        reminder.fired = false;
+        let snoozed = reminder.clone();
+        self.persist(&guard)?;
+        drop(guard);
+        self.wake.notify_one();
+        Ok(snoozed)
+    }
+
+    pub(crate) async fn get(&self, reminder_id: &str) -> Result<Reminder, ReminderError> {
+        let guard = self.reminders.lock().await;
+        guard
+            .iter()
+            .find(|reminder| reminder.id == reminder_id)
+            .cloned()
+            .ok_or_else(|| ReminderError::NotFound(reminder_id.to_string()))
+    }
+
+    async fn next_due(&self) -> Option<DateTime<Utc>> {
+        let guard = self.reminders.lock().await;
+        guard
+            .iter()
+            .filter(|reminder| reminder.pending())
+            .filter_map(Reminder::next)
+            .min()
+    }
+
+    /// 取出已到时间的提醒：重复提醒推进到下一次，其余标记为已触发；已过期的直接丢弃不提醒。
+    async fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<ReminderFiredEvent>, ReminderError> {
+        let mut guard = self.reminders.lock().await;
+        let mut due = Vec::new();
+        let mut changed = false;
+        for reminder in guard.iter_mut().filter(|reminder| reminder.pending()) {
+            let Some(next) = reminder.next().filter(|next| *next <= now) else {
+                continue;
+            };
+            changed = true;
+            let expired = reminder
+                .expires_at
+                .as_deref()
+                .and_then(parse_instant)
+                .is_some_and(|expires_at| expires_at <= now);
+            if !expired {
+                due.push(ReminderFiredEvent {
+                    reminder: reminder.clone(),
+                    late: now - next > LATE_AFTER,
+                });
+            }
+            if reminder.repeat.is_some() {
+                reminder.advance_past(now);
+            } else {
+                reminder.fired = true;
+            }
+        }
+        if changed {
+            self.persist(&guard)?;
+        }
+        Ok(due)
+    }
+
+    fn persist(&self, reminders: &[Reminder]) -> Result<(), ReminderError> {
+        write_atomic(&self.reminders_file, &serde_json::to_vec_pretty(reminders)?)?;
+        Ok(())
+    }
+
+    /// 启动后台任务：先让各模块同步一次提醒，之后睡到最近的提醒时间（最长 `MAX_SLEEP`）再按系统时间检查，
+    /// 不依赖休眠时会暂停的单调时钟，提醒有变化时立即醒来。
+    pub fn spawn_wake_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            sync_all(&app).await;
+            let wake = app.state::<ReminderEngine>().wake.clone();
+            loop {
+                fire_due(&app).await;
+                let wait = app
+                    .state::<ReminderEngine>()
+                    .next_due()
+                    .await
+                    .and_then(|next| (next - Utc::now()).to_std().ok())
+                    .map_or(MAX_SLEEP, |wait| wait.min(MAX_SLEEP));
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = wake.notified() => {}
+                }
+            }
+        });
+    }
+}
+
+/// 让各模块重新同步提醒并立即检查到期的提醒，用于休眠唤醒后。
+pub async fn recompute(app: &AppHandle) {
+    sync_all(app).await;
+    app.state::<ReminderEngine>().wake.notify_one();
+}
+
+async fn sync_all(app: &AppHandle) {
+    app.state::<TodoStore>().sync_reminders(app).await;
+    app.state::<HabitStore>().sync_reminders(app).await;
+    app.state::<CalendarManager>().sync_reminders(app).await;
+}
+
+async fn fire_due(app: &AppHandle) {
+    let due = match app.state::<ReminderEngine>().take_due(Utc::now()).await {
+        Ok(due) => due,
+        Err(err) => {
+            log::warn!("failed to check reminders: {err}");
+            return;
+        }
+    };
+    for event in due {
+        if !still_due(app, &event.reminder).await {
+            continue;
+        }
+        let title = if event.late {
+            format!("{}（错过的提醒）", event.reminder.title)
+        } else {
+            event.reminder.title.clone()
+        };
+        if let Err(err) = app
+            .notification()
+            .builder()
+            .title(title)
+            .body(&event.reminder.body)
+            .action_type_id(reminder_action_type(event.reminder.source))
+            .extra(TARGET_EXTRA, &event.reminder.id)
+            .show()
+        {
+            log::warn!("failed to show reminder {}: {err}", event.reminder.id);
+        }
+        let _ = app.emit(REMINDER_FIRED_EVENT, &event);
+        after_fired(app, &event.reminder).await;
+    }
+}
+
+/// 触发前再向模块确认一次，例如待办已完成、习惯今天已打卡就不再提醒。
+async fn still_due(app: &AppHandle, reminder: &Reminder) -> bool {
+    match reminder.source {
+        ReminderSource::Todo => {
+            app.state::<TodoStore>()
+                .reminder_due(&reminder.entity_id)
+                .await
+        }
+        ReminderSource::Habit => {
+            app.state::<HabitStore>()
+                .reminder_due(&reminder.entity_id)
+                .await
+        }
+        ReminderSource::Calendar => true,
+    }
+}
+
+async fn after_fired(app: &AppHandle, reminder: &Reminder) {
+    if reminder.source == ReminderSource::Todo {
+        app.state::<TodoStore>()
+            .mark_reminder_sent(app, &reminder.entity_id)
+            .await;
+    }
+}
+
+pub(crate) async fn run_action(
+    app: &AppHandle,
+    reminder: &Reminder,
+    action: ReminderAction,
+    minutes: u32,
+) -> Result<(), ReminderError> {
+    if !reminder.actions.contains(&action) {
+        return Err(ReminderError::UnsupportedAction);
+    }
+    match (reminder.source, action) {
+        // 待办的稍后提醒直接改待办的提醒时间，与待办页面的“稍后”一致
+        (ReminderSource::Todo, ReminderAction::Snooze) => app
+            .state::<TodoStore>()
+            .snooze_from_reminder(app, &reminder.entity_id, minutes)
+            .await
+            .map_err(Into::into),
+        (ReminderSource::Todo, ReminderAction::Complete) => app
+            .state::<TodoStore>()
+            .complete_from_reminder(app, &reminder.entity_id)
+            .await
+            .map_err(Into::into),
+        (ReminderSource::Habit, ReminderAction::CheckIn) => app
+            .state::<HabitStore>()
+            .check_in_from_reminder(app, &reminder.entity_id)
+            .await
+            .map_err(Into::into),
+        (_, ReminderAction::Snooze) => app
+            .state::<ReminderEngine>()
+            .snooze(&reminder.id, minutes)
+            .await
+            .map(drop),
+        _ => Err(ReminderError::UnsupportedAction),
+    }
+}
+
+#[tauri::command]
+pub async fn list_reminders(
+    reminders: tauri::State<'_, ReminderEngine>,
+) -> Result<Vec<Reminder>, String> {
+    Ok(reminders.all().await)
+}
+
+/// 执行提醒通知上的操作；`minutes` 只用于稍后提醒，缺省 10 分钟。
+#[tauri::command]
+pub async fn run_reminder_action(
+    app: AppHandle,
+    reminders: tauri::State<'_, ReminderEngine>,
+    id: String,
+    action: ReminderAction,
+    minutes: Option<u32>,
+) -> Result<(), String> {
+    let reminder = reminders.get(&id).await.map_err(|e| e.to_string())?;
+    run_action(
+        &app,
+        &reminder,
+        action,
+        minutes.unwrap_or(DEFAULT_SNOOZE_MINUTES),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,291 @@
+use std::{fs, io, path::PathBuf};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use thiserror::Error;
+
+use crate::datetime::now_utc;
+use crate::speech::{SpeechManager, SpeechSession};
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("无效的日期：{0}")]
+    InvalidDate(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportTimeEntry {
+    pub date: String,
+    pub duration_minutes: f64,
+}
+
+/// 待办目前保存在前端，生成周报时由前端传入快照。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportTodo {
+    pub title: String,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub time_entries: Vec<ReportTimeEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GenerateWeeklyReportPayload {
+    #[serde(default)]
+    pub week_start: Option<String>,
+    #[serde(default)]
+    pub todos: Vec<ReportTodo>,
+    #[serde(default)]
+    pub notify: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyActivity {
+    pub date: String,
+    pub transcribed_minutes: f64,
+    pub completed_todos: usize,
+    pub focus_minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub week_start: String,
+    pub week_end: String,
+    pub generated_at: String,
+    pub session_count: usize,
+    pub transcribed_minutes: f64,
+    pub completed_todos: Vec<String>,
+    pub focus_minutes: f64,
+    pub daily: Vec<DailyActivity>,
+    pub markdown: String,
+    pub html: String,
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, ReportError> {
+    let dir = app.path().app_local_data_dir()?.join("reports");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn report_path(app: &AppHandle, week_start: NaiveDate) -> Result<PathBuf, ReportError> {
+    Ok(reports_dir(app)?.join(format!("weekly-{}.json", week_start.format("%Y-%m-%d"))))
+}
+
+fn resolve_week_start(value: Option<&str>) -> Result<NaiveDate, ReportError> {
+    let date = match value {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| ReportError::InvalidDate(value.to_string()))?,
+        None => Local::now().date_naive(),
+    };
+    Ok(date - Duration::days(i64::from(date.weekday().num_days_from_monday())))
+}
+
+fn local_date(value: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|value| value.with_timezone(&Local).date_naive())
+}
+
+fn build_report(
+    week_start: NaiveDate,
+    sessions: &[SpeechSession],
+    todos: &[ReportTodo],
+) -> WeeklyReport {
+    let week_end = week_start + Duration::days(6);
+    let mut daily: Vec<DailyActivity> = (0..7)
+        .map(|offset| DailyActivity {
+            date: (week_start + Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string(),
+            transcribed_minutes: 0.0,
+            completed_todos: 0,
+            focus_minutes: 0.0,
+        })
+        .collect();
+    let day_index = |date: NaiveDate| -> Option<usize> {
+        if date < week_start || date > week_end {
+            None
+        } else {
+            Some((date - week_start).num_days() as usize)
+        }
+    };
+
+    let mut session_count = 0;
+    for session in sessions {
+        if let Some(index) = local_date(&session.created_at).and_then(day_index) {
+            session_count += 1;
+            daily[index].transcribed_minutes += f64::from(session.duration_seconds()) / 60.0;
+        }
+    }
+
+    let mut completed_todos = Vec::new();
+    for todo in todos {
+        if let Some(index) = todo
+            .completed_at
+            .as_deref()
+            .and_then(local_date)
+            .and_then(day_index)
+        {
+            daily[index].completed_todos += 1;
+            completed_todos.push(todo.title.clone());
+        }
+        for entry in &todo.time_entries {
+            if let Some(index) = local_date(&entry.date).and_then(day_index) {
+                daily[index].focus_minutes += entry.duration_minutes.max(0.0);
+            }
+        }
+    }
+
+    let transcribed_minutes = daily.iter().map(|day| day.transcribed_minutes).sum();
+    let focus_minutes = daily.iter().map(|day| day.focus_minutes).sum();
+
+    let mut report = WeeklyReport {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: week_end.format("%Y-%m-%d").to_string(),
+        generated_at: now_utc(),
+        session_count,
+        transcribed_minutes,
+        completed_todos,
+        focus_minutes,
+        daily,
+        markdown: String::new(),
+        html: String::new(),
+    };
+    report.markdown = render_markdown(&report);
+    report.html = render_html(&report);
+    report
+}
+
+fn render_markdown(report: &WeeklyReport) -> String {
+    let mut output = format!(
+        "# 周报 {} ~ {}\n\n- 转写会话：{} 个，共 {:.1} 分钟\n- 完成待办：{} 项\n- 专注时间：{:.1} 分钟\n\n",
+        report.week_start,
+        report.week_end,
+        report.session_count,
+        report.transcribed_minutes,
+        report.completed_todos.len(),
+        report.focus_minutes,
+    );
+    output
+        .push_str("| 日期 | 转写（分钟） | 完成待办 | 专注（分钟） |\n| --- | --- | --- | --- |\n");
+    for day in &report.daily {
+        output.push_str(&format!(
+            "| {} | {:.1} | {} | {:.1} |\n",
+            day.date, day.transcribed_minutes, day.completed_todos, day.focus_minutes
+        ));
+    }
+    if !report.completed_todos.is_empty() {
+        output.push_str("\n## 已完成\n\n");
+        for title in &report.completed_todos {
+            output.push_str(&format!("- {title}\n"));
+        }
+    }
+    output
+}
+
+fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(report: &WeeklyReport) -> String {
+    let mut output = format!(
+        "<h1>周报 {} ~ {}</h1>\n<ul>\n<li>转写会话：{} 个，共 {:.1} 分钟</li>\n<li>完成待办：{} 项</li>\n<li>专注时间：{:.1} 分钟</li>\n</ul>\n",
+        report.week_start,
+        report.week_end,
+        report.session_count,
+        report.transcribed_minutes,
+        report.completed_todos.len(),
+        report.focus_minutes,
+    );
+    output.push_str(
+        "<table>\n<tr><th>日期</th><th>转写（分钟）</th><th>完成待办</th><th>专注（分钟）</th></tr>\n",
+    );
+    for day in &report.daily {
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{:.1}</td><td>{}</td><td>{:.1}</td></tr>\n",
+            day.date, day.transcribed_minutes, day.completed_todos, day.focus_minutes
+        ));
+    }
+    output.push_str("</table>\n");
+    if !report.completed_todos.is_empty() {
+        output.push_str("<h2>已完成</h2>\n<ul>\n");
+        for title in &report.completed_todos {
+            output.push_str(&format!("<li>{}</li>\n", escape_html(title)));
+        }
+        output.push_str("</ul>\n");
+    }
+    output
+}
+
+async fn generate_report(
+    app: &AppHandle,
+    speech: &SpeechManager,
+    payload: GenerateWeeklyReportPayload,
+) -> Result<WeeklyReport, ReportError> {
+    let week_start = resolve_week_start(payload.week_start.as_deref())?;
+    let sessions = speech.list_sessions().await;
+    let report = build_report(week_start, &sessions, &payload.todos);
+
+    fs::write(
+        report_path(app, week_start)?,
+        serde_json::to_vec_pretty(&report)?,
+    )?;
+
+    if payload.notify.unwrap_or(true) {
+        let _ = app
+            .notification()
+            .builder()
+            .title("本周报告已生成")
+            .body(format!(
+                "转写 {:.0} 分钟，完成 {} 项待办",
+                report.transcribed_minutes,
+                report.completed_todos.len()
+            ))
+            .show();
+    }
+
+    Ok(report)
+}
+
+fn load_report(
+    app: &AppHandle,
+    week_start: Option<&str>,
+) -> Result<Option<WeeklyReport>, ReportError> {
+    let path = report_path(app, resolve_week_start(week_start)?)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read(path)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+#[tauri::command]
+pub async fn generate_weekly_report(
+    app: AppHandle,
+    speech: tauri::State<'_, SpeechManager>,
+    payload: Option<GenerateWeeklyReportPayload>,
+) -> Result<WeeklyReport, String> {
+    generate_report(&app, &speech, payload.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_weekly_report(
+    app: AppHandle,
+    week_start: Option<String>,
+) -> Result<Option<WeeklyReport>, String> {
+    load_report(&app, week_start.as_deref()).map_err(|e| e.to_string())
+}
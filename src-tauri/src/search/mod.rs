@@ -0,0 +1,475 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use crate::datetime::now_utc;
+use crate::notes::NoteStore;
+use crate::speech::SpeechManager;
+use crate::storage::prepare_dir;
+use crate::todos::TodoStore;
+
+const INDEX_FILENAME: &str = "search-index.json";
+/// 版本 2 起索引待办和笔记，旧索引启动时按版本不符重建。
+const INDEX_VERSION: u32 = 2;
+const SNIPPET_RADIUS: usize = 40;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntityKind {
+    Session,
+    Todo,
+    Note,
+}
+
+impl SearchEntityKind {
+    fn key(&self, id: &str) -> String {
+        match self {
+            SearchEntityKind::Session => format!("session:{id}"),
+            SearchEntityKind::Todo => format!("todo:{id}"),
+            SearchEntityKind::Note => format!("note:{id}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchDocument {
+    pub kind: SearchEntityKind,
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub kind: SearchEntityKind,
+    pub id: String,
+    pub title: String,
+    pub snippet: String,
+    pub updated_at: String,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchIndexStatus {
+    pub document_count: usize,
+    pub token_count: usize,
+    pub pending_updates: usize,
+    pub corrupted: bool,
+    pub last_updated: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    documents: Vec<SearchDocument>,
+}
+
+#[derive(Default)]
+struct IndexData {
+    documents: HashMap<String, SearchDocument>,
+    postings: HashMap<String, HashSet<String>>,
+    corrupted: bool,
+    last_updated: Option<String>,
+}
+
+impl IndexData {
+    fn insert(&mut self, document: SearchDocument) {
+        let key = document.kind.key(&document.id);
+        self.remove(&key);
+        let tokens = tokenize(&format!("{}\n{}", document.title, document.body));
+        for token in tokens {
+            self.postings.entry(token).or_default().insert(key.clone());
+        }
+        self.documents.insert(key, document);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.documents.remove(key).is_none() {
+            return;
+        }
+        self.postings.retain(|_, keys| {
+            keys.remove(key);
+            !keys.is_empty()
+        });
+    }
+
+    /// 让某一类文档与 `documents` 一致：内容没变的跳过，不在列表中的删除。
+    fn sync(&mut self, kind: SearchEntityKind, documents: Vec<SearchDocument>) {
+        let keys: HashSet<String> = documents
+            .iter()
+            .map(|document| kind.key(&document.id))
+            .collect();
+        let stale: Vec<String> = self
+            .documents
+            .iter()
+            .filter(|(key, document)| document.kind == kind && !keys.contains(*key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            self.remove(&key);
+        }
+        for document in documents {
+            if self.documents.get(&kind.key(&document.id)) != Some(&document) {
+                self.insert(document);
+            }
+        }
+    }
+}
+
+enum IndexCommand {
+    Upsert(SearchDocument),
+    Remove(SearchEntityKind, String),
+    Sync(SearchEntityKind, Vec<SearchDocument>),
+}
+
+/// 供其他模块推送增量更新的轻量句柄，更新由后台任务统一落盘。
+#[derive(Clone)]
+pub struct SearchIndexHandle {
+    sender: mpsc::UnboundedSender<IndexCommand>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl SearchIndexHandle {
+    pub fn upsert(&self, document: SearchDocument) {
+        self.send(IndexCommand::Upsert(document));
+    }
+
+    pub fn remove(&self, kind: SearchEntityKind, id: &str) {
+        self.send(IndexCommand::Remove(kind, id.to_string()));
+    }
+
+    /// 待办、笔记这类整表保存的数据每次保存后推送全部文档，由后台任务找出新增、修改和删除。
+    pub fn sync(&self, kind: SearchEntityKind, documents: Vec<SearchDocument>) {
+        self.send(IndexCommand::Sync(kind, documents));
+    }
+
+    fn send(&self, command: IndexCommand) {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        if self.sender.send(command).is_err() {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+pub struct SearchIndexer {
+    index_file: PathBuf,
+    data: Arc<async_runtime::Mutex<IndexData>>,
+    handle: SearchIndexHandle,
+    needs_rebuild: bool,
+}
+
+impl SearchIndexer {
+    pub fn new(app: &AppHandle) -> Result<Self, SearchError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let index_file = base_dir.join(INDEX_FILENAME);
+
+        let mut data = IndexData::default();
+        let mut needs_rebuild = !index_file.exists();
+        if index_file.exists() {
+            match load_index_file(&index_file) {
+                Ok(documents) => {
+                    for document in documents {
+                        data.insert(document);
+                    }
+                }
+                Err(err) => {
+                    log::warn!("search index is corrupted, rebuild required: {err}");
+                    data.corrupted = true;
+                    needs_rebuild = true;
+                }
+            }
+        }
+
+        let data = Arc::new(async_runtime::Mutex::new(data));
+        let pending = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        async_runtime::spawn(run_index_worker(
+            receiver,
+            data.clone(),
+            pending.clone(),
+            index_file.clone(),
+        ));
+
+        Ok(Self {
+            index_file,
+            data,
+            handle: SearchIndexHandle { sender, pending },
+            needs_rebuild,
+        })
+    }
+
+    pub fn handle(&self) -> SearchIndexHandle {
+        self.handle.clone()
+    }
+
+    pub fn needs_rebuild(&self) -> bool {
+        self.needs_rebuild
+    }
+
+    pub async fn status(&self) -> SearchIndexStatus {
+        let guard = self.data.lock().await;
+        SearchIndexStatus {
+            document_count: guard.documents.len(),
+            token_count: guard.postings.len(),
+            pending_updates: self.handle.pending.load(Ordering::Relaxed),
+            corrupted: guard.corrupted,
+            last_updated: guard.last_updated.clone(),
+        }
+    }
+
+    pub async fn rebuild(
+        &self,
+        documents: Vec<SearchDocument>,
+    ) -> Result<SearchIndexStatus, SearchError> {
+        {
+            let mut guard = self.data.lock().await;
+            *guard = IndexData::default();
+            for document in documents {
+                guard.insert(document);
+            }
+            persist_index(&self.index_file, &mut guard)?;
+        }
+        Ok(self.status().await)
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query_tokens = tokenize(query);
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let guard = self.data.lock().await;
+        let mut scores: HashMap<&str, u32> = HashMap::new();
+        for (index, token) in query_tokens.iter().enumerate() {
+            let mut matched: HashSet<&str> = HashSet::new();
+            for (indexed_token, keys) in &guard.postings {
+                if indexed_token.starts_with(token.as_str()) {
+                    matched.extend(keys.iter().map(String::as_str));
+                }
+            }
+            if index == 0 {
+                for key in matched {
+                    scores.insert(key, 1);
+                }
+            } else {
+                scores.retain(|key, _| matched.contains(key));
+                for score in scores.values_mut() {
+                    *score += 1;
+                }
+            }
+            if scores.is_empty() {
+                return Vec::new();
+            }
+        }
+
+        let needle = query.trim().to_lowercase();
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter_map(|(key, score)| guard.documents.get(key).map(|doc| (doc, score)))
+            .map(|(doc, score)| {
+                let title_bonus = if doc.title.to_lowercase().contains(&needle) {
+                    query_tokens.len() as u32
+                } else {
+                    0
+                };
+                SearchHit {
+                    kind: doc.kind,
+                    id: doc.id.clone(),
+                    title: doc.title.clone(),
+                    snippet: build_snippet(&doc.body, &needle),
+                    updated_at: doc.updated_at.clone(),
+                    score: score + title_bonus,
+                }
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.updated_at.cmp(&a.updated_at))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+async fn run_index_worker(
+    mut receiver: mpsc::UnboundedReceiver<IndexCommand>,
+    data: Arc<async_runtime::Mutex<IndexData>>,
+    pending: Arc<AtomicUsize>,
+    index_file: PathBuf,
+) {
+    while let Some(command) = receiver.recv().await {
+        let mut batch = vec![command];
+        while let Ok(next) = receiver.try_recv() {
+            batch.push(next);
+        }
+
+        let mut guard = data.lock().await;
+        for command in batch {
+            match command {
+                IndexCommand::Upsert(document) => guard.insert(document),
+                IndexCommand::Remove(kind, id) => guard.remove(&kind.key(&id)),
+                IndexCommand::Sync(kind, documents) => guard.sync(kind, documents),
+            }
+            pending.fetch_sub(1, Ordering::Relaxed);
+        }
+        if let Err(err) = persist_index(&index_file, &mut guard) {
+            log::error!("failed to persist search index: {err}");
+        }
+    }
+}
+
+fn load_index_file(path: &Path) -> Result<Vec<SearchDocument>, SearchError> {
+    let content = fs::read(path)?;
+    let file: IndexFile = serde_json::from_slice(&content)?;
+    if file.version != INDEX_VERSION {
+        return Err(SearchError::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected index version {}", file.version),
+        )));
+    }
+    Ok(file.documents)
+}
+
+fn persist_index(path: &Path, data: &mut IndexData) -> Result<(), SearchError> {
+    let file = IndexFile {
+        version: INDEX_VERSION,
+        documents: data.documents.values().cloned().collect(),
+    };
+    fs::write(path, serde_json::to_vec(&file)?)?;
+    data.corrupted = false;
+    data.last_updated = Some(now_utc());
+    Ok(())
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}')
+}
+
+/// 英文按单词切分，中日韩文本同时索引单字与相邻双字。
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+
+    let mut push = |token: String, tokens: &mut Vec<String>| {
+        if seen.insert(token.clone()) {
+            tokens.push(token);
+        }
+    };
+
+    for ch in text.chars() {
+        if is_cjk(ch) {
+            if !word.is_empty() {
+                push(std::mem::take(&mut word), &mut tokens);
+            }
+            push(ch.to_string(), &mut tokens);
+            if let Some(previous) = previous_cjk {
+                push(format!("{previous}{ch}"), &mut tokens);
+            }
+            previous_cjk = Some(ch);
+        } else if ch.is_alphanumeric() {
+            previous_cjk = None;
+            word.extend(ch.to_lowercase());
+        } else {
+            previous_cjk = None;
+            if !word.is_empty() {
+                push(std::mem::take(&mut word), &mut tokens);
+            }
+        }
+    }
+    if !word.is_empty() {
+        push(word, &mut tokens);
+    }
+    tokens
+}
+
+fn build_snippet(body: &str, needle: &str) -> String {
+    let chars: Vec<char> = body.chars().collect();
+    let lowered: Vec<char> = body.to_lowercase().chars().collect();
+    let needle_chars: Vec<char> = needle.chars().collect();
+
+    let position = if needle_chars.is_empty() || lowered.len() != chars.len() {
+        None
+    } else {
+        lowered
+            .windows(needle_chars.len())
+            .position(|window| window == needle_chars.as_slice())
+    };
+
+    let start = position
+        .map(|pos| pos.saturating_sub(SNIPPET_RADIUS))
+        .unwrap_or(0);
+    let end = (start + SNIPPET_RADIUS * 2 + needle_chars.len()).min(chars.len());
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet.replace('\n', " ")
+}
+
+/// 重建索引用的全部文档：会话、待办和笔记。
+pub async fn collect_documents(app: &AppHandle) -> Vec<SearchDocument> {
+    let mut documents = app.state::<SpeechManager>().search_documents().await;
+    documents.extend(app.state::<TodoStore>().search_documents().await);
+    documents.extend(app.state::<NoteStore>().search_documents().await);
+    documents
+}
+
+#[tauri::command]
+pub async fn search_everything(
+    indexer: tauri::State<'_, SearchIndexer>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    Ok(indexer.search(&query, limit.unwrap_or(50)).await)
+}
+
+#[tauri::command]
+pub async fn rebuild_search_index(
+    app: AppHandle,
+    indexer: tauri::State<'_, SearchIndexer>,
+) -> Result<SearchIndexStatus, String> {
+    let documents = collect_documents(&app).await;
+    indexer.rebuild(documents).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_search_index_status(
+    indexer: tauri::State<'_, SearchIndexer>,
+) -> Result<SearchIndexStatus, String> {
+    Ok(indexer.status().await)
+}
@@ -0,0 +1,193 @@
+mod preset;
+
+pub use preset::{apply_preset_from_url, list_trusted_preset_keys, remove_trusted_preset_key};
+
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::backup::{BackupError, BackupManager, BackupSettings};
+use crate::datetime::now_utc;
+use crate::speech::{
+    RetentionPolicy, SmartCollection, SpeechError, SpeechManager, SpeechSettingsProfile,
+};
+
+const SETTINGS_FORMAT: &str = "kk-settings";
+const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Speech(#[from] SpeechError),
+    #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error("不是有效的设置文件")]
+    InvalidFile,
+    #[error("设置文件版本 {0} 过新，请先升级应用")]
+    UnsupportedVersion(u32),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("预设下载失败，状态码 {0}")]
+    PresetDownload(u16),
+    #[error("预设地址无效：{0}")]
+    InvalidPresetUrl(String),
+    #[error("公钥无效，应为 Base64 编码的 32 字节 Ed25519 公钥")]
+    InvalidPublicKey,
+    #[error("预设未由受信任的公钥签名")]
+    UntrustedPreset,
+    #[error("预设签名校验失败，内容可能被篡改")]
+    InvalidSignature,
+    #[error("已开启离线模式，无法下载团队预设")]
+    Offline,
+}
+
+/// 备份开关和间隔；备份目录是本机路径，不随设置导出。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupPreferences {
+    enabled: bool,
+    interval_hours: u32,
+}
+
+/// 设置导出文件。日历订阅地址常带私有令牌，和代理一样不导出。
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsFile {
+    format: String,
+    version: u32,
+    exported_at: String,
+    speech: SpeechSettingsProfile,
+    #[serde(default)]
+    collections: Vec<SmartCollection>,
+    #[serde(default)]
+    retention_policies: Vec<RetentionPolicy>,
+    #[serde(default)]
+    backup: Option<BackupPreferences>,
+    /// 前端自己的偏好设置，原样保存，导入时交还给前端。
+    #[serde(default)]
+    client_settings: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportSettingsReport {
+    pub exported_at: String,
+    pub collections_added: usize,
+    pub retention_policies_added: usize,
+    /// 未能应用的项及原因。
+    pub skipped: Vec<String>,
+    pub client_settings: serde_json::Value,
+}
+
+async fn export_settings_file(
+    speech: &SpeechManager,
+    backup: &BackupManager,
+    path: PathBuf,
+    client_settings: serde_json::Value,
+) -> Result<(), SettingsError> {
+    let backup_settings = backup.status().await.settings;
+    let file = SettingsFile {
+        format: SETTINGS_FORMAT.to_string(),
+        version: SETTINGS_VERSION,
+        exported_at: now_utc(),
+        speech: speech.settings_profile().await,
+        collections: speech.smart_collections().await,
+        retention_policies: speech.list_retention_policies().await,
+        backup: Some(BackupPreferences {
+            enabled: backup_settings.enabled,
+            interval_hours: backup_settings.interval_hours,
+        }),
+        client_settings,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&file)?)?;
+    Ok(())
+}
+
+async fn import_settings_file(
+    speech: &SpeechManager,
+    backup: &BackupManager,
+    path: PathBuf,
+) -> Result<ImportSettingsReport, SettingsError> {
+    apply_settings(speech, backup, &fs::read(&path)?).await
+}
+
+/// 校验并应用一份设置文件内容，本地导入和团队预设共用。
+async fn apply_settings(
+    speech: &SpeechManager,
+    backup: &BackupManager,
+    content: &[u8],
+) -> Result<ImportSettingsReport, SettingsError> {
+    let file: SettingsFile =
+        serde_json::from_slice(content).map_err(|_| SettingsError::InvalidFile)?;
+    if file.format != SETTINGS_FORMAT {
+        return Err(SettingsError::InvalidFile);
+    }
+    if file.version > SETTINGS_VERSION {
+        return Err(SettingsError::UnsupportedVersion(file.version));
+    }
+
+    let mut skipped = speech.apply_settings_profile(file.speech).await?;
+    let collections_added = speech.merge_smart_collections(file.collections).await?;
+    let retention_policies_added = speech
+        .merge_retention_policies(file.retention_policies)
+        .await?;
+
+    if let Some(preferences) = file.backup {
+        let current = backup.status().await.settings;
+        let enabled = preferences.enabled && current.target_dir.is_some();
+        if preferences.enabled && !enabled {
+            skipped.push("本机尚未设置备份目录，自动备份保持关闭".into());
+        }
+        backup
+            .set_settings(BackupSettings {
+                enabled,
+                target_dir: current.target_dir,
+                interval_hours: preferences.interval_hours.max(1),
+            })
+            .await?;
+    }
+
+    Ok(ImportSettingsReport {
+        exported_at: file.exported_at,
+        collections_added,
+        retention_policies_added,
+        skipped,
+        client_settings: file.client_settings,
+    })
+}
+
+/// 导出可在其他设备复用的设置（不含代理、日历订阅等敏感信息）。
+#[tauri::command]
+pub async fn export_settings(
+    speech: tauri::State<'_, SpeechManager>,
+    backup: tauri::State<'_, BackupManager>,
+    path: String,
+    client_settings: Option<serde_json::Value>,
+) -> Result<(), String> {
+    export_settings_file(
+        &speech,
+        &backup,
+        PathBuf::from(path),
+        client_settings.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_settings(
+    speech: tauri::State<'_, SpeechManager>,
+    backup: tauri::State<'_, BackupManager>,
+    path: String,
+) -> Result<ImportSettingsReport, String> {
+    import_settings_file(&speech, &backup, PathBuf::from(path))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,155 @@
+use std::{fs, path::PathBuf};
+
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::{apply_settings, ImportSettingsReport, SettingsError};
+use crate::backup::BackupManager;
+use crate::datetime::now_utc;
+use crate::network::is_offline;
+use crate::speech::SpeechManager;
+use crate::storage::write_atomic;
+
+const TRUSTED_KEYS_FILENAME: &str = "preset_keys.json";
+
+/// 团队预设文件：`payload` 为 Base64 编码的设置文件，`signature` 是对其原始字节的 Ed25519 签名。
+#[derive(Debug, Deserialize)]
+struct SignedPreset {
+    payload: String,
+    signature: String,
+    public_key: String,
+}
+
+/// 用户确认过的预设发布者公钥。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedPresetKey {
+    pub public_key: String,
+    pub added_at: String,
+}
+
+fn trusted_keys_path(app: &AppHandle) -> Result<PathBuf, SettingsError> {
+    Ok(app.path().app_local_data_dir()?.join(TRUSTED_KEYS_FILENAME))
+}
+
+fn load_trusted_keys(app: &AppHandle) -> Result<Vec<TrustedPresetKey>, SettingsError> {
+    let path = trusted_keys_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_slice(&fs::read(path)?)?)
+}
+
+fn decode_public_key(value: &str) -> Result<VerifyingKey, SettingsError> {
+    let bytes: [u8; 32] = BASE64_STANDARD
+        .decode(value.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SettingsError::InvalidPublicKey)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| SettingsError::InvalidPublicKey)
+}
+
+fn trust_key(app: &AppHandle, public_key: &str) -> Result<(), SettingsError> {
+    let public_key = public_key.trim();
+    decode_public_key(public_key)?;
+    let mut keys = load_trusted_keys(app)?;
+    if keys.iter().any(|key| key.public_key == public_key) {
+        return Ok(());
+    }
+    keys.push(TrustedPresetKey {
+        public_key: public_key.to_string(),
+        added_at: now_utc(),
+    });
+    write_atomic(&trusted_keys_path(app)?, &serde_json::to_vec_pretty(&keys)?)?;
+    Ok(())
+}
+
+/// 校验签名并返回设置文件原文；签名公钥必须已受信任。
+fn verify_preset(
+    preset: &SignedPreset,
+    trusted: &[TrustedPresetKey],
+) -> Result<Vec<u8>, SettingsError> {
+    let public_key = preset.public_key.trim();
+    if !trusted.iter().any(|key| key.public_key == public_key) {
+        return Err(SettingsError::UntrustedPreset);
+    }
+    let key = decode_public_key(public_key)?;
+    let payload = BASE64_STANDARD
+        .decode(preset.payload.trim())
+        .map_err(|_| SettingsError::InvalidFile)?;
+    let signature = BASE64_STANDARD
+        .decode(preset.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or(SettingsError::InvalidSignature)?;
+    key.verify_strict(&payload, &signature)
+        .map_err(|_| SettingsError::InvalidSignature)?;
+    Ok(payload)
+}
+
+async fn apply_preset(
+    app: &AppHandle,
+    speech: &SpeechManager,
+    backup: &BackupManager,
+    url: &str,
+    public_key: Option<String>,
+) -> Result<ImportSettingsReport, SettingsError> {
+    let url = url.trim();
+    if !url.starts_with("https://") {
+        return Err(SettingsError::InvalidPresetUrl(
+            "仅支持 https:// 地址".into(),
+        ));
+    }
+    if is_offline() {
+        return Err(SettingsError::Offline);
+    }
+    if let Some(public_key) = public_key.as_deref() {
+        trust_key(app, public_key)?;
+    }
+
+    let response = reqwest::get(url).await?;
+    if !response.status().is_success() {
+        return Err(SettingsError::PresetDownload(response.status().as_u16()));
+    }
+    let preset: SignedPreset =
+        serde_json::from_slice(&response.bytes().await?).map_err(|_| SettingsError::InvalidFile)?;
+    let payload = verify_preset(&preset, &load_trusted_keys(app)?)?;
+    apply_settings(speech, backup, &payload).await
+}
+
+/// 从 URL 下载团队预设并在签名校验通过后应用；`public_key` 非空时先将其加入受信任列表。
+#[tauri::command]
+pub async fn apply_preset_from_url(
+    app: AppHandle,
+    speech: tauri::State<'_, SpeechManager>,
+    backup: tauri::State<'_, BackupManager>,
+    url: String,
+    public_key: Option<String>,
+) -> Result<ImportSettingsReport, String> {
+    apply_preset(&app, &speech, &backup, &url, public_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_trusted_preset_keys(app: AppHandle) -> Result<Vec<TrustedPresetKey>, String> {
+    load_trusted_keys(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_trusted_preset_key(
+    app: AppHandle,
+    public_key: String,
+) -> Result<Vec<TrustedPresetKey>, String> {
+    let remove = || -> Result<Vec<TrustedPresetKey>, SettingsError> {
+        let mut keys = load_trusted_keys(&app)?;
+        keys.retain(|key| key.public_key != public_key.trim());
+        write_atomic(
+            &trusted_keys_path(&app)?,
+            &serde_json::to_vec_pretty(&keys)?,
+        )?;
+        Ok(keys)
+    };
+    remove().map_err(|e| e.to_string())
+}
@@ -0,0 +1,309 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    context_cache::lock_contexts, ensure_writable, models::models_sharing_encoder, SpeechError,
+    SpeechManager, SpeechModel,
+};
+use crate::storage::write_atomic;
+
+/// 转写实际使用的加速方式，取决于编译时启用的 cargo feature 和所选后端。
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Acceleration {
+    Cpu,
+    Metal,
+    Cuda,
+    Vulkan,
+    /// 编码器在 Apple Neural Engine 上运行，解码器仍走 Metal / CPU。
+    CoreMl,
+}
+
+/// 用户选择的转写后端，保存在模型配置中。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionBackend {
+    Cpu,
+    Metal,
+    Cuda,
+    Vulkan,
+}
+
+const GPU_BACKENDS: [TranscriptionBackend; 3] = [
+    TranscriptionBackend::Metal,
+    TranscriptionBackend::Cuda,
+    TranscriptionBackend::Vulkan,
+];
+
+impl TranscriptionBackend {
+    /// 当前构建是否包含该后端；GPU 后端需分别以 metal / cuda / vulkan feature 编译。
+    pub fn compiled(self) -> bool {
+        match self {
+            TranscriptionBackend::Cpu => true,
+            TranscriptionBackend::Metal => cfg!(feature = "metal"),
+            TranscriptionBackend::Cuda => cfg!(feature = "cuda"),
+            TranscriptionBackend::Vulkan => cfg!(feature = "vulkan"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TranscriptionBackend::Cpu => "CPU",
+            TranscriptionBackend::Metal => "Metal",
+            TranscriptionBackend::Cuda => "CUDA",
+            TranscriptionBackend::Vulkan => "Vulkan",
+        }
+    }
+
+    /// 所选后端在本构建中不可用时（如导入了其他设备的设置）退回 CPU。
+    pub(super) fn use_gpu(self) -> bool {
+        self != TranscriptionBackend::Cpu && self.compiled()
+    }
+
+    fn acceleration(self) -> Acceleration {
+        if !self.use_gpu() {
+            return Acceleration::Cpu;
+        }
+        match self {
+            TranscriptionBackend::Cpu => Acceleration::Cpu,
+            TranscriptionBackend::Metal => Acceleration::Metal,
+            TranscriptionBackend::Cuda => Acceleration::Cuda,
+            TranscriptionBackend::Vulkan => Acceleration::Vulkan,
+        }
+    }
+}
+
+impl Default for TranscriptionBackend {
+    /// 默认使用本构建中第一个可用的 GPU 后端。
+    fn default() -> Self {
+        GPU_BACKENDS
+            .into_iter()
+            .find(|backend| backend.compiled())
+            .unwrap_or(TranscriptionBackend::Cpu)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackendCapability {
+    pub backend: TranscriptionBackend,
+    pub available: bool,
+    pub selected: bool,
+}
+
+impl SpeechManager {
+    pub(super) async fn transcription_backend(&self) -> TranscriptionBackend {
+        let guard = self.state.lock().await;
+        guard.model_config.backend
+    }
+
+    pub(super) fn coreml_encoder_path(&self, model: &SpeechModel) -> Option<PathBuf> {
+        model
+            .coreml_encoder_name()
+            .map(|name| self.models_dir.join(name))
+    }
+
+    /// whisper.cpp 只要找到编码器就会启用 Core ML，后端设置只影响 GPU。
+    pub(super) async fn acceleration(&self, model: &SpeechModel) -> Acceleration {
+        let has_encoder = self
+            .coreml_encoder_path(model)
+            .is_some_and(|path| path.is_dir());
+        if cfg!(feature = "coreml") && has_encoder {
+            Acceleration::CoreMl
+        } else {
+            self.transcription_backend().await.acceleration()
+        }
+    }
+
+    pub async fn backend_capabilities(&self) -> Vec<BackendCapability> {
+        let selected = self.transcription_backend().await;
+        [TranscriptionBackend::Cpu]
+            .into_iter()
+            .chain(GPU_BACKENDS)
+            .map(|backend| BackendCapability {
+                backend,
+                available: backend.compiled(),
+                selected: backend == selected,
+            })
+            .collect()
+    }
+
+    pub async fn set_transcription_backend(
+        &self,
+        backend: TranscriptionBackend,
+    ) -> Result<Acceleration, SpeechError> {
+        ensure_writable()?;
+        if !backend.compiled() {
+            return Err(SpeechError::BackendUnavailable(backend));
+        }
+        let model = {
+            let mut guard = self.state.lock().await;
+            guard.model_config.backend = backend;
+            write_atomic(
+                &self.model_config_file,
+                &serde_json::to_vec_pretty(&guard.model_config)?,
+            )?;
+            guard.model_config.active.clone()
+        };
+        // 已加载的上下文按旧设置创建，下次转写时重新加载
+        lock_contexts(&self.whisper_contexts).invalidate(&model);
+        Ok(self.acceleration(&model).await)
+    }
+
+    /// 删除模型后，没有其他已安装的模型再使用该编码器时一并删除，返回释放的字节数。
+    pub(super) fn remove_unused_coreml_encoder(&self, model: &SpeechModel) -> u64 {
+        let (Some(name), Some(path)) =
+            (model.coreml_encoder_name(), self.coreml_encoder_path(model))
+        else {
+            return 0;
+        };
+        if !path.is_dir()
+            || models_sharing_encoder(&name).any(|other| self.model_path(&other).exists())
+        {
+            return 0;
+        }
+        let size = dir_size(&path);
+        match fs::remove_dir_all(&path) {
+            Ok(()) => size,
+            Err(err) => {
+                log::warn!("failed to remove Core ML encoder {}: {err}", path.display());
+                0
+            }
+        }
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+#[cfg(feature = "coreml")]
+impl SpeechManager {
+    /// whisper.cpp 启用 Core ML 后会自动加载模型旁的 `*-encoder.mlmodelc`，缺失时退回 CPU 编码。
+    pub(super) async fn download_coreml_encoder(
+        &self,
+        model: &SpeechModel,
+    ) -> Result<(), SpeechError> {
+        use std::{fs::File, io::Write};
+
+        use futures_util::StreamExt;
+        use tauri::async_runtime;
+
+        let Some(encoder_path) = self.coreml_encoder_path(model) else {
+            return Ok(());
+        };
+        if encoder_path.is_dir() {
+            return Ok(());
+        }
+        super::ensure_online()?;
+        let (http, url) = {
+            let guard = self.state.lock().await;
+            (
+                guard.http.clone(),
+                model.coreml_encoder_url(&guard.model_config.source),
+            )
+        };
+        let Some(url) = url else {
+            return Ok(());
+        };
+
+        let response = http.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(SpeechError::CoreMlEncoder(format!(
+                "状态码 {}",
+                response.status()
+            )));
+        }
+        let zip_path = encoder_path.with_extension("mlmodelc.zip.part");
+        let mut file = File::create(&zip_path)?;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?)?;
+        }
+        file.flush()?;
+        drop(file);
+
+        let models_dir = self.models_dir.clone();
+        let extracted = async_runtime::spawn_blocking({
+            let zip_path = zip_path.clone();
+            move || -> Result<(), SpeechError> {
+                let mut archive = zip::ZipArchive::new(File::open(&zip_path)?)
+                    .map_err(|err| SpeechError::CoreMlEncoder(err.to_string()))?;
+                archive
+                    .extract(&models_dir)
+                    .map_err(|err| SpeechError::CoreMlEncoder(err.to_string()))
+            }
+        })
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))?;
+        let _ = fs::remove_file(&zip_path);
+        if let Err(err) = extracted {
+            let _ = fs::remove_dir_all(&encoder_path);
+            return Err(err);
+        }
+
+        if !encoder_path.is_dir() {
+            return Err(SpeechError::CoreMlEncoder("压缩包中没有编码器目录".into()));
+        }
+        // 让下一次转写加载带编码器的上下文
+        lock_contexts(&self.whisper_contexts).invalidate(model);
+        Ok(())
+    }
+
+    /// 已下载的模型缺少编码器时在后台补齐，不阻塞模型状态的返回。
+    pub(super) fn spawn_coreml_encoder_download(
+        &self,
+        app: &tauri::AppHandle,
+        model: &SpeechModel,
+    ) {
+        use tauri::{async_runtime, Manager};
+
+        if self
+            .coreml_encoder_path(model)
+            .map_or(true, |path| path.is_dir())
+        {
+            return;
+        }
+        let app_handle = app.clone();
+        let model = model.clone();
+        async_runtime::spawn(async move {
+            let manager = app_handle.state::<SpeechManager>();
+            if let Err(err) = manager.download_coreml_encoder(&model).await {
+                log::warn!(
+                    "failed to download Core ML encoder for {}: {err}",
+                    model.id()
+                );
+            }
+        });
+    }
+}
+
+/// 列出所有后端及其在当前构建中是否可用，前端据此隐藏不支持的选项。
+#[tauri::command]
+pub async fn get_transcription_backends(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<BackendCapability>, String> {
+    Ok(state.backend_capabilities().await)
+}
+
+#[tauri::command]
+pub async fn set_transcription_backend(
+    state: tauri::State<'_, SpeechManager>,
+    backend: TranscriptionBackend,
+) -> Result<Acceleration, String> {
+    state
+        .set_transcription_backend(backend)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,188 @@
+use std::{collections::BTreeSet, path::Path};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{async_runtime, AppHandle};
+
+use super::models::file_sha256;
+use super::{
+    AudioWarning, SpeechError, SpeechLanguage, SpeechManager, TranscriptSegment,
+    TranscriptionOptions,
+};
+use crate::calendar::CalendarEvent;
+use crate::datetime::now_utc;
+use crate::links::{EntityKind, EntityLink, EntityRef, LinkStore};
+
+pub const SESSION_ARCHIVE_FORMAT: &str = "kk-session";
+/// 只在字段含义改变或删除字段时递增；新增字段不改版本，读取方应忽略不认识的字段。
+pub const SESSION_ARCHIVE_VERSION: u32 = 1;
+
+/// 单个会话的归档格式。时间均为 UTC 的 RFC 3339 字符串，片段时间为相对录音开头的秒数。
+#[derive(Debug, Serialize)]
+pub struct SessionArchive {
+    pub format: &'static str,
+    pub format_version: u32,
+    /// 导出时的应用版本。
+    pub generator: String,
+    pub exported_at: String,
+    pub session: ArchivedSession,
+    /// 片段按时间顺序排列，`words` 为逐词时间，可能为空。
+    pub segments: Vec<TranscriptSegment>,
+    pub annotations: SessionAnnotations,
+    pub checksums: SessionChecksums,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchivedSession {
+    pub id: String,
+    pub title: String,
+    /// whisper 语言代码，为录音的原始语言。
+    pub language: SpeechLanguage,
+    /// 为 true 时 `transcript` 和片段文字是翻译成的英文。
+    pub translated: bool,
+    pub created_at: String,
+    pub duration_seconds: f32,
+    pub transcript: String,
+    pub initial_prompt: Option<String>,
+    /// 旧会话和导入的会话为空。
+    pub transcription_options: Option<TranscriptionOptions>,
+    pub gpu_fallback: Option<String>,
+    pub calendar_event: Option<CalendarEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionAnnotations {
+    pub tags: Vec<String>,
+    /// 片段中出现过的说话人，按名称排序。
+    pub speakers: Vec<String>,
+    /// 从本会话指向其他条目以及其他条目指向本会话的链接。
+    pub links: Vec<EntityLink>,
+    pub audio_warnings: Vec<AudioWarning>,
+}
+
+/// 均为小写十六进制的 SHA-256。
+#[derive(Debug, Serialize)]
+pub struct SessionChecksums {
+    /// `session.transcript` 的 UTF-8 字节。
+    pub transcript_sha256: String,
+    /// `segments` 数组的紧凑 JSON（无空白，字段顺序同本文件）。
+    pub segments_sha256: String,
+    /// 录音已删除时为空。
+    pub audio: Option<ArchivedAudio>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchivedAudio {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+async fn archive_audio(path: &Path) -> Result<ArchivedAudio, SpeechError> {
+    let size_bytes = path.metadata()?.len();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let path = path.to_path_buf();
+    let sha256 = async_runtime::spawn_blocking(move || file_sha256(&path))
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))??;
+    Ok(ArchivedAudio {
+        file_name,
+        size_bytes,
+        sha256,
+    })
+}
+
+impl SpeechManager {
+    pub async fn export_session_archive(
+        &self,
+        links: &LinkStore,
+        generator: String,
+        session_id: &str,
+    ) -> Result<SessionArchive, SpeechError> {
+        let session = {
+            let guard = self.state.lock().await;
+            guard
+                .sessions
+                .iter()
+                .find(|session| session.id == session_id)
+                .cloned()
+                .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?
+        };
+        let audio = if session.audio_removed_at.is_some() {
+            None
+        } else {
+            Some(archive_audio(&self.base_dir.join(&session.audio_path)).await?)
+        };
+
+        let entity = EntityRef {
+            kind: EntityKind::Session,
+            id: session.id.clone(),
+        };
+        let mut session_links = links.outgoing(&entity).await;
+        session_links.extend(links.backlinks(&entity).await);
+        let speakers: BTreeSet<String> = session
+            .segments
+            .iter()
+            .filter_map(|segment| segment.speaker.clone())
+            .collect();
+
+        let checksums = SessionChecksums {
+            transcript_sha256: sha256_hex(session.transcript.as_bytes()),
+            segments_sha256: sha256_hex(&serde_json::to_vec(&session.segments)?),
+            audio,
+        };
+        let duration_seconds = session.duration_seconds();
+        Ok(SessionArchive {
+            format: SESSION_ARCHIVE_FORMAT,
+            format_version: SESSION_ARCHIVE_VERSION,
+            generator,
+            exported_at: now_utc(),
+            session: ArchivedSession {
+                id: session.id,
+                title: session.title,
+                language: session.language,
+                translated: session.translated,
+                created_at: session.created_at,
+                duration_seconds,
+                transcript: session.transcript,
+                initial_prompt: session.initial_prompt,
+                transcription_options: session.transcription_options,
+                gpu_fallback: session.gpu_fallback,
+                calendar_event: session.calendar_event,
+            },
+            segments: session.segments,
+            annotations: SessionAnnotations {
+                tags: session.tags,
+                speakers: speakers.into_iter().collect(),
+                links: session_links,
+                audio_warnings: session.audio_warnings,
+            },
+            checksums,
+        })
+    }
+}
+
+/// 导出单个会话的完整元数据、片段、标注和校验值，格式带版本号，供长期归档和其他工具读取。
+#[tauri::command]
+pub async fn export_session_json(
+    app: AppHandle,
+    state: tauri::State<'_, SpeechManager>,
+    links: tauri::State<'_, LinkStore>,
+    session_id: String,
+) -> Result<SessionArchive, String> {
+    state
+        .export_session_archive(
+            &links,
+            format!("Kk {}", app.package_info().version),
+            &session_id,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use tauri::async_runtime;
+
+use super::{decode_audio_file, DecodedAudio, SpeechError, SpeechManager};
+
+const CACHE_MAX_ENTRIES: usize = 4;
+const CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+struct CacheEntry {
+    key: String,
+    modified: SystemTime,
+    audio: Arc<DecodedAudio>,
+}
+
+/// 最近解码过的 PCM，按会话 id 与文件修改时间命中，避免短时间内重复解码同一录音。
+#[derive(Default)]
+pub(super) struct DecodedAudioCache {
+    entries: VecDeque<CacheEntry>,
+}
+
+impl DecodedAudioCache {
+    fn get(&mut self, key: &str, modified: SystemTime) -> Option<Arc<DecodedAudio>> {
+        let index = self.entries.iter().position(|entry| entry.key == key)?;
+        let entry = self.entries.remove(index)?;
+        if entry.modified != modified {
+            return None;
+        }
+        let audio = entry.audio.clone();
+        self.entries.push_front(entry);
+        Some(audio)
+    }
+
+    fn insert(&mut self, key: &str, modified: SystemTime, audio: Arc<DecodedAudio>) {
+        let size = audio.size_bytes();
+        if size > CACHE_MAX_BYTES {
+            return;
+        }
+        self.entries.retain(|entry| entry.key != key);
+        self.entries.push_front(CacheEntry {
+            key: key.to_string(),
+            modified,
+            audio,
+        });
+
+        let mut total = 0;
+        let mut keep = 0;
+        for entry in &self.entries {
+            total += entry.audio.size_bytes();
+            if keep >= CACHE_MAX_ENTRIES || total > CACHE_MAX_BYTES {
+                break;
+            }
+            keep += 1;
+        }
+        self.entries.truncate(keep);
+    }
+
+    pub(super) fn invalidate(&mut self, key: &str) {
+        self.entries.retain(|entry| entry.key != key);
+    }
+}
+
+impl DecodedAudio {
+    fn size_bytes(&self) -> usize {
+        self.samples.len() * std::mem::size_of::<f32>()
+    }
+}
+
+impl SpeechManager {
+    /// 读取并解码录音文件，命中缓存时直接复用上一次的解码结果。
+    pub(super) async fn decoded_audio(
+        &self,
+        key: &str,
+        path: &Path,
+    ) -> Result<Arc<DecodedAudio>, SpeechError> {
+        let modified = fs::metadata(path)?.modified()?;
+        if let Some(audio) = lock_cache(&self.audio_cache).get(key, modified) {
+            return Ok(audio);
+        }
+
+        let path = path.to_path_buf();
+        let audio = async_runtime::spawn_blocking(move || decode_audio_file(&path))
+            .await
+            .map_err(|e| SpeechError::Join(e.to_string()))??;
+        let audio = Arc::new(audio);
+        lock_cache(&self.audio_cache).insert(key, modified, audio.clone());
+        Ok(audio)
+    }
+}
+
+pub(super) fn lock_cache(
+    cache: &Mutex<DecodedAudioCache>,
+) -> std::sync::MutexGuard<'_, DecodedAudioCache> {
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,201 @@
+use std::{
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use sysinfo::{Pid, System};
+use tauri::{async_runtime, path::BaseDirectory, AppHandle, Manager};
+use whisper_rs::{WhisperContext, WhisperContextParameters};
+
+use super::{
+    decode_audio, reduce_channels, resample_audio, transcribe_tracks, DecodingSettings,
+    SpeechError, SpeechLanguage, SpeechManager, SpeechModel, Track, TrackOrigin,
+    TranscriptionOptions, WhisperTask,
+};
+
+/// 随安装包分发的英文语音片段，所有模型（包括仅英语模型）都能转写。
+const BENCHMARK_SAMPLE: &str = "resources/benchmark/sample.wav";
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Serialize)]
+pub struct ModelBenchmark {
+    pub model: SpeechModel,
+    pub gpu: bool,
+    pub audio_seconds: f32,
+    pub load_seconds: f32,
+    pub transcribe_seconds: f32,
+    /// 转写耗时除以音频时长，小于 1 表示快于实时。
+    pub realtime_factor: f32,
+    /// 测试期间进程内存峰值相对测试开始前的增量，无法读取进程信息时为空。
+    pub peak_memory_bytes: Option<u64>,
+}
+
+/// 在后台线程轮询进程内存，记录测试期间的峰值。
+struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    handle: thread::JoinHandle<u64>,
+    baseline: u64,
+}
+
+fn process_memory(system: &mut System, pid: Pid) -> u64 {
+    system.refresh_process(pid);
+    system.process(pid).map_or(0, |process| process.memory())
+}
+
+impl MemorySampler {
+    fn start() -> Option<Self> {
+        let pid = sysinfo::get_current_pid().ok()?;
+        let mut system = System::new();
+        let baseline = process_memory(&mut system, pid);
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let mut peak = baseline;
+                while !stop.load(Ordering::Relaxed) {
+                    peak = peak.max(process_memory(&mut system, pid));
+                    thread::sleep(MEMORY_POLL_INTERVAL);
+                }
+                peak.max(process_memory(&mut system, pid))
+            }
+        });
+        Some(Self {
+            stop,
+            handle,
+            baseline,
+        })
+    }
+
+    fn finish(self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        let peak = self.handle.join().unwrap_or(self.baseline);
+        peak.saturating_sub(self.baseline)
+    }
+}
+
+fn load_context(model_path: &Path, use_gpu: bool) -> Result<WhisperContext, SpeechError> {
+    let model_str = model_path.to_str().ok_or(SpeechError::InvalidModelPath)?;
+    let mut ctx_params = WhisperContextParameters::default();
+    ctx_params.use_gpu(use_gpu);
+    Ok(WhisperContext::new_with_params(model_str, ctx_params)?)
+}
+
+/// 独立加载一份上下文计时，不经过常驻缓存，测得的是冷启动的加载耗时。
+fn run_benchmark(
+    model: SpeechModel,
+    model_path: &Path,
+    use_gpu: bool,
+    audio: Vec<f32>,
+    audio_seconds: f32,
+    decoding: DecodingSettings,
+) -> Result<ModelBenchmark, SpeechError> {
+    let sampler = MemorySampler::start();
+
+    let load_started = Instant::now();
+    let (ctx, gpu) = match load_context(model_path, use_gpu) {
+        Ok(ctx) => (ctx, use_gpu),
+        Err(err) if !use_gpu => return Err(err),
+        Err(err) => {
+            log::warn!("failed to load benchmark model on GPU, using CPU: {err}");
+            (load_context(model_path, false)?, false)
+        }
+    };
+    let load_seconds = load_started.elapsed().as_secs_f32();
+
+    let transcribe_started = Instant::now();
+    let tracks = [Track {
+        audio,
+        origin: TrackOrigin {
+            speaker: None,
+            timeline: None,
+        },
+    }];
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    transcribe_tracks(
+        &ctx,
+        &tracks,
+        &WhisperTask {
+            language: SpeechLanguage::ENGLISH,
+            translate: false,
+            initial_prompt: None,
+            options: TranscriptionOptions::default(),
+        },
+        &decoding,
+        &cancel_flag,
+        None,
+        None,
+        None,
+    )?;
+    let transcribe_seconds = transcribe_started.elapsed().as_secs_f32();
+    drop(ctx);
+
+    Ok(ModelBenchmark {
+        model,
+        gpu,
+        audio_seconds,
+        load_seconds,
+        transcribe_seconds,
+        realtime_factor: transcribe_seconds / audio_seconds.max(f32::EPSILON),
+        peak_memory_bytes: sampler.map(MemorySampler::finish),
+    })
+}
+
+impl SpeechManager {
+    /// 用内置音频测量模型在本机上的加载耗时、实时率和内存峰值，帮助用户选择模型大小。
+    pub async fn benchmark_model(
+        &self,
+        app: &AppHandle,
+        model_id: &str,
+    ) -> Result<ModelBenchmark, SpeechError> {
+        let model = self.resolve_model(model_id).await?;
+        let model_path = self.model_path(&model);
+        if !model_path.exists() {
+            return Err(SpeechError::ModelNotDownloaded(model.id().to_string()));
+        }
+        {
+            let guard = self.state.lock().await;
+            if guard.active_transcription.is_some() {
+                return Err(SpeechError::TranscriptionInProgress);
+            }
+        }
+
+        let sample_path = app
+            .path()
+            .resolve(BENCHMARK_SAMPLE, BaseDirectory::Resource)?;
+        let sample = fs::read(&sample_path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => SpeechError::BenchmarkSampleMissing,
+            _ => err.into(),
+        })?;
+        let decoded = decode_audio(&sample)?;
+        let mono = reduce_channels(&decoded.samples, decoded.channels);
+        let audio = resample_audio(&mono, decoded.sample_rate, 16_000);
+        let audio_seconds = audio.len() as f32 / 16_000.0;
+
+        let use_gpu = self.transcription_backend().await.use_gpu();
+        let decoding = self.decoding_settings().await;
+        async_runtime::spawn_blocking(move || {
+            run_benchmark(model, &model_path, use_gpu, audio, audio_seconds, decoding)
+        })
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))?
+    }
+}
+
+#[tauri::command]
+pub async fn benchmark_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+    app: AppHandle,
+    model_id: String,
+) -> Result<ModelBenchmark, String> {
+    state
+        .benchmark_model(&app, &model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
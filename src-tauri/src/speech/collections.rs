@@ -0,0 +1,290 @@
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ensure_writable, SpeechError, SpeechLanguage, SpeechManager, SpeechSession};
+use crate::datetime::now_utc;
+use crate::storage::write_atomic;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CollectionDateRange {
+    LastDays {
+        days: u32,
+    },
+    ThisMonth,
+    LastMonth,
+    Custom {
+        #[serde(default)]
+        from: Option<String>,
+        #[serde(default)]
+        to: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SmartCollectionFilter {
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub languages: Vec<SpeechLanguage>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub date_range: Option<CollectionDateRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartCollection {
+    pub id: String,
+    pub name: String,
+    pub filter: SmartCollectionFilter,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SmartCollectionSummary {
+    #[serde(flatten)]
+    pub collection: SmartCollection,
+    pub session_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveSmartCollectionPayload {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub filter: SmartCollectionFilter,
+}
+
+impl SmartCollectionFilter {
+    pub fn matches(&self, session: &SpeechSession) -> bool {
+        if !self.languages.is_empty() && !self.languages.contains(&session.language) {
+            return false;
+        }
+
+        if !self
+            .tags
+            .iter()
+            .all(|tag| session.tags.iter().any(|t| t == tag))
+        {
+            return false;
+        }
+
+        if let Some(query) = self.query.as_deref().map(str::trim) {
+            if !query.is_empty() {
+                let query = query.to_lowercase();
+                if !session.title.to_lowercase().contains(&query)
+                    && !session.transcript.to_lowercase().contains(&query)
+                {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(range) = &self.date_range {
+            let Ok(created_at) = DateTime::parse_from_rfc3339(&session.created_at) else {
+                return false;
+            };
+            let (from, to) = range.bounds(Local::now());
+            let created_at = created_at.with_timezone(&Local);
+            if from.is_some_and(|from| created_at < from) || to.is_some_and(|to| created_at >= to) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl CollectionDateRange {
+    fn bounds(&self, now: DateTime<Local>) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+        match self {
+            CollectionDateRange::LastDays { days } => {
+                (Some(now - Duration::days(i64::from(*days))), None)
+            }
+            CollectionDateRange::ThisMonth => (start_of_month(now.year(), now.month()), None),
+            CollectionDateRange::LastMonth => {
+                let (year, month) = if now.month() == 1 {
+                    (now.year() - 1, 12)
+                } else {
+                    (now.year(), now.month() - 1)
+                };
+                (
+                    start_of_month(year, month),
+                    start_of_month(now.year(), now.month()),
+                )
+            }
+            CollectionDateRange::Custom { from, to } => {
+                let parse = |value: &Option<String>| {
+                    value
+                        .as_deref()
+                        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                        .map(|v| v.with_timezone(&Local))
+                };
+                (parse(from), parse(to))
+            }
+        }
+    }
+}
+
+fn start_of_month(year: i32, month: u32) -> Option<DateTime<Local>> {
+    Local.with_ymd_and_hms(year, month, 1, 0, 0, 0).earliest()
+}
+
+impl SpeechManager {
+    pub async fn list_smart_collections(&self) -> Vec<SmartCollectionSummary> {
+        let guard = self.state.lock().await;
+        guard
+            .collections
+            .iter()
+            .map(|collection| SmartCollectionSummary {
+                collection: collection.clone(),
+                session_count: guard
+                    .sessions
+                    .iter()
+                    .filter(|session| collection.filter.matches(session))
+                    .count(),
+            })
+            .collect()
+    }
+
+    pub async fn save_smart_collection(
+        &self,
+        payload: SaveSmartCollectionPayload,
+    ) -> Result<SmartCollection, SpeechError> {
+        ensure_writable()?;
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(SpeechError::InvalidCollection("名称不能为空".into()));
+        }
+
+        let mut guard = self.state.lock().await;
+        let collection = match payload.id {
+            Some(id) => {
+                let existing = guard
+                    .collections
+                    .iter_mut()
+                    .find(|collection| collection.id == id)
+                    .ok_or_else(|| SpeechError::CollectionNotFound(id.clone()))?;
+                existing.name = name.to_string();
+                existing.filter = payload.filter;
+                existing.clone()
+            }
+            None => {
+                let collection = SmartCollection {
+                    id: Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    filter: payload.filter,
+                    created_at: now_utc(),
+                };
+                guard.collections.push(collection.clone());
+                collection
+            }
+        };
+
+        self.persist_collections(&guard.collections)?;
+        Ok(collection)
+    }
+
+    pub async fn delete_smart_collection(&self, collection_id: &str) -> Result<(), SpeechError> {
+        ensure_writable()?;
+        let mut guard = self.state.lock().await;
+        let before = guard.collections.len();
+        guard
+            .collections
+            .retain(|collection| collection.id != collection_id);
+        if guard.collections.len() != before {
+            self.persist_collections(&guard.collections)?;
+        }
+        Ok(())
+    }
+
+    pub async fn evaluate_smart_collection(
+        &self,
+        collection_id: &str,
+    ) -> Result<Vec<SpeechSession>, SpeechError> {
+        let guard = self.state.lock().await;
+        let collection = guard
+            .collections
+            .iter()
+            .find(|collection| collection.id == collection_id)
+            .ok_or_else(|| SpeechError::CollectionNotFound(collection_id.to_string()))?;
+        Ok(guard
+            .sessions
+            .iter()
+            .filter(|session| collection.filter.matches(session))
+            .cloned()
+            .collect())
+    }
+
+    pub async fn smart_collections(&self) -> Vec<SmartCollection> {
+        let guard = self.state.lock().await;
+        guard.collections.clone()
+    }
+
+    /// 合并导入的智能集合，已存在的 id 保持不变，返回新增数量。
+    pub async fn merge_smart_collections(
+        &self,
+        collections: Vec<SmartCollection>,
+    ) -> Result<usize, SpeechError> {
+        let mut guard = self.state.lock().await;
+        let before = guard.collections.len();
+        for collection in collections {
+            if !guard.collections.iter().any(|c| c.id == collection.id) {
+                guard.collections.push(collection);
+            }
+        }
+        let added = guard.collections.len() - before;
+        if added > 0 {
+            self.persist_collections(&guard.collections)?;
+        }
+        Ok(added)
+    }
+
+    fn persist_collections(&self, collections: &[SmartCollection]) -> Result<(), SpeechError> {
+        let json = serde_json::to_vec_pretty(collections)?;
+        write_atomic(&self.collections_file, &json)?;
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn list_smart_collections(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<SmartCollectionSummary>, String> {
+    Ok(state.list_smart_collections().await)
+}
+
+#[tauri::command]
+pub async fn save_smart_collection(
+    state: tauri::State<'_, SpeechManager>,
+    payload: SaveSmartCollectionPayload,
+) -> Result<SmartCollection, String> {
+    state
+        .save_smart_collection(payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_smart_collection(
+    state: tauri::State<'_, SpeechManager>,
+    collection_id: String,
+) -> Result<(), String> {
+    state
+        .delete_smart_collection(&collection_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn evaluate_smart_collection(
+    state: tauri::State<'_, SpeechManager>,
+    collection_id: String,
+) -> Result<Vec<SpeechSession>, String> {
+    state
+        .evaluate_smart_collection(&collection_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs::{self, File},
+    io::{self, Cursor, Read},
+    path::Path,
+};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::FormatOptions,
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use super::{DecodedAudio, SpeechError};
+
+impl From<SymphoniaError> for SpeechError {
+    fn from(value: SymphoniaError) -> Self {
+        Self::Audio(value.to_string())
+    }
+}
+
+/// hound 只能读取 RIFF/WAVE，其余格式（包括 RF64）交给 symphonia。
+pub(super) fn is_wav(audio_bytes: &[u8]) -> bool {
+    audio_bytes.len() >= 12 && &audio_bytes[..4] == b"RIFF" && &audio_bytes[8..12] == b"WAVE"
+}
+
+pub(super) fn is_wav_file(path: &Path) -> Result<bool, SpeechError> {
+    let mut header = Vec::with_capacity(12);
+    File::open(path)?.take(12).read_to_end(&mut header)?;
+    Ok(is_wav(&header))
+}
+
+pub(super) fn decode_compressed(audio_bytes: &[u8]) -> Result<DecodedAudio, SpeechError> {
+    decode_source(Box::new(Cursor::new(audio_bytes.to_vec())))
+}
+
+pub(super) fn decode_compressed_file(path: &Path) -> Result<DecodedAudio, SpeechError> {
+    decode_source(Box::new(File::open(path)?))
+}
+
+/// 根据内容识别容器（MP3、M4A/AAC、OGG/Vorbis、FLAC 等）并解码为交错的 f32 采样。
+fn decode_source(source: Box<dyn MediaSource>) -> Result<DecodedAudio, SpeechError> {
+    let source = MediaSourceStream::new(source, Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(
+            &Hint::new(),
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|_| SpeechError::Audio("无法识别的音频格式".into()))?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| SpeechError::Audio("音频文件中没有可解码的音轨".into()))?;
+    let track_id = track.id;
+    let mut channels = track.codec_params.channels.map_or(0, |c| c.count());
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 个别损坏的帧直接跳过，与常见播放器的处理一致
+            Err(SymphoniaError::DecodeError(err)) => {
+                log::warn!("skipping undecodable audio packet: {err}");
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let spec = *decoded.spec();
+        channels = spec.channels.count();
+        sample_rate = spec.rate;
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    if channels == 0 || sample_rate == 0 {
+        return Err(SpeechError::Audio("音频通道数无效".into()));
+    }
+    Ok(DecodedAudio {
+        samples,
+        channels,
+        sample_rate,
+    })
+}
+
+/// 保存为 16 位 WAV，保留原有声道，会话目录中的录音始终是 WAV。
+pub(super) fn encode_wav(audio: &DecodedAudio) -> Result<Vec<u8>, SpeechError> {
+    let spec = WavSpec {
+        channels: audio.channels as u16,
+        sample_rate: audio.sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut cursor, spec)?;
+    for sample in &audio.samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// WAV 原样返回，其他格式解码后转为 WAV。
+pub(super) fn into_wav(audio_bytes: Vec<u8>) -> Result<Vec<u8>, SpeechError> {
+    if is_wav(&audio_bytes) {
+        return Ok(audio_bytes);
+    }
+    encode_wav(&decode_compressed(&audio_bytes)?)
+}
+
+/// 把本机音频文件放到 `dest`：WAV 直接复制，其他格式转码。
+pub(super) fn copy_as_wav(source: &Path, dest: &Path) -> Result<(), SpeechError> {
+    if is_wav_file(source)? {
+        fs::copy(source, dest)?;
+    } else {
+        fs::write(dest, encode_wav(&decode_compressed_file(source)?)?)?;
+    }
+    Ok(())
+}
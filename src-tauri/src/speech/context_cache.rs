@@ -0,0 +1,131 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use serde::Serialize;
+use tauri::async_runtime;
+use whisper_rs::{WhisperContext, WhisperContextParameters};
+
+use super::{SpeechError, SpeechManager, SpeechModel};
+
+struct LoadedContext {
+    model: SpeechModel,
+    use_gpu: bool,
+    ctx: Arc<WhisperContext>,
+}
+
+/// 常驻内存的 WhisperContext，只保留当前模型一份，避免每次转写都从磁盘重新加载。
+#[derive(Default)]
+pub(super) struct WhisperContextCache {
+    loaded: Option<LoadedContext>,
+}
+
+impl WhisperContextCache {
+    /// 模型已在内存中时返回它是否运行在 GPU 上。
+    pub(super) fn loaded_on_gpu(&self, model: &SpeechModel) -> Option<bool> {
+        self.loaded
+            .as_ref()
+            .filter(|loaded| &loaded.model == model)
+            .map(|loaded| loaded.use_gpu)
+    }
+
+    /// 命中时直接复用；模型或 GPU 设置不同则替换为新加载的上下文。
+    pub(super) fn load(
+        &mut self,
+        model: &SpeechModel,
+        model_path: &Path,
+        use_gpu: bool,
+    ) -> Result<Arc<WhisperContext>, SpeechError> {
+        if let Some(loaded) = &self.loaded {
+            if &loaded.model == model && loaded.use_gpu == use_gpu {
+                return Ok(loaded.ctx.clone());
+            }
+        }
+
+        // 先释放旧模型，避免两份模型同时占用内存
+        self.loaded = None;
+        let model_str = model_path.to_str().ok_or(SpeechError::InvalidModelPath)?;
+        let mut ctx_params = WhisperContextParameters::default();
+        ctx_params.use_gpu(use_gpu);
+        let ctx = Arc::new(WhisperContext::new_with_params(model_str, ctx_params)?);
+        self.loaded = Some(LoadedContext {
+            model: model.clone(),
+            use_gpu,
+            ctx: ctx.clone(),
+        });
+        Ok(ctx)
+    }
+
+    pub(super) fn invalidate(&mut self, model: &SpeechModel) {
+        if self.loaded_on_gpu(model).is_some() {
+            self.loaded = None;
+        }
+    }
+}
+
+pub(super) fn lock_contexts(
+    cache: &Mutex<WhisperContextCache>,
+) -> MutexGuard<'_, WhisperContextCache> {
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreloadModelResponse {
+    pub model: SpeechModel,
+    /// 调用前模型已在内存中。
+    pub already_loaded: bool,
+    pub gpu: bool,
+}
+
+impl SpeechManager {
+    /// 提前把当前模型加载进内存，让启动后的第一次转写不必等待模型加载。
+    pub async fn preload_model(&self) -> Result<PreloadModelResponse, SpeechError> {
+        let model = self.active_model().await;
+        let model_path = self.model_path(&model);
+        if !model_path.exists() {
+            return Err(SpeechError::ModelNotDownloaded(model.id().to_string()));
+        }
+        if let Some(gpu) = lock_contexts(&self.whisper_contexts).loaded_on_gpu(&model) {
+            return Ok(PreloadModelResponse {
+                model,
+                already_loaded: true,
+                gpu,
+            });
+        }
+
+        let contexts = self.whisper_contexts.clone();
+        let use_gpu = self.transcription_backend().await.use_gpu();
+        let gpu = async_runtime::spawn_blocking({
+            let model = model.clone();
+            move || {
+                let mut cache = lock_contexts(&contexts);
+                match cache.load(&model, &model_path, use_gpu) {
+                    Ok(_) => Ok(use_gpu),
+                    Err(err) if !use_gpu => Err(err),
+                    Err(err) => {
+                        log::warn!("failed to preload model on GPU, using CPU: {err}");
+                        cache.load(&model, &model_path, false).map(|_| false)
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))??;
+
+        Ok(PreloadModelResponse {
+            model,
+            already_loaded: false,
+            gpu,
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn preload_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<PreloadModelResponse, String> {
+    state.preload_model().await.map_err(|e| e.to_string())
+}
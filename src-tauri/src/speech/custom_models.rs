@@ -0,0 +1,159 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime;
+
+use super::{ensure_writable, SpeechError, SpeechManager, SpeechModel};
+use crate::datetime::now_utc;
+use crate::storage::write_atomic;
+
+/// whisper.cpp ggml 模型文件头的 magic（小端序 "ggml"）。
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+/// 多语言模型的词表至少有 51865 个 token，仅英语模型为 51864。
+const MULTILINGUAL_MIN_VOCAB: i32 = 51_865;
+const CUSTOM_MODEL_PREFIX: &str = "custom-";
+
+/// 用户从本地导入的模型，文件放在 models 目录下，与内置模型一样按 id 选用。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomModel {
+    pub id: String,
+    pub name: String,
+    pub source_path: String,
+    pub size_bytes: u64,
+    #[serde(default)]
+    pub linked: bool,
+    /// 按文件头中的词表大小判断，仅英语模型拒绝其他语言的转写。
+    #[serde(default)]
+    pub english_only: bool,
+    pub imported_at: String,
+}
+
+/// 校验 magic 并返回是否为仅英语模型；magic 之后紧跟的 hparams 第一项是词表大小。
+pub(super) fn read_ggml_header(path: &Path) -> Result<bool, SpeechError> {
+    let mut header = [0_u8; 8];
+    File::open(path)?
+        .read_exact(&mut header)
+        .map_err(|_| SpeechError::InvalidModelFile)?;
+    let [m0, m1, m2, m3, v0, v1, v2, v3] = header;
+    if u32::from_le_bytes([m0, m1, m2, m3]) != GGML_MAGIC {
+        return Err(SpeechError::InvalidModelFile);
+    }
+    Ok(i32::from_le_bytes([v0, v1, v2, v3]) < MULTILINGUAL_MIN_VOCAB)
+}
+
+fn slugify(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        "model".into()
+    } else {
+        slug
+    }
+}
+
+#[cfg(unix)]
+fn link_model(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn link_model(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(source, target)
+}
+
+impl SpeechManager {
+    /// 校验 ggml 文件头后复制（或软链接）到 models 目录并登记为可选模型。
+    pub async fn import_model(
+        &self,
+        source: PathBuf,
+        name: Option<String>,
+        link: bool,
+    ) -> Result<CustomModel, SpeechError> {
+        ensure_writable()?;
+        let source = source.canonicalize()?;
+        let english_only = read_ggml_header(&source)?;
+        let size_bytes = fs::metadata(&source)?.len();
+
+        let stem = source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| stem.clone());
+        let base_id = format!(
+            "{CUSTOM_MODEL_PREFIX}{}",
+            slugify(stem.trim_start_matches("ggml-"))
+        );
+
+        let id = {
+            let guard = self.state.lock().await;
+            let taken = |id: &str| guard.model_config.custom.iter().any(|m| m.id == id);
+            let mut id = base_id.clone();
+            let mut suffix = 2;
+            while taken(&id) {
+                id = format!("{base_id}-{suffix}");
+                suffix += 1;
+            }
+            id
+        };
+
+        let model = SpeechModel::new(id.clone());
+        let target = self.model_path(&model);
+        if target.exists() {
+            fs::remove_file(&target)?;
+        }
+        let linked = link && link_model(&source, &target).is_ok();
+        if !linked {
+            let (from, to) = (source.clone(), target.clone());
+            async_runtime::spawn_blocking(move || fs::copy(from, to))
+                .await
+                .map_err(|e| SpeechError::Join(e.to_string()))??;
+        }
+
+        let custom = CustomModel {
+            id,
+            name,
+            source_path: source.to_string_lossy().into_owned(),
+            size_bytes,
+            linked,
+            english_only,
+            imported_at: now_utc(),
+        };
+        let mut guard = self.state.lock().await;
+        guard.model_config.custom.push(custom.clone());
+        write_atomic(
+            &self.model_config_file,
+            &serde_json::to_vec_pretty(&guard.model_config)?,
+        )?;
+        Ok(custom)
+    }
+}
+
+/// 导入已有的 whisper.cpp 模型文件；`link` 为 true 时尽量创建软链接以节省空间。
+#[tauri::command]
+pub async fn import_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+    path: String,
+    name: Option<String>,
+    link: Option<bool>,
+) -> Result<CustomModel, String> {
+    state
+        .import_model(PathBuf::from(path), name, link.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
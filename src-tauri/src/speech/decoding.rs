@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ensure_writable, SpeechError, SpeechManager, TranscriptSegment};
+use crate::storage::write_atomic;
+
+/// 静音时 whisper 常见的“片尾”幻觉文本。
+const HALLUCINATION_MARKERS: &[&str] = &[
+    "谢谢观看",
+    "感谢观看",
+    "谢谢收看",
+    "请不吝点赞",
+    "订阅 转发 打赏",
+    "字幕由amara.org",
+    "明镜与点点栏目",
+    "优优独播剧场",
+    "thank you for watching",
+    "thanks for watching",
+];
+
+/// whisper 的解码阈值，对应 whisper.cpp 的 no_speech_thold / entropy_thold / logprob_thold。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DecodingSettings {
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+    /// 压缩率（熵）阈值，超过时按 `temperature_increment` 升温重新解码。
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f32,
+    #[serde(default = "default_logprob_threshold")]
+    pub logprob_threshold: f32,
+    #[serde(default = "default_temperature_increment")]
+    pub temperature_increment: f32,
+    /// 丢弃连续重复以及“谢谢观看”一类的幻觉片段。
+    #[serde(default = "default_suppress_hallucinations")]
+    pub suppress_hallucinations: bool,
+}
+
+impl Default for DecodingSettings {
+    fn default() -> Self {
+        Self {
+            no_speech_threshold: default_no_speech_threshold(),
+            entropy_threshold: default_entropy_threshold(),
+            logprob_threshold: default_logprob_threshold(),
+            temperature_increment: default_temperature_increment(),
+            suppress_hallucinations: default_suppress_hallucinations(),
+        }
+    }
+}
+
+fn default_no_speech_threshold() -> f32 {
+    0.6
+}
+
+fn default_entropy_threshold() -> f32 {
+    2.4
+}
+
+fn default_logprob_threshold() -> f32 {
+    -1.0
+}
+
+fn default_temperature_increment() -> f32 {
+    0.2
+}
+
+fn default_suppress_hallucinations() -> bool {
+    true
+}
+
+impl DecodingSettings {
+    fn validate(&self) -> Result<(), SpeechError> {
+        if !(0.0..=1.0).contains(&self.no_speech_threshold) {
+            return Err(SpeechError::InvalidDecodingSettings(
+                "静音阈值需在 0 到 1 之间".into(),
+            ));
+        }
+        if !self.entropy_threshold.is_finite() || self.entropy_threshold <= 0.0 {
+            return Err(SpeechError::InvalidDecodingSettings(
+                "压缩率阈值必须大于 0".into(),
+            ));
+        }
+        if !self.logprob_threshold.is_finite() || self.logprob_threshold > 0.0 {
+            return Err(SpeechError::InvalidDecodingSettings(
+                "对数概率阈值不能大于 0".into(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            return Err(SpeechError::InvalidDecodingSettings(
+                "温度步进需在 0 到 1 之间".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// whisper 只保留上下文的一半给输出，单段 token 数不能超过这个值。
+const MAX_TOKENS_PER_SEGMENT: u32 = 224;
+
+/// 单次转写覆盖的解码参数，未填写的项沿用全局解码设置和 whisper 的默认值。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptionOptions {
+    /// 首次解码的采样温度，0 为贪心解码；解码失败时仍按 `temperature_increment` 升温。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_threshold: Option<f32>,
+    /// 每段最多生成的 token 数，0 表示不限制。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_segment: Option<u32>,
+    /// 禁止在段首输出空白。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppress_blanks: Option<bool>,
+}
+
+impl TranscriptionOptions {
+    pub(super) fn validate(&self) -> Result<(), SpeechError> {
+        if self
+            .temperature
+            .is_some_and(|temperature| !(0.0..=1.0).contains(&temperature))
+        {
+            return Err(SpeechError::InvalidDecodingSettings(
+                "温度需在 0 到 1 之间".into(),
+            ));
+        }
+        if self
+            .max_tokens_per_segment
+            .is_some_and(|max_tokens| max_tokens > MAX_TOKENS_PER_SEGMENT)
+        {
+            return Err(SpeechError::InvalidDecodingSettings(format!(
+                "每段最大 token 数不能超过 {MAX_TOKENS_PER_SEGMENT}"
+            )));
+        }
+        self.apply(DecodingSettings::default()).validate()
+    }
+
+    /// 用覆盖项替换全局设置中的阈值。
+    pub(super) fn apply(&self, settings: DecodingSettings) -> DecodingSettings {
+        DecodingSettings {
+            entropy_threshold: self.entropy_threshold.unwrap_or(settings.entropy_threshold),
+            no_speech_threshold: self
+                .no_speech_threshold
+                .unwrap_or(settings.no_speech_threshold),
+            ..settings
+        }
+    }
+
+    /// 按 `apply` 之后的设置补全所有未填写的项，记录到会话中以便复现同样的转写。
+    pub(super) fn resolve(&self, settings: &DecodingSettings) -> Self {
+        Self {
+            temperature: Some(self.temperature()),
+            entropy_threshold: Some(settings.entropy_threshold),
+            no_speech_threshold: Some(settings.no_speech_threshold),
+            max_tokens_per_segment: Some(self.max_tokens_per_segment()),
+            suppress_blanks: Some(self.suppress_blanks()),
+        }
+    }
+
+    pub(super) fn temperature(&self) -> f32 {
+        self.temperature.unwrap_or(0.0)
+    }
+
+    pub(super) fn max_tokens_per_segment(&self) -> u32 {
+        self.max_tokens_per_segment.unwrap_or(0)
+    }
+
+    pub(super) fn suppress_blanks(&self) -> bool {
+        self.suppress_blanks.unwrap_or(true)
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !c.is_ascii_punctuation() && !is_cjk_punctuation(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '，' | '。' | '！' | '？' | '、' | '：' | '；' | '…' | '“' | '”'
+    )
+}
+
+/// 去掉连续重复的片段和已知的幻觉文本，保留第一次出现。
+pub(super) fn suppress_hallucinations(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+    let markers: Vec<String> = HALLUCINATION_MARKERS.iter().map(|m| normalize(m)).collect();
+    let mut kept: Vec<TranscriptSegment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let text = normalize(&segment.text);
+        if text.is_empty() {
+            continue;
+        }
+        if markers.iter().any(|marker| text.contains(marker.as_str())) {
+            continue;
+        }
+        let repeated = kept
+            .iter()
+            .rev()
+            .find(|previous| previous.speaker == segment.speaker)
+            .is_some_and(|previous| normalize(&previous.text) == text);
+        if repeated {
+            continue;
+        }
+        kept.push(segment);
+    }
+    kept
+}
+
+impl SpeechManager {
+    pub async fn decoding_settings(&self) -> DecodingSettings {
+        let guard = self.state.lock().await;
+        guard.decoding
+    }
+
+    pub async fn set_decoding_settings(
+        &self,
+        settings: DecodingSettings,
+    ) -> Result<DecodingSettings, SpeechError> {
+        ensure_writable()?;
+        settings.validate()?;
+        let mut guard = self.state.lock().await;
+        guard.decoding = settings;
+        write_atomic(&self.decoding_file, &serde_json::to_vec_pretty(&settings)?)?;
+        Ok(settings)
+    }
+}
+
+#[tauri::command]
+pub async fn get_decoding_settings(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<DecodingSettings, String> {
+    Ok(state.decoding_settings().await)
+}
+
+#[tauri::command]
+pub async fn set_decoding_settings(
+    state: tauri::State<'_, SpeechManager>,
+    settings: DecodingSettings,
+) -> Result<DecodingSettings, String> {
+    state
+        .set_decoding_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
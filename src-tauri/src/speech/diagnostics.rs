@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime;
+
+use super::{decode_audio, decode_audio_base64, DecodedAudio, SpeechError, SpeechManager};
+
+const CLIPPING_LEVEL: f32 = 0.999;
+const CLIPPING_RATIO_WARNING: f32 = 0.001;
+const SILENCE_RMS_DBFS: f32 = -50.0;
+const QUIET_RMS_DBFS: f32 = -40.0;
+const MIN_SAMPLE_RATE: u32 = 16_000;
+const PHASE_CANCELLATION_RATIO: f32 = 0.25;
+const MIN_DURATION_SECONDS: f32 = 0.5;
+const VOICE_FRAME_SECONDS: f32 = 0.03;
+const VOICE_RMS_DBFS: f32 = -45.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioWarningKind {
+    Clipping,
+    NearSilence,
+    LowVolume,
+    LowSampleRate,
+    PhaseCancellation,
+    TooShort,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioWarning {
+    pub kind: AudioWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioAnalysis {
+    pub duration_seconds: f32,
+    pub sample_rate: u32,
+    pub channels: usize,
+    pub peak: f32,
+    pub rms_dbfs: f32,
+    pub clipping_ratio: f32,
+    /// 能量高于语音阈值的总时长，接近 0 时可跳过转写。
+    pub voiced_seconds: f32,
+    pub warnings: Vec<AudioWarning>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeAudioPayload {
+    pub audio_base64: String,
+}
+
+fn to_dbfs(rms: f32) -> f32 {
+    if rms <= f32::EPSILON {
+        -120.0
+    } else {
+        20.0 * rms.log10()
+    }
+}
+
+fn rms(samples: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = samples.fold((0.0_f64, 0_usize), |(sum, count), sample| {
+        (sum + f64::from(sample) * f64::from(sample), count + 1)
+    });
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).sqrt() as f32
+    }
+}
+
+/// 检测削波、近乎静音、采样率过低以及立体声相位抵消等会导致识别效果差的问题。
+pub(super) fn analyze(audio: &DecodedAudio) -> AudioAnalysis {
+    let channels = audio.channels.max(1);
+    let frames = audio.samples.len() / channels;
+    let duration_seconds = if audio.sample_rate == 0 {
+        0.0
+    } else {
+        frames as f32 / audio.sample_rate as f32
+    };
+
+    let peak = audio
+        .samples
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    let clipped = audio
+        .samples
+        .iter()
+        .filter(|sample| sample.abs() >= CLIPPING_LEVEL)
+        .count();
+    let clipping_ratio = if audio.samples.is_empty() {
+        0.0
+    } else {
+        clipped as f32 / audio.samples.len() as f32
+    };
+
+    let mixed_rms = rms(audio
+        .samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32));
+    let rms_dbfs = to_dbfs(mixed_rms);
+    let voiced_seconds = voiced_seconds(audio, channels);
+
+    let mut warnings = Vec::new();
+    if duration_seconds < MIN_DURATION_SECONDS {
+        warnings.push(AudioWarning {
+            kind: AudioWarningKind::TooShort,
+            message: format!("音频时长仅 {duration_seconds:.2} 秒，可能无法识别"),
+        });
+    }
+    if clipping_ratio >= CLIPPING_RATIO_WARNING {
+        warnings.push(AudioWarning {
+            kind: AudioWarningKind::Clipping,
+            message: format!(
+                "{:.1}% 的采样出现削波，录音音量过大会导致失真",
+                clipping_ratio * 100.0
+            ),
+        });
+    }
+    if rms_dbfs < SILENCE_RMS_DBFS {
+        warnings.push(AudioWarning {
+            kind: AudioWarningKind::NearSilence,
+            message: format!("音频几乎是静音（平均电平 {rms_dbfs:.1} dBFS），请检查麦克风"),
+        });
+    } else if rms_dbfs < QUIET_RMS_DBFS {
+        warnings.push(AudioWarning {
+            kind: AudioWarningKind::LowVolume,
+            message: format!("录音音量偏低（平均电平 {rms_dbfs:.1} dBFS）"),
+        });
+    }
+    if audio.sample_rate < MIN_SAMPLE_RATE {
+        warnings.push(AudioWarning {
+            kind: AudioWarningKind::LowSampleRate,
+            message: format!(
+                "采样率仅 {} Hz，低于识别所需的 {} Hz，高频信息已丢失",
+                audio.sample_rate, MIN_SAMPLE_RATE
+            ),
+        });
+    }
+    if channels > 1 {
+        let channel_rms = (0..channels)
+            .map(|channel| {
+                rms(audio
+                    .samples
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels)
+                    .copied())
+            })
+            .fold(0.0_f32, f32::max);
+        if to_dbfs(channel_rms) > SILENCE_RMS_DBFS
+            && mixed_rms < channel_rms * PHASE_CANCELLATION_RATIO
+        {
+            warnings.push(AudioWarning {
+                kind: AudioWarningKind::PhaseCancellation,
+                message: "多声道混合为单声道时出现明显相位抵消，建议只使用单个声道".into(),
+            });
+        }
+    }
+
+    AudioAnalysis {
+        duration_seconds,
+        sample_rate: audio.sample_rate,
+        channels,
+        peak,
+        rms_dbfs,
+        clipping_ratio,
+        voiced_seconds,
+        warnings,
+    }
+}
+
+/// 按 30ms 分帧统计能量超过阈值的帧；按所有声道的能量计算，不受相位抵消影响。
+fn voiced_seconds(audio: &DecodedAudio, channels: usize) -> f32 {
+    let frame_len = ((audio.sample_rate as f32 * VOICE_FRAME_SECONDS) as usize).max(1) * channels;
+    let voiced_frames = audio
+        .samples
+        .chunks(frame_len)
+        .filter(|frame| to_dbfs(rms(frame.iter().copied())) >= VOICE_RMS_DBFS)
+        .count();
+    voiced_frames as f32 * VOICE_FRAME_SECONDS
+}
+
+impl SpeechManager {
+    pub async fn analyze_session_audio(
+        &self,
+        session_id: &str,
+    ) -> Result<AudioAnalysis, SpeechError> {
+        let audio_path = {
+            let guard = self.state.lock().await;
+            let session = guard
+                .sessions
+                .iter()
+                .find(|session| session.id == session_id)
+                .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?;
+            self.base_dir.join(&session.audio_path)
+        };
+        let decoded = self.decoded_audio(session_id, &audio_path).await?;
+        Ok(analyze(&decoded))
+    }
+}
+
+fn analyze_payload(payload: AnalyzeAudioPayload) -> Result<AudioAnalysis, SpeechError> {
+    let audio_bytes = decode_audio_base64(&payload.audio_base64)?;
+    let decoded = decode_audio(&audio_bytes)?;
+    Ok(analyze(&decoded))
+}
+
+#[tauri::command]
+pub async fn analyze_audio(payload: AnalyzeAudioPayload) -> Result<AudioAnalysis, String> {
+    async_runtime::spawn_blocking(move || analyze_payload(payload))
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()).to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 分析已保存会话的录音，解码结果与转写共用缓存。
+#[tauri::command]
+pub async fn analyze_session_audio(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+) -> Result<AudioAnalysis, String> {
+    state
+        .analyze_session_audio(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,473 @@
+use std::{f32::consts::PI, fs, sync::Arc};
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use tauri::async_runtime;
+
+use super::{
+    build_transcript, ensure_writable, reduce_channels, resample_audio, session_search_document,
+    SpeechError, SpeechManager, SpeechSession, TranscriptSegment, WHISPER_SAMPLE_RATE,
+};
+
+const FRAME_LEN: usize = 512;
+const HOP_LEN: usize = 256;
+const MEL_BANDS: usize = 24;
+/// 去掉代表整体音量的 c0 后保留的倒谱系数个数。
+const MFCC_COUNT: usize = 12;
+const MIN_FRAME_RMS: f32 = 1e-3;
+/// 短于该时长的片段特征不稳定，不参与聚类，只按最近的说话人归类。
+const MIN_FIT_SECONDS: f32 = 1.0;
+const MAX_SPEAKERS: usize = 6;
+/// 自动判断人数时，聚类的平均轮廓系数低于该值就认为只有一个人。
+const MIN_SILHOUETTE: f32 = 0.15;
+const KMEANS_ITERATIONS: usize = 50;
+
+/// 每帧计算 MFCC，按片段汇总为均值和标准差作为该片段的声纹特征。
+struct FeatureExtractor {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    filters: Vec<Vec<f32>>,
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+impl FeatureExtractor {
+    fn new() -> Self {
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(FRAME_LEN);
+        let window = (0..FRAME_LEN)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME_LEN as f32).cos())
+            .collect();
+
+        let bins = FRAME_LEN / 2 + 1;
+        let (low, high) = (hz_to_mel(60.0), hz_to_mel(7600.0));
+        let edges: Vec<f32> = (0..MEL_BANDS + 2)
+            .map(|i| {
+                let hz = mel_to_hz(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32);
+                hz * FRAME_LEN as f32 / WHISPER_SAMPLE_RATE as f32
+            })
+            .collect();
+        let filters = edges
+            .windows(3)
+            .map(|edge| {
+                (0..bins)
+                    .map(|bin| {
+                        let bin = bin as f32;
+                        if bin <= edge[0] || bin >= edge[2] {
+                            0.0
+                        } else if bin <= edge[1] {
+                            (bin - edge[0]) / (edge[1] - edge[0]).max(f32::EPSILON)
+                        } else {
+                            (edge[2] - bin) / (edge[2] - edge[1]).max(f32::EPSILON)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            fft,
+            window,
+            filters,
+        }
+    }
+
+    fn mfcc(&self, frame: &[f32], buffer: &mut [Complex<f32>]) -> Option<[f32; MFCC_COUNT]> {
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+        if rms < MIN_FRAME_RMS {
+            return None;
+        }
+        for ((slot, sample), weight) in buffer.iter_mut().zip(frame).zip(&self.window) {
+            *slot = Complex::new(sample * weight, 0.0);
+        }
+        self.fft.process(buffer);
+
+        let power: Vec<f32> = buffer[..FRAME_LEN / 2 + 1]
+            .iter()
+            .map(|c| c.norm_sqr())
+            .collect();
+        let log_mel: Vec<f32> = self
+            .filters
+            .iter()
+            .map(|filter| {
+                let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+                (energy + 1e-10).ln()
+            })
+            .collect();
+
+        let mut coefficients = [0.0; MFCC_COUNT];
+        for (k, coefficient) in coefficients.iter_mut().enumerate() {
+            let k = k + 1;
+            *coefficient = log_mel
+                .iter()
+                .enumerate()
+                .map(|(m, value)| {
+                    value * (PI * k as f32 * (m as f32 + 0.5) / MEL_BANDS as f32).cos()
+                })
+                .sum();
+        }
+        Some(coefficients)
+    }
+
+    fn segment_features(&self, audio: &[f32]) -> Option<Vec<f32>> {
+        let mut buffer = vec![Complex::new(0.0, 0.0); FRAME_LEN];
+        let frames: Vec<[f32; MFCC_COUNT]> = audio
+            .windows(FRAME_LEN)
+            .step_by(HOP_LEN)
+            .filter_map(|frame| self.mfcc(frame, &mut buffer))
+            .collect();
+        if frames.len() < 2 {
+            return None;
+        }
+
+        let count = frames.len() as f32;
+        let mut features = vec![0.0; MFCC_COUNT * 2];
+        for frame in &frames {
+            for (i, value) in frame.iter().enumerate() {
+                features[i] += value / count;
+            }
+        }
+        for frame in &frames {
+            for (i, value) in frame.iter().enumerate() {
+                features[MFCC_COUNT + i] += (value - features[i]).powi(2) / count;
+            }
+        }
+        for value in &mut features[MFCC_COUNT..] {
+            *value = value.sqrt();
+        }
+        Some(features)
+    }
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+fn nearest(point: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| distance(point, a).total_cmp(&distance(point, b)))
+        .map_or(0, |(index, _)| index)
+}
+
+/// 各维标准化为零均值、单位方差，避免数值大的系数主导距离。
+fn standardize(points: &mut [Vec<f32>]) {
+    let Some(dims) = points.first().map(Vec::len) else {
+        return;
+    };
+    let count = points.len() as f32;
+    for dim in 0..dims {
+        let mean = points.iter().map(|p| p[dim]).sum::<f32>() / count;
+        let std = (points.iter().map(|p| (p[dim] - mean).powi(2)).sum::<f32>() / count).sqrt();
+        for point in points.iter_mut() {
+            point[dim] = (point[dim] - mean) / std.max(1e-6);
+        }
+    }
+}
+
+/// 用最远点法确定初始中心，结果可复现。
+fn kmeans(points: &[&Vec<f32>], k: usize) -> (Vec<Vec<f32>>, Vec<usize>) {
+    let mut centroids: Vec<Vec<f32>> = vec![points[0].clone()];
+    while centroids.len() < k {
+        let farthest = points
+            .iter()
+            .max_by(|a, b| {
+                let da = distance(a, &centroids[nearest(a, &centroids)]);
+                let db = distance(b, &centroids[nearest(b, &centroids)]);
+                da.total_cmp(&db)
+            })
+            .map(|point| (*point).clone());
+        match farthest {
+            Some(point) => centroids.push(point),
+            None => break,
+        }
+    }
+
+    let mut labels = vec![0; points.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let next: Vec<usize> = points.iter().map(|p| nearest(p, &centroids)).collect();
+        let changed = next != labels;
+        labels = next;
+        for (cluster, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<&&Vec<f32>> = points
+                .iter()
+                .zip(&labels)
+                .filter(|(_, label)| **label == cluster)
+                .map(|(point, _)| point)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (dim, value) in centroid.iter_mut().enumerate() {
+                *value = members.iter().map(|p| p[dim]).sum::<f32>() / members.len() as f32;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    (centroids, labels)
+}
+
+fn silhouette(points: &[&Vec<f32>], labels: &[usize], k: usize) -> f32 {
+    let mut total = 0.0;
+    for (i, point) in points.iter().enumerate() {
+        let mut sums = vec![0.0; k];
+        let mut counts = vec![0usize; k];
+        for (j, other) in points.iter().enumerate() {
+            if i != j {
+                sums[labels[j]] += distance(point, other);
+                counts[labels[j]] += 1;
+            }
+        }
+        let own = labels[i];
+        if counts[own] == 0 {
+            continue;
+        }
+        let a = sums[own] / counts[own] as f32;
+        let b = (0..k)
+            .filter(|&c| c != own && counts[c] > 0)
+            .map(|c| sums[c] / counts[c] as f32)
+            .fold(f32::INFINITY, f32::min);
+        if b.is_finite() {
+            total += (b - a) / a.max(b).max(f32::EPSILON);
+        }
+    }
+    total / points.len() as f32
+}
+
+/// 按声纹特征把片段聚类为说话人，返回与 `segments` 一一对应的标签。
+/// `speakers` 为空时在 1 到 6 人之间按轮廓系数自动选择人数。
+fn diarize(audio: &[f32], segments: &[TranscriptSegment], speakers: Option<usize>) -> Vec<String> {
+    let extractor = FeatureExtractor::new();
+    let mut features: Vec<Option<Vec<f32>>> = segments
+        .iter()
+        .map(|segment| {
+            let start = (segment.start.max(0.0) * WHISPER_SAMPLE_RATE as f32) as usize;
+            let end = (segment.end.max(0.0) * WHISPER_SAMPLE_RATE as f32) as usize;
+            let end = end.min(audio.len());
+            (start < end)
+                .then(|| extractor.segment_features(&audio[start..end]))
+                .flatten()
+        })
+        .collect();
+
+    let mut present: Vec<Vec<f32>> = features.iter().flatten().cloned().collect();
+    standardize(&mut present);
+    let mut present = present.into_iter();
+    for slot in features.iter_mut().filter(|slot| slot.is_some()) {
+        *slot = present.next();
+    }
+
+    let long_enough = |(segment, feature): (&TranscriptSegment, &Option<Vec<f32>>)| {
+        (segment.end - segment.start >= MIN_FIT_SECONDS)
+            .then_some(feature.as_ref())
+            .flatten()
+    };
+    let mut fit: Vec<&Vec<f32>> = segments
+        .iter()
+        .zip(&features)
+        .filter_map(long_enough)
+        .collect();
+    if fit.len() < 2 {
+        fit = features.iter().flatten().collect();
+    }
+
+    let centroids = if fit.len() < 2 {
+        fit.iter().map(|point| (*point).clone()).collect()
+    } else {
+        let k = match speakers {
+            Some(speakers) => speakers.clamp(1, fit.len()),
+            None => {
+                let mut best = (1, MIN_SILHOUETTE);
+                for k in 2..=MAX_SPEAKERS.min(fit.len() - 1) {
+                    let (_, labels) = kmeans(&fit, k);
+                    let score = silhouette(&fit, &labels, k);
+                    if score > best.1 {
+                        best = (k, score);
+                    }
+                }
+                best.0
+            }
+        };
+        kmeans(&fit, k).0
+    };
+
+    // 按首次出现的顺序编号，没有特征的片段沿用前一段的说话人
+    let mut order: Vec<usize> = Vec::new();
+    let mut previous = 0;
+    features
+        .iter()
+        .map(|feature| {
+            let cluster = match feature {
+                Some(point) if !centroids.is_empty() => nearest(point, &centroids),
+                _ => previous,
+            };
+            previous = cluster;
+            let index = match order.iter().position(|&c| c == cluster) {
+                Some(index) => index,
+                None => {
+                    order.push(cluster);
+                    order.len() - 1
+                }
+            };
+            format!("说话人 {}", index + 1)
+        })
+        .collect()
+}
+
+impl SpeechManager {
+    /// 对已保存的会话做说话人区分，覆盖原有的说话人标签。
+    pub async fn diarize_session(
+        &self,
+        session_id: &str,
+        speakers: Option<usize>,
+    ) -> Result<SpeechSession, SpeechError> {
+        ensure_writable()?;
+        let session = {
+            let guard = self.state.lock().await;
+            guard
+                .sessions
+                .iter()
+                .find(|session| session.id == session_id)
+                .cloned()
+                .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?
+        };
+        if session.audio_removed_at.is_some() {
+            return Err(SpeechError::AudioRemoved);
+        }
+        let decoded = self
+            .decoded_audio(session_id, &self.base_dir.join(&session.audio_path))
+            .await?;
+        let segments = session.segments.clone();
+        let labels = async_runtime::spawn_blocking(move || {
+            let mono = reduce_channels(&decoded.samples, decoded.channels.max(1));
+            let audio = resample_audio(&mono, decoded.sample_rate, WHISPER_SAMPLE_RATE as u32);
+            diarize(&audio, &segments, speakers)
+        })
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))?;
+
+        self.update_speakers(session_id, None, |segments| {
+            for (segment, label) in segments.iter_mut().zip(labels) {
+                segment.speaker = Some(label);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// 修改会话中某个说话人的名称，正文中对应的行首也一并替换。
+    pub async fn rename_speaker(
+        &self,
+        session_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<SpeechSession, SpeechError> {
+        ensure_writable()?;
+        let to = to.trim();
+        if to.is_empty() {
+            return Err(SpeechError::InvalidSpeakerName);
+        }
+        self.update_speakers(session_id, Some((from, to)), |segments| {
+            let mut found = false;
+            for segment in segments.iter_mut() {
+                if segment.speaker.as_deref() == Some(from) {
+                    segment.speaker = Some(to.to_string());
+                    found = true;
+                }
+            }
+            if found {
+                Ok(())
+            } else {
+                Err(SpeechError::SpeakerNotFound(from.to_string()))
+            }
+        })
+        .await
+    }
+
+    /// 正文未被手动编辑过时按新标签重新生成；编辑过的正文只替换改名的说话人。
+    async fn update_speakers(
+        &self,
+        session_id: &str,
+        rename: Option<(&str, &str)>,
+        update: impl FnOnce(&mut Vec<TranscriptSegment>) -> Result<(), SpeechError>,
+    ) -> Result<SpeechSession, SpeechError> {
+        let mut guard = self.state.lock().await;
+        let session = guard
+            .sessions
+            .iter_mut()
+            .find(|session| session.id == session_id)
+            .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?;
+
+        let edited = session.transcript != build_transcript(&session.segments);
+        update(&mut session.segments)?;
+        if !edited {
+            session.transcript = build_transcript(&session.segments);
+        } else if let Some((from, to)) = rename {
+            session.transcript = rename_line_prefix(&session.transcript, from, to);
+        }
+
+        let session_dir = self.sessions_dir.join(&session.id);
+        fs::write(
+            session_dir.join("segments.json"),
+            serde_json::to_vec_pretty(&session.segments)?,
+        )?;
+        fs::write(
+            session_dir.join("transcript.txt"),
+            session.transcript.as_bytes(),
+        )?;
+
+        let result = session.clone();
+        self.persist_sessions(&guard.sessions)?;
+        self.indexer.upsert(session_search_document(&result));
+        Ok(result)
+    }
+}
+
+fn rename_line_prefix(transcript: &str, from: &str, to: &str) -> String {
+    let (from, to) = (format!("{from}："), format!("{to}："));
+    transcript
+        .lines()
+        .map(|line| match line.strip_prefix(&from) {
+            Some(rest) => format!("{to}{rest}"),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 对会话做说话人区分；`speakers` 为已知人数，缺省时自动判断。
+#[tauri::command]
+pub async fn diarize_speech_session(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+    speakers: Option<usize>,
+) -> Result<SpeechSession, String> {
+    state
+        .diarize_session(&session_id, speakers)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_speaker(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+    from: String,
+    to: String,
+) -> Result<SpeechSession, String> {
+    state
+        .rename_speaker(&session_id, &from, &to)
+        .await
+        .map_err(|e| e.to_string())
+}
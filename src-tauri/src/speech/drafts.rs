@@ -0,0 +1,137 @@
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ensure_writable, SpeechError, SpeechManager};
+use crate::datetime::now_utc;
+use crate::storage::write_atomic;
+
+const DRAFT_FILENAME: &str = "transcript.draft.json";
+
+/// 编辑中尚未保存的文字稿，与已保存的 transcript.txt 分开存放。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptDraft {
+    pub session_id: String,
+    pub text: String,
+    pub saved_at: String,
+}
+
+impl SpeechManager {
+    fn draft_path(&self, session_id: &str) -> PathBuf {
+        self.sessions_dir.join(session_id).join(DRAFT_FILENAME)
+    }
+
+    async fn saved_transcript(&self, session_id: &str) -> Result<String, SpeechError> {
+        let guard = self.state.lock().await;
+        guard
+            .sessions
+            .iter()
+            .find(|session| session.id == session_id)
+            .map(|session| session.transcript.clone())
+            .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// 写入草稿；与已保存的文字稿相同时删除草稿并返回 `None`。
+    pub async fn autosave_draft(
+        &self,
+        session_id: &str,
+        text: String,
+    ) -> Result<Option<TranscriptDraft>, SpeechError> {
+        ensure_writable()?;
+        if text == self.saved_transcript(session_id).await? {
+            self.discard_draft(session_id)?;
+            return Ok(None);
+        }
+        let draft = TranscriptDraft {
+            session_id: session_id.to_string(),
+            text,
+            saved_at: now_utc(),
+        };
+        let path = self.draft_path(session_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, &serde_json::to_vec_pretty(&draft)?)?;
+        Ok(Some(draft))
+    }
+
+    fn read_draft(&self, session_id: &str) -> Option<TranscriptDraft> {
+        let bytes = fs::read(self.draft_path(session_id)).ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(draft) => Some(draft),
+            Err(err) => {
+                log::warn!("ignoring unreadable transcript draft for {session_id}: {err}");
+                None
+            }
+        }
+    }
+
+    pub async fn draft(&self, session_id: &str) -> Result<Option<TranscriptDraft>, SpeechError> {
+        self.saved_transcript(session_id).await?;
+        Ok(self.read_draft(session_id))
+    }
+
+    /// 所有留有草稿的会话，通常是上次崩溃或强退前正在编辑的内容。
+    pub async fn list_drafts(&self) -> Vec<TranscriptDraft> {
+        let session_ids: Vec<String> = {
+            let guard = self.state.lock().await;
+            guard
+                .sessions
+                .iter()
+                .map(|session| session.id.clone())
+                .collect()
+        };
+        session_ids
+            .iter()
+            .filter_map(|session_id| self.read_draft(session_id))
+            .collect()
+    }
+
+    pub(super) fn discard_draft(&self, session_id: &str) -> Result<(), SpeechError> {
+        match fs::remove_file(self.draft_path(session_id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 编辑文字稿时由前端每隔几秒调用一次，崩溃后可从草稿恢复；正式保存仍通过 `update_speech_session`。
+#[tauri::command]
+pub async fn autosave_transcript_draft(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+    text: String,
+) -> Result<Option<TranscriptDraft>, String> {
+    state
+        .autosave_draft(&session_id, text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_transcript_draft(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+) -> Result<Option<TranscriptDraft>, String> {
+    state.draft(&session_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_transcript_drafts(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<TranscriptDraft>, String> {
+    Ok(state.list_drafts().await)
+}
+
+/// 放弃草稿，保留已保存的文字稿。
+#[tauri::command]
+pub async fn discard_transcript_draft(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+) -> Result<(), String> {
+    state
+        .saved_transcript(&session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.discard_draft(&session_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,117 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{SpeechLanguage, SpeechManager, SpeechSession};
+use crate::datetime::parse_instant;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupGranularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl GroupGranularity {
+    /// 返回日期所在分组的起止日期（含），周从周一开始。
+    fn bounds(self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            GroupGranularity::Day => (date, date),
+            GroupGranularity::Week => {
+                let start = date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+                (start, start + Duration::days(6))
+            }
+            GroupGranularity::Month => {
+                let start = date.with_day(1).unwrap_or(date);
+                let next = if start.month() == 12 {
+                    NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+                };
+                (start, next.map_or(date, |next| next - Duration::days(1)))
+            }
+        }
+    }
+}
+
+/// 分组列表中的一行，不含转写全文和分段。
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionListItem {
+    pub id: String,
+    pub title: String,
+    pub language: SpeechLanguage,
+    pub created_at: String,
+    pub duration_seconds: f32,
+    pub tags: Vec<String>,
+}
+
+impl From<&SpeechSession> for SessionListItem {
+    fn from(session: &SpeechSession) -> Self {
+        Self {
+            id: session.id.clone(),
+            title: session.title.clone(),
+            language: session.language,
+            created_at: session.created_at.clone(),
+            duration_seconds: session.duration_seconds(),
+            tags: session.tags.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionGroup {
+    /// 分组起始日期（本地时区，YYYY-MM-DD）。
+    pub start: String,
+    pub end: String,
+    pub session_count: usize,
+    pub duration_seconds: f32,
+    pub sessions: Vec<SessionListItem>,
+}
+
+impl SpeechManager {
+    /// 按本地日期把会话分到日 / 周 / 月，分组和组内会话都按时间倒序。
+    pub async fn list_sessions_grouped(&self, granularity: GroupGranularity) -> Vec<SessionGroup> {
+        let guard = self.state.lock().await;
+        let mut dated: Vec<(NaiveDate, _, SessionListItem)> = guard
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                let instant = parse_instant(&session.created_at)?;
+                let date = instant.with_timezone(&Local).date_naive();
+                Some((date, instant, SessionListItem::from(session)))
+            })
+            .collect();
+        drop(guard);
+        dated.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut groups: Vec<SessionGroup> = Vec::new();
+        let mut current: Option<(NaiveDate, NaiveDate)> = None;
+        for (date, _, item) in dated {
+            let bounds = granularity.bounds(date);
+            if current != Some(bounds) {
+                current = Some(bounds);
+                groups.push(SessionGroup {
+                    start: bounds.0.format("%Y-%m-%d").to_string(),
+                    end: bounds.1.format("%Y-%m-%d").to_string(),
+                    session_count: 0,
+                    duration_seconds: 0.0,
+                    sessions: Vec::new(),
+                });
+            }
+            if let Some(group) = groups.last_mut() {
+                group.session_count += 1;
+                group.duration_seconds += item.duration_seconds;
+                group.sessions.push(item);
+            }
+        }
+        groups
+    }
+}
+
+#[tauri::command]
+pub async fn list_sessions_grouped(
+    state: tauri::State<'_, SpeechManager>,
+    granularity: GroupGranularity,
+) -> Result<Vec<SessionGroup>, String> {
+    Ok(state.list_sessions_grouped(granularity).await)
+}
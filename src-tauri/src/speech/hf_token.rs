@@ -0,0 +1,72 @@
+use keyring::Entry;
+use serde::Serialize;
+use tauri::async_runtime;
+
+use super::SpeechError;
+
+const KEYRING_SERVICE: &str = "Kk model downloads";
+const KEYRING_USER: &str = "huggingface-token";
+
+/// 只告诉前端是否已设置，令牌本身不离开系统钥匙串。
+#[derive(Debug, Serialize)]
+pub struct HfTokenStatus {
+    pub configured: bool,
+}
+
+fn entry() -> Result<Entry, SpeechError> {
+    Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|err| SpeechError::Credential(err.to_string()))
+}
+
+fn read_token() -> Result<Option<String>, SpeechError> {
+    match entry()?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(SpeechError::Credential(err.to_string())),
+    }
+}
+
+fn write_token(token: Option<&str>) -> Result<(), SpeechError> {
+    let entry = entry()?;
+    let result = match token {
+        Some(token) => entry.set_password(token),
+        None => match entry.delete_credential() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            other => other,
+        },
+    };
+    result.map_err(|err| SpeechError::Credential(err.to_string()))
+}
+
+/// 钥匙串不可用时按未设置处理，公开模型仍可下载。
+pub(super) async fn load_token() -> Option<String> {
+    let token = async_runtime::spawn_blocking(read_token)
+        .await
+        .map_err(|err| SpeechError::Join(err.to_string()))
+        .and_then(|token| token);
+    token.unwrap_or_else(|err| {
+        log::warn!("failed to read Hugging Face token: {err}");
+        None
+    })
+}
+
+/// 设置或清除（传空）受限仓库和部分镜像需要的 Hugging Face 访问令牌，保存在系统钥匙串中。
+#[tauri::command]
+pub async fn set_hf_token(token: Option<String>) -> Result<HfTokenStatus, String> {
+    let token = token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    let configured = token.is_some();
+    async_runtime::spawn_blocking(move || write_token(token.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(HfTokenStatus { configured })
+}
+
+#[tauri::command]
+pub async fn get_hf_token_status() -> Result<HfTokenStatus, String> {
+    Ok(HfTokenStatus {
+        configured: load_token().await.is_some(),
+    })
+}
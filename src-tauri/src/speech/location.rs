@@ -0,0 +1,177 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+
+use super::{ensure_writable, SpeechError, SpeechManager};
+use crate::storage::{probe_data_dir, write_atomic};
+
+const SPEECH_DIR: &str = "speech";
+/// 记录自定义存储位置，放在应用数据目录下，迁移后重启时据此找到语音数据。
+const LOCATION_FILENAME: &str = "speech_location.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpeechLocation {
+    #[serde(default)]
+    dir: Option<String>,
+}
+
+/// 启动时确定语音数据目录；自定义位置不可用（如移动硬盘未连接）时退回默认目录。
+pub(super) fn resolve_base_dir(data_dir: &Path) -> PathBuf {
+    let default_dir = data_dir.join(SPEECH_DIR);
+    let location_file = data_dir.join(LOCATION_FILENAME);
+    let Ok(content) = fs::read(&location_file) else {
+        return default_dir;
+    };
+    match serde_json::from_slice::<SpeechLocation>(&content) {
+        Ok(SpeechLocation { dir: Some(dir) }) if Path::new(&dir).is_dir() => PathBuf::from(dir),
+        Ok(SpeechLocation { dir: Some(dir) }) => {
+            log::warn!("speech storage dir {dir} is unavailable, using default location");
+            default_dir
+        }
+        Ok(SpeechLocation { dir: None }) => default_dir,
+        Err(err) => {
+            log::warn!("failed to read speech storage location: {err}");
+            default_dir
+        }
+    }
+}
+
+fn save_location(data_dir: &Path, base_dir: &Path) -> Result<(), SpeechError> {
+    let location_file = data_dir.join(LOCATION_FILENAME);
+    let default_dir = data_dir.canonicalize()?.join(SPEECH_DIR);
+    if base_dir == default_dir {
+        if location_file.exists() {
+            fs::remove_file(location_file)?;
+        }
+        return Ok(());
+    }
+    let location = SpeechLocation {
+        dir: Some(base_dir.to_string_lossy().into_owned()),
+    };
+    write_atomic(&location_file, &serde_json::to_vec_pretty(&location)?)?;
+    Ok(())
+}
+
+/// 复制时保留软链接（链接导入的模型），不把链接目标复制一份。
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&source, &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(fs::read_link(source)?, target)
+}
+
+/// 同一分区内直接重命名；跨分区时先完整复制再删除原目录，复制失败则清理目标目录。
+pub(super) fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(err) = copy_dir(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(err);
+    }
+    if let Err(err) = fs::remove_dir_all(from) {
+        log::warn!(
+            "failed to remove old speech storage {}: {err}",
+            from.display()
+        );
+    }
+    Ok(())
+}
+
+impl SpeechManager {
+    /// 把模型和转写记录整体移到 `parent/speech`。记录中的音频路径相对于该目录保存，无需改写；
+    /// 各文件路径在启动时确定，迁移完成后需要重启应用。
+    pub async fn relocate_storage(
+        &self,
+        data_dir: &Path,
+        parent: &Path,
+    ) -> Result<PathBuf, SpeechError> {
+        ensure_writable()?;
+        if !parent.is_absolute() || !parent.is_dir() {
+            return Err(SpeechError::InvalidStorageDir(parent.display().to_string()));
+        }
+        let parent = parent.canonicalize()?;
+        let current = self.base_dir.canonicalize()?;
+        let target = parent.join(SPEECH_DIR);
+        if target == current {
+            return Ok(target);
+        }
+        if target.starts_with(&current) {
+            return Err(SpeechError::InvalidStorageDir(
+                "不能迁移到当前存储目录内部".into(),
+            ));
+        }
+        if target.exists() && fs::read_dir(&target)?.next().is_some() {
+            return Err(SpeechError::InvalidStorageDir(format!(
+                "{} 已存在且不为空",
+                target.display()
+            )));
+        }
+        probe_data_dir(&parent)?;
+
+        // 迁移期间持有状态锁，避免新的转写或下载写入旧目录
+        let guard = self.state.lock().await;
+        if guard.active_transcription.is_some() || guard.model_download.is_some() {
+            return Err(SpeechError::StorageBusy);
+        }
+        if target.exists() {
+            fs::remove_dir(&target)?;
+        }
+        let (from, to) = (current.clone(), target.clone());
+        async_runtime::spawn_blocking(move || move_dir(&from, &to))
+            .await
+            .map_err(|e| SpeechError::Join(e.to_string()))??;
+        if let Err(err) = save_location(data_dir, &target) {
+            if let Err(revert) = move_dir(&target, &current) {
+                log::error!(
+                    "failed to move speech storage back to {}: {revert}",
+                    current.display()
+                );
+            }
+            return Err(err);
+        }
+        drop(guard);
+        Ok(target)
+    }
+}
+
+/// 迁移模型和转写记录的存储目录（如更大的第二块硬盘），完成后重启应用以使用新位置。
+#[tauri::command]
+pub async fn set_speech_storage_dir(
+    app: AppHandle,
+    state: tauri::State<'_, SpeechManager>,
+    path: String,
+) -> Result<(), String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    let target = state
+        .relocate_storage(&data_dir, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())?;
+    log::info!("speech storage moved to {}", target.display());
+    app.restart();
+}
@@ -0,0 +1,136 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use tauri::async_runtime;
+
+use super::compressed::{encode_wav, is_wav};
+use super::{
+    decode_audio, decode_audio_base64, transcribe_blocking, ActiveTranscriptionHandle, ChannelMode,
+    DecodedAudio, Preemption, SpeechError, SpeechLanguage, SpeechManager, SpeechModel,
+    TranscriptionOptions, WhisperTask,
+};
+
+/// 附加到待办等条目上的简短语音备忘，不生成转写记录。
+pub struct VoiceMemo {
+    pub audio_bytes: Vec<u8>,
+    pub duration_seconds: f32,
+    decoded: DecodedAudio,
+}
+
+impl VoiceMemo {
+    /// 解码前端录制的 Base64 音频，同时校验音频格式。
+    pub fn from_base64(data: &str) -> Result<Self, SpeechError> {
+        Self::from_bytes(decode_audio_base64(data)?)
+    }
+
+    /// 也接受 MP3、M4A 等压缩格式，保存的音频统一转为 WAV。
+    pub fn from_bytes(audio_bytes: Vec<u8>) -> Result<Self, SpeechError> {
+        let decoded = decode_audio(&audio_bytes)?;
+        let audio_bytes = if is_wav(&audio_bytes) {
+            audio_bytes
+        } else {
+            encode_wav(&decoded)?
+        };
+        let frames = decoded.samples.len() / decoded.channels.max(1);
+        let duration_seconds = frames as f32 / decoded.sample_rate.max(1) as f32;
+        Ok(Self {
+            audio_bytes,
+            duration_seconds,
+            decoded,
+        })
+    }
+}
+
+impl SpeechManager {
+    /// 备忘录很短，优先使用 small 模型；未下载时使用当前模型。
+    /// 文件转写进行中时先让它在当前切块结束后暂停，备忘录转写完再继续。
+    pub async fn transcribe_memo(
+        &self,
+        memo: VoiceMemo,
+        language: SpeechLanguage,
+    ) -> Result<String, SpeechError> {
+        let small = SpeechModel::default();
+        let model = if self.model_path(&small).exists() {
+            small
+        } else {
+            self.active_model().await
+        };
+        let model_path = self.model_path(&model);
+        if !model_path.exists() {
+            return Err(SpeechError::ModelNotDownloaded(model.id().to_string()));
+        }
+        self.ensure_language_supported(&model, language).await?;
+
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let preempted = self.preempt_file_transcription().await?;
+        let mut active_guard = if preempted.is_some() {
+            None
+        } else {
+            Some(
+                ActiveTranscriptionHandle::acquire(self.state.clone(), cancel_flag.clone(), None)
+                    .await?,
+            )
+        };
+        let contexts = self.whisper_contexts.clone();
+        let use_gpu = self.transcription_backend().await.use_gpu();
+        let decoding = self.decoding_settings().await;
+        let result = async_runtime::spawn_blocking(move || {
+            transcribe_blocking(
+                &contexts,
+                &model,
+                &model_path,
+                use_gpu,
+                &memo.decoded,
+                WhisperTask {
+                    language,
+                    translate: false,
+                    initial_prompt: None,
+                    options: TranscriptionOptions::default(),
+                },
+                ChannelMode::Mix,
+                decoding,
+                false,
+                cancel_flag,
+                None,
+                None,
+                None,
+            )
+        })
+        .await;
+        if let Some(preemption) = &preempted {
+            preemption.release();
+        }
+        if let Some(active_guard) = active_guard.as_mut() {
+            active_guard.release().await;
+        }
+        Ok(result
+            .map_err(|e| SpeechError::Join(e.to_string()))??
+            .transcript)
+    }
+
+    /// 请求进行中的文件转写暂停并等到它停下，没有可抢占的任务或它已先结束时返回 `None`。
+    async fn preempt_file_transcription(&self) -> Result<Option<Arc<Preemption>>, SpeechError> {
+        let preemption = {
+            let guard = self.state.lock().await;
+            let Some(active) = guard.active_transcription.as_ref() else {
+                return Ok(None);
+            };
+            match active.preemption.clone() {
+                Some(preemption) if preemption.request() => preemption,
+                _ => return Err(SpeechError::TranscriptionInProgress),
+            }
+        };
+        let parked = async_runtime::spawn_blocking({
+            let preemption = preemption.clone();
+            move || preemption.wait_parked()
+        })
+        .await;
+        match parked {
+            Ok(true) => Ok(Some(preemption)),
+            Ok(false) => Ok(None),
+            Err(err) => {
+                preemption.release();
+                Err(SpeechError::Join(err.to_string()))
+            }
+        }
+    }
+}
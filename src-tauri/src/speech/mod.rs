@@ -1,53 +1,182 @@
+mod acceleration;
+mod archive;
+mod audio_cache;
+mod benchmark;
+mod collections;
+mod compressed;
+mod context_cache;
+mod custom_models;
+mod decoding;
+mod diagnostics;
+mod diarization;
+mod drafts;
+mod grouping;
+mod hf_token;
+mod languages;
+mod location;
+mod memo;
+mod model_update;
+mod models;
+mod preempt;
+mod profile;
+mod prompt;
+mod recorder;
+mod recording_settings;
+mod retention;
+mod segments_csv;
+mod stub;
+mod timestamps;
+mod title_template;
+mod untranscribed;
+mod vad;
+mod words;
+
+pub use acceleration::{get_transcription_backends, set_transcription_backend};
+pub use archive::export_session_json;
+pub use benchmark::benchmark_speech_model;
+pub use collections::{
+    delete_smart_collection, evaluate_smart_collection, list_smart_collections,
+    save_smart_collection, SmartCollection,
+};
+pub use context_cache::preload_speech_model;
+pub use custom_models::import_speech_model;
+pub use decoding::{get_decoding_settings, set_decoding_settings, TranscriptionOptions};
+pub use diagnostics::{analyze_audio, analyze_session_audio};
+pub use diarization::{diarize_speech_session, rename_speaker};
+pub use drafts::{
+    autosave_transcript_draft, discard_transcript_draft, get_transcript_draft,
+    list_transcript_drafts,
+};
+pub use grouping::list_sessions_grouped;
+pub use hf_token::{get_hf_token_status, set_hf_token};
+pub use languages::{list_speech_languages, SpeechLanguage};
+pub use location::set_speech_storage_dir;
+pub use memo::VoiceMemo;
+pub use model_update::check_model_update;
+pub use models::{
+    cancel_model_download, delete_speech_model, ensure_speech_model, get_model_registry,
+    get_model_source, list_speech_models, pause_model_download, recommend_speech_model,
+    resume_model_download, set_model_source, set_speech_model,
+};
+pub use profile::SpeechSettingsProfile;
+pub use recorder::{
+    arm_recording, disarm_recording, get_recording_status, pause_recording, start_recording,
+    stop_recording, transcribe_recording, RecordingStatus, SpeechRecorder,
+};
+pub use recording_settings::{get_recording_settings, set_recording_settings};
+pub use retention::{
+    delete_retention_policy, list_retention_policies, run_retention_policies,
+    save_retention_policy, RetentionPolicy,
+};
+pub use segments_csv::export_segments_csv;
+pub use timestamps::validate_segments;
+pub use title_template::{get_title_template, set_title_template};
+pub use untranscribed::{
+    discard_untranscribed_recording, list_untranscribed_recordings,
+    transcribe_untranscribed_recording,
+};
+
+use audio_cache::{lock_cache, DecodedAudioCache};
+use context_cache::{lock_contexts, WhisperContextCache};
+use decoding::DecodingSettings;
+use diagnostics::AudioWarning;
+use location::{move_dir, resolve_base_dir};
+use models::{build_http_client, load_model_config, DownloadControl, ModelConfig, SpeechModel};
+use preempt::Preemption;
+use recording_settings::RecordingSettings;
+use title_template::{TitleContext, TitleTemplate};
+use untranscribed::{recover_orphaned_recordings, UntranscribedRecording};
+
 use std::{
     fs,
-    fs::File,
-    io::{self, Cursor, Write},
+    io::{self, Cursor},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use chrono::Local;
-use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::{async_runtime, AppHandle};
 use tauri::{Emitter, Manager};
 use thiserror::Error;
 use uuid::Uuid;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
-
-const MODEL_URL: &str =
-    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin?download=1";
-const MODEL_FILENAME: &str = "ggml-small.bin";
-const BUNDLED_MODEL_RELATIVE_PATH: &str = "models/ggml-small.bin";
-const MODEL_PROGRESS_EVENT: &str = "speech://model-progress";
-const MODEL_STATUS_EVENT: &str = "speech://model-status";
+use whisper_rs::{FullParams, SamplingStrategy, SegmentCallbackData, WhisperContext};
+
+use crate::badge;
+use crate::calendar::{CalendarEvent, CalendarManager};
+use crate::datetime::{cmp_instants, now_utc, to_utc_string};
+use crate::journal::OperationJournal;
+use crate::links::{EntityLink, LinkStore};
+use crate::network::is_offline;
+use crate::notifications::notify_transcription_finished;
+use crate::search::{SearchDocument, SearchEntityKind, SearchIndexHandle};
+use crate::storage::{is_read_only, load_json, prepare_dir, write_atomic};
+
+const IMPORT_PROGRESS_EVENT: &str = "speech://import-progress";
+const GPU_FALLBACK_EVENT: &str = "speech://gpu-fallback";
+const TRANSCRIBE_SEGMENT_EVENT: &str = "speech://transcribe-segment";
+const TRANSCRIBE_PROGRESS_EVENT: &str = "speech://transcribe-progress";
+/// 有效语音不足该时长时不运行模型，避免空录音产生幻觉文本。
+const MIN_VOICED_SECONDS: f32 = 0.3;
+const WHISPER_SAMPLE_RATE: usize = 16_000;
+/// 长音频按块依次转写，每块结束后上报进度。
+const CHUNK_SECONDS: usize = 180;
+/// 相邻块重叠的时长，避免块边界切断词语。
+const CHUNK_OVERLAP_SECONDS: usize = 4;
 
 pub struct SpeechManager {
     base_dir: PathBuf,
-    model_path: PathBuf,
+    models_dir: PathBuf,
+    model_config_file: PathBuf,
     sessions_dir: PathBuf,
     sessions_file: PathBuf,
+    collections_file: PathBuf,
+    retention_file: PathBuf,
+    decoding_file: PathBuf,
+    recording_file: PathBuf,
+    title_template_file: PathBuf,
     state: Arc<async_runtime::Mutex<SpeechState>>,
-    http: Client,
+    audio_cache: Mutex<DecodedAudioCache>,
+    whisper_contexts: Arc<Mutex<WhisperContextCache>>,
+    indexer: SearchIndexHandle,
 }
 
 struct SpeechState {
     sessions: Vec<SpeechSession>,
+    collections: Vec<collections::SmartCollection>,
+    retention_policies: Vec<retention::RetentionPolicy>,
+    model_config: ModelConfig,
+    decoding: DecodingSettings,
+    recording: RecordingSettings,
+    title_template: TitleTemplate,
+    http: Client,
     active_transcription: Option<ActiveTranscription>,
+    model_download: Option<Arc<DownloadControl>>,
 }
 
 struct ActiveTranscription {
     cancel_flag: Arc<AtomicBool>,
+    model: SpeechModel,
+    /// 文件转写可被听写抢占，听写和基准测试为 `None`。
+    preemption: Option<Arc<Preemption>>,
 }
 
 impl ActiveTranscription {
-    fn new(cancel_flag: Arc<AtomicBool>) -> Self {
-        Self { cancel_flag }
+    fn new(
+        cancel_flag: Arc<AtomicBool>,
+        model: SpeechModel,
+        preemption: Option<Arc<Preemption>>,
+    ) -> Self {
+        Self {
+            cancel_flag,
+            model,
+            preemption,
+        }
     }
 
     fn cancel(&self) {
@@ -57,6 +186,8 @@ impl ActiveTranscription {
 
 struct ActiveTranscriptionHandle {
     state: Arc<async_runtime::Mutex<SpeechState>>,
+    model: SpeechModel,
+    preemption: Option<Arc<Preemption>>,
     released: bool,
 }
 
@@ -64,16 +195,25 @@ impl ActiveTranscriptionHandle {
     async fn acquire(
         state: Arc<async_runtime::Mutex<SpeechState>>,
         cancel_flag: Arc<AtomicBool>,
+        preemption: Option<Arc<Preemption>>,
     ) -> Result<Self, SpeechError> {
-        {
+        let model = {
             let mut guard = state.lock().await;
             if guard.active_transcription.is_some() {
                 return Err(SpeechError::TranscriptionInProgress);
             }
-            guard.active_transcription = Some(ActiveTranscription::new(cancel_flag));
-        }
+            let model = guard.model_config.active.clone();
+            guard.active_transcription = Some(ActiveTranscription::new(
+                cancel_flag,
+                model.clone(),
+                preemption.clone(),
+            ));
+            model
+        };
         Ok(Self {
             state: state.clone(),
+            model,
+            preemption,
             released: false,
         })
     }
@@ -84,6 +224,10 @@ impl ActiveTranscriptionHandle {
         }
         let mut guard = self.state.lock().await;
         guard.active_transcription = None;
+        // 先清空再通知，等待抢占的听写随后可以直接占用
+        if let Some(preemption) = &self.preemption {
+            preemption.finish();
+        }
         self.released = true;
     }
 }
@@ -94,9 +238,13 @@ impl Drop for ActiveTranscriptionHandle {
             return;
         }
         let state = self.state.clone();
+        let preemption = self.preemption.take();
         async_runtime::spawn(async move {
             let mut guard = state.lock().await;
             guard.active_transcription = None;
+            if let Some(preemption) = preemption {
+                preemption.finish();
+            }
         });
     }
 }
@@ -129,6 +277,83 @@ pub enum SpeechError {
     TranscriptionInProgress,
     #[error("转写已取消")]
     TranscriptionCancelled,
+    #[error("录音中没有检测到语音")]
+    NoSpeechDetected,
+    #[error("未找到指定的智能集合：{0}")]
+    CollectionNotFound(String),
+    #[error("智能集合无效：{0}")]
+    InvalidCollection(String),
+    #[error("未找到待转写的录音：{0}")]
+    UntranscribedNotFound(String),
+    #[error("未找到指定的保留策略：{0}")]
+    RetentionPolicyNotFound(String),
+    #[error("保留策略无效：{0}")]
+    InvalidRetentionPolicy(String),
+    #[error("数据目录不可写，当前处于只读模式")]
+    ReadOnly,
+    #[error("模型 {0} 校验失败（checksum mismatch），已删除损坏的文件")]
+    ChecksumMismatch(String),
+    #[error("模型下载源无效：{0}")]
+    InvalidModelSource(String),
+    #[error("未知的模型：{0}")]
+    UnknownModel(String),
+    #[error("导入的模型 {0} 文件已丢失，请重新导入")]
+    CustomModelMissing(String),
+    #[error("不是有效的 whisper.cpp ggml 模型文件")]
+    InvalidModelFile,
+    #[error("模型 {0} 正在用于转写，无法删除")]
+    ModelInUse(String),
+    #[error("模型 {0} 正在下载，请先取消下载再删除")]
+    ModelDownloading(String),
+    #[error("解码参数无效：{0}")]
+    InvalidDecodingSettings(String),
+    #[error("录音设置无效：{0}")]
+    InvalidRecordingSettings(String),
+    #[error("标题模板无效：{0}")]
+    InvalidTitleTemplate(String),
+    #[error("模型下载失败，状态码 {0}")]
+    ModelDownloadFailed(String),
+    #[error("模型下载已取消")]
+    ModelDownloadCancelled,
+    #[error("模型下载已暂停")]
+    ModelDownloadPaused,
+    #[error("模型 {0} 尚未下载")]
+    ModelNotDownloaded(String),
+    #[error("模型 {model} 仅支持英语，无法转写{language}")]
+    LanguageNotSupported {
+        model: String,
+        language: &'static str,
+    },
+    #[error("Core ML 编码器下载失败：{0}")]
+    CoreMlEncoder(String),
+    #[error("当前版本未包含 {} 转写后端", .0.label())]
+    BackendUnavailable(acceleration::TranscriptionBackend),
+    #[error("未找到基准测试音频，安装包可能不完整")]
+    BenchmarkSampleMissing,
+    #[error("存储目录无效：{0}")]
+    InvalidStorageDir(String),
+    #[error("正在转写或下载模型，暂时无法迁移存储目录")]
+    StorageBusy,
+    #[error("已开启离线模式，无法联网下载或检查模型")]
+    Offline,
+    #[error("访问令牌不可用：{0}")]
+    Credential(String),
+    #[error("录音失败：{0}")]
+    Recording(String),
+    #[error("已有录音正在进行")]
+    RecordingInProgress,
+    #[error("当前没有进行中的录音")]
+    NoActiveRecording,
+    #[error("未找到录好的音频：{0}")]
+    RecordingNotFound(String),
+    #[error("该转写的录音已被清理")]
+    AudioRemoved,
+    #[error("说话人名称不能为空")]
+    InvalidSpeakerName,
+    #[error("初始提示和词汇表合计不能超过 {0} 个字")]
+    InitialPromptTooLong(usize),
+    #[error("未找到说话人：{0}")]
+    SpeakerNotFound(String),
 }
 
 impl From<hound::Error> for SpeechError {
@@ -143,48 +368,36 @@ impl From<whisper_rs::WhisperError> for SpeechError {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-pub enum SpeechLanguage {
-    #[serde(rename = "en")]
-    English,
-    #[serde(rename = "zh")]
-    Chinese,
-}
-
-impl SpeechLanguage {
-    pub fn code(&self) -> &'static str {
-        match self {
-            SpeechLanguage::English => "en",
-            SpeechLanguage::Chinese => "zh",
-        }
-    }
-
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            SpeechLanguage::English => "英语",
-            SpeechLanguage::Chinese => "中文",
-        }
-    }
-}
-
-impl TryFrom<&str> for SpeechLanguage {
-    type Error = SpeechError;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.to_lowercase().as_str() {
-            "en" | "english" => Ok(SpeechLanguage::English),
-            "zh" | "zh-cn" | "chinese" | "zh-hans" => Ok(SpeechLanguage::Chinese),
-            other => Err(SpeechError::UnsupportedLanguage(other.to_string())),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptSegment {
     pub start: f32,
     pub end: f32,
     pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// 逐词时间，用于点击跳转和逐词高亮；实时转写事件中的片段没有这一项。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTiming>,
+    /// whisper 对本段文字 token 的平均概率（0 到 1）；旧会话和实时转写事件中的片段没有这一项。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordTiming {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMode {
+    #[default]
+    Mix,
+    Left,
+    Right,
+    Speakers,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -196,6 +409,35 @@ pub struct SpeechSession {
     pub segments: Vec<TranscriptSegment>,
     pub audio_path: String,
     pub created_at: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_event: Option<CalendarEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audio_warnings: Vec<AudioWarning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_removed_at: Option<String>,
+    /// GPU 转写失败后改用 CPU 重试时记录的 GPU 错误。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_fallback: Option<String>,
+    /// 文字稿是 whisper 翻译成的英文，`language` 仍为录音的原始语言。
+    #[serde(default)]
+    pub translated: bool,
+    /// 转写时使用的自定义提示（含词汇表）；为空表示使用默认提示。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_prompt: Option<String>,
+    /// 转写时实际使用的解码参数，便于复现；旧会话和导入的会话为空。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription_options: Option<TranscriptionOptions>,
+}
+
+impl SpeechSession {
+    pub fn duration_seconds(&self) -> f32 {
+        self.segments
+            .iter()
+            .map(|segment| segment.end)
+            .fold(0.0, f32::max)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -208,53 +450,55 @@ pub struct SpeechSessionBackup {
     pub created_at: String,
     pub audio_filename: String,
     pub audio_base64: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ModelStatusResponse {
-    pub ready: bool,
-    pub downloaded: bool,
-    pub model_path: Option<String>,
-}
-
-impl ModelStatusResponse {
-    fn ready(path: &Path, downloaded: bool) -> Self {
-        Self {
-            ready: true,
-            downloaded,
-            model_path: Some(path.to_string_lossy().into_owned()),
-        }
-    }
-}
-
-#[derive(Debug, Serialize)]
-pub struct ModelDownloadProgress {
-    pub downloaded_bytes: u64,
-    pub total_bytes: Option<u64>,
-}
-
-#[derive(Debug, Clone, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ModelStatusKind {
-    Exists,
-    Downloading,
-    Finished,
-    Failed,
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct ModelStatusEvent {
-    pub status: ModelStatusKind,
-    pub model_path: Option<String>,
-    pub message: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub calendar_event: Option<CalendarEvent>,
+    #[serde(default)]
+    pub audio_removed_at: Option<String>,
+    #[serde(default)]
+    pub translated: bool,
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    #[serde(default)]
+    pub transcription_options: Option<TranscriptionOptions>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TranscribeAudioPayload {
+    #[serde(default)]
     pub audio_base64: String,
+    /// 本机音频文件的路径，与 `audio_base64` 二选一；大文件应使用路径，避免 Base64 占用内存和 IPC。
+    #[serde(default)]
+    pub audio_path: Option<String>,
     pub language: String,
     #[serde(default)]
     pub session_title: Option<String>,
+    /// 标题模板中 `{project}` 的取值。
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub recorded_at: Option<String>,
+    #[serde(default)]
+    pub channel_mode: ChannelMode,
+    /// 转写完成后按声纹区分说话人，失败时保留转写结果。
+    #[serde(default)]
+    pub diarize: bool,
+    /// 转写前跳过较长的静音，加快长录音的转写；时间戳仍对应原始音频。
+    #[serde(default)]
+    pub vad_enabled: bool,
+    /// 使用 whisper 的翻译模式直接输出英文；`language` 填写录音的原始语言。
+    #[serde(default)]
+    pub translate: bool,
+    /// 自定义初始提示，引导识别的用词和风格。
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// 希望优先识别的人名、术语和产品名，会拼入初始提示。
+    #[serde(default)]
+    pub vocabulary: Vec<String>,
+    /// 覆盖全局解码设置的高级参数。
+    #[serde(default)]
+    pub options: TranscriptionOptions,
 }
 
 #[derive(Debug, Serialize)]
@@ -269,177 +513,177 @@ pub struct UpdateSpeechSessionPayload {
     pub transcript: Option<String>,
     #[serde(default)]
     pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
 }
 
-struct TranscriptionResult {
-    transcript: String,
-    segments: Vec<TranscriptSegment>,
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportItemStatus {
+    Imported,
+    Skipped,
+    Failed,
 }
 
-impl SpeechManager {
-    pub fn new(app: &AppHandle) -> Result<Self, SpeechError> {
-        let base_dir = app.path().app_local_data_dir()?;
-        let base_dir = base_dir.join("speech");
-        fs::create_dir_all(&base_dir)?;
-
-        let model_path = base_dir.join(MODEL_FILENAME);
-        let sessions_dir = base_dir.join("sessions");
-        fs::create_dir_all(&sessions_dir)?;
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgressEvent {
+    pub current: usize,
+    pub total: usize,
+    pub session_id: String,
+    pub title: String,
+    pub status: ImportItemStatus,
+    pub message: Option<String>,
+}
 
-        let sessions_file = base_dir.join("sessions.json");
-        let sessions = if sessions_file.exists() {
-            let content = fs::read(&sessions_file)?;
-            serde_json::from_slice::<Vec<SpeechSession>>(&content)?
-        } else {
-            Vec::new()
-        };
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFailure {
+    pub session_id: String,
+    pub title: String,
+    pub reason: String,
+}
 
-        if !sessions_file.exists() {
-            fs::write(&sessions_file, b"[]")?;
-        }
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSessionsReport {
+    pub total: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: Vec<ImportFailure>,
+}
 
-        Ok(Self {
-            base_dir,
-            model_path,
-            sessions_dir,
-            sessions_file,
-            state: Arc::new(async_runtime::Mutex::new(SpeechState {
-                sessions,
-                active_transcription: None,
-            })),
-            http: Client::new(),
-        })
-    }
+/// 待转写音频的来源。
+enum AudioInput {
+    Bytes(Vec<u8>),
+    /// 本机上的音频文件，复制到会话目录（非 WAV 时转码），不整体读入内存。
+    File(PathBuf),
+    /// `stop_recording` 已写入会话目录的录音，值为会话 id。
+    Recorded(String),
+}
 
-    pub async fn ensure_model(&self, app: &AppHandle) -> Result<ModelStatusResponse, SpeechError> {
-        if self.model_path.exists() {
-            let event = ModelStatusEvent {
-                status: ModelStatusKind::Exists,
-                model_path: Some(self.model_path.to_string_lossy().into_owned()),
-                message: None,
-            };
-            let _ = app.emit(MODEL_STATUS_EVENT, event);
-            return Ok(ModelStatusResponse::ready(&self.model_path, false));
-        }
+struct TranscriptionRequest {
+    audio: AudioInput,
+    language: SpeechLanguage,
+    title: Option<String>,
+    project: Option<String>,
+    calendar_event: Option<CalendarEvent>,
+    channel_mode: ChannelMode,
+    vad_enabled: bool,
+    translate: bool,
+    /// 已拼好的自定义提示。
+    initial_prompt: Option<String>,
+    options: TranscriptionOptions,
+    retry_of: Option<String>,
+    /// 设置后每解码出一段就发送 `speech://transcribe-segment`，每转写完一块发送
+    /// `speech://transcribe-progress`，供前端实时显示。
+    app: Option<AppHandle>,
+}
 
-        if let Some(parent) = self.model_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+/// 解码出一段文字时调用，参数为第几轮转写（GPU 失败改用 CPU 重转时加 1）和该段内容。
+type SegmentSink = Arc<dyn Fn(u32, TranscriptSegment) + Send + Sync>;
 
-        if self.try_copy_bundled_model(app)? {
-            let finish_event = ModelStatusEvent {
-                status: ModelStatusKind::Finished,
-                model_path: Some(self.model_path.to_string_lossy().into_owned()),
-                message: Some("使用内置模型".into()),
-            };
-            let _ = app.emit(MODEL_STATUS_EVENT, finish_event);
-            return Ok(ModelStatusResponse::ready(&self.model_path, false));
-        }
+/// 转写进度回调，参数为第几轮转写、已完成的块数、总块数和 whisper 报告的当前块进度（0-100）。
+type ProgressSink = Arc<dyn Fn(u32, usize, usize, u8) + Send + Sync>;
 
-        let start_event = ModelStatusEvent {
-            status: ModelStatusKind::Downloading,
-            model_path: Some(self.model_path.to_string_lossy().into_owned()),
-            message: None,
-        };
-        let _ = app.emit(MODEL_STATUS_EVENT, start_event);
-
-        match self.download_model(app).await {
-            Ok(()) => {
-                let finish_event = ModelStatusEvent {
-                    status: ModelStatusKind::Finished,
-                    model_path: Some(self.model_path.to_string_lossy().into_owned()),
-                    message: None,
-                };
-                let _ = app.emit(MODEL_STATUS_EVENT, finish_event);
-                Ok(ModelStatusResponse::ready(&self.model_path, true))
-            }
-            Err(err) => {
-                let _ = app.emit(
-                    MODEL_STATUS_EVENT,
-                    ModelStatusEvent {
-                        status: ModelStatusKind::Failed,
-                        model_path: Some(self.model_path.to_string_lossy().into_owned()),
-                        message: Some(err.to_string()),
-                    },
-                );
-                if self.model_path.exists() {
-                    let _ = fs::remove_file(&self.model_path);
-                }
-                Err(err)
-            }
-        }
-    }
+/// 单块转写中 whisper 报告的进度百分比。
+type ChunkProgress = Box<dyn FnMut(i32)>;
 
-    fn try_copy_bundled_model(&self, app: &AppHandle) -> Result<bool, SpeechError> {
-        let mut candidate_files: Vec<PathBuf> = Vec::new();
+/// 实时转写的一段；`pass` 变化时说明从头重新转写，前端应清空已显示的内容。
+/// 最终结果以转写完成后返回的会话为准（可能过滤掉了幻觉片段）。
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscribeSegmentEvent {
+    pub session_id: String,
+    pub pass: u32,
+    pub segment: TranscriptSegment,
+}
 
-        if let Ok(resource_dir) = app.path().resource_dir() {
-            let search_dirs = [
-                resource_dir.clone(),
-                resource_dir.join("resources"),
-                resource_dir.join("Resources"),
-                resource_dir.join("../resources"),
-                resource_dir.join("../Resources"),
-            ];
+/// 转写进度，`percent` 包含 whisper 报告的块内进度，只有一块时也会逐步增加；
+/// `chunk` 为正在转写的块（从 1 开始），全部完成时等于 `total_chunks`。
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscribeProgressEvent {
+    pub session_id: String,
+    pub pass: u32,
+    pub percent: u8,
+    pub chunk: usize,
+    pub total_chunks: usize,
+}
 
-            for dir in search_dirs {
-                candidate_files.push(dir.join(BUNDLED_MODEL_RELATIVE_PATH));
-            }
-        }
+struct TranscriptionResult {
+    transcript: String,
+    segments: Vec<TranscriptSegment>,
+    audio_warnings: Vec<AudioWarning>,
+    gpu_fallback: Option<String>,
+}
 
-        if let Some(manifest_dir) = option_env!("CARGO_MANIFEST_DIR") {
-            candidate_files.push(
-                Path::new(manifest_dir)
-                    .join("resources")
-                    .join(BUNDLED_MODEL_RELATIVE_PATH),
+impl SpeechManager {
+    pub fn new(app: &AppHandle, indexer: SearchIndexHandle) -> Result<Self, SpeechError> {
+        let base_dir = resolve_base_dir(&app.path().app_local_data_dir()?);
+        prepare_dir(&base_dir)?;
+
+        let (models_dir, model_config_file, model_config) = load_model_config(&base_dir)?;
+        let http = build_http_client(&model_config.source).unwrap_or_else(|err| {
+            log::warn!("invalid model source settings, falling back to direct connection: {err}");
+            Client::new()
+        });
+        let sessions_dir = base_dir.join("sessions");
+        prepare_dir(&sessions_dir)?;
+        if stub::enabled() {
+            log::warn!(
+                "{} is set, transcriptions return canned text",
+                stub::STUB_ENV
             );
         }
 
-        candidate_files.push(Path::new("resources").join(BUNDLED_MODEL_RELATIVE_PATH));
-        candidate_files.push(
-            Path::new("src-tauri")
-                .join("resources")
-                .join(BUNDLED_MODEL_RELATIVE_PATH),
-        );
+        let sessions_file = base_dir.join("sessions.json");
+        let sessions: Vec<SpeechSession> = load_json(&sessions_file, Vec::new);
 
-        for candidate in candidate_files {
-            if candidate.exists() {
-                fs::copy(&candidate, &self.model_path)?;
-                return Ok(true);
+        if !is_read_only() {
+            if !sessions_file.exists() {
+                fs::write(&sessions_file, b"[]")?;
             }
+            recover_orphaned_recordings(&base_dir, &sessions_dir, &sessions);
         }
 
-        Ok(false)
-    }
+        let collections_file = base_dir.join("collections.json");
+        let collections = load_json(&collections_file, Vec::new);
 
-    async fn download_model(&self, app: &AppHandle) -> Result<(), SpeechError> {
-        let response = self.http.get(MODEL_URL).send().await?;
-        if !response.status().is_success() {
-            return Err(SpeechError::Audio(format!(
-                "模型下载失败，状态码 {}",
-                response.status()
-            )));
-        }
+        let retention_file = base_dir.join("retention.json");
+        let retention_policies = load_json(&retention_file, Vec::new);
 
-        let total = response.content_length();
-        let mut file = File::create(&self.model_path)?;
-        let mut downloaded: u64 = 0;
-        let mut stream = response.bytes_stream();
+        let decoding_file = base_dir.join("decoding.json");
+        let decoding = load_json(&decoding_file, DecodingSettings::default);
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            file.write_all(&chunk)?;
-            downloaded += chunk.len() as u64;
-            let progress = ModelDownloadProgress {
-                downloaded_bytes: downloaded,
-                total_bytes: total,
-            };
-            let _ = app.emit(MODEL_PROGRESS_EVENT, &progress);
-        }
+        let recording_file = base_dir.join("recording.json");
+        let recording = load_json(&recording_file, RecordingSettings::default);
 
-        file.flush()?;
+        let title_template_file = base_dir.join("title_template.json");
+        let title_template = load_json(&title_template_file, TitleTemplate::default);
 
-        Ok(())
+        Ok(Self {
+            base_dir,
+            models_dir,
+            model_config_file,
+            sessions_dir,
+            sessions_file,
+            collections_file,
+            retention_file,
+            decoding_file,
+            recording_file,
+            title_template_file,
+            state: Arc::new(async_runtime::Mutex::new(SpeechState {
+                sessions,
+                collections,
+                retention_policies,
+                model_config,
+                decoding,
+                recording,
+                title_template,
+                http,
+                active_transcription: None,
+                model_download: None,
+            })),
+            audio_cache: Mutex::new(DecodedAudioCache::default()),
+            whisper_contexts: Arc::new(Mutex::new(WhisperContextCache::default())),
+            indexer,
+        })
     }
 
     pub async fn list_sessions(&self) -> Vec<SpeechSession> {
@@ -447,20 +691,73 @@ impl SpeechManager {
         guard.sessions.clone()
     }
 
-    pub async fn delete_session(&self, session_id: &str) -> Result<(), SpeechError> {
+    pub async fn has_session(&self, session_id: &str) -> bool {
+        let guard = self.state.lock().await;
+        guard
+            .sessions
+            .iter()
+            .any(|session| session.id == session_id)
+    }
+
+    /// 删除会话，会话目录移到 `stash_dir` 以便撤销；返回会话原来的位置和内容，不存在时返回 `None`。
+    pub async fn delete_session(
+        &self,
+        session_id: &str,
+        stash_dir: &Path,
+    ) -> Result<Option<(usize, SpeechSession)>, SpeechError> {
+        ensure_writable()?;
         let mut guard = self.state.lock().await;
-        if let Some(index) = guard
+        let Some(index) = guard
             .sessions
             .iter()
             .position(|session| session.id == session_id)
-        {
-            let session = guard.sessions.remove(index);
-            self.persist_sessions(&guard.sessions)?;
-            let session_dir = self.sessions_dir.join(session.id);
-            if session_dir.exists() {
-                fs::remove_dir_all(session_dir)?;
+        else {
+            return Ok(None);
+        };
+        // 先移走目录再写记录，移动失败时会话保持原样；写入失败时把目录移回去
+        let session_dir = self.sessions_dir.join(session_id);
+        let moved = session_dir.exists();
+        lock_cache(&self.audio_cache).invalidate(session_id);
+        if moved {
+            move_dir(&session_dir, stash_dir)?;
+        }
+        let session = guard.sessions.remove(index);
+        if let Err(err) = self.persist_sessions(&guard.sessions) {
+            guard.sessions.insert(index, session);
+            if moved {
+                if let Err(restore_err) = move_dir(stash_dir, &session_dir) {
+                    log::error!("failed to restore session dir {session_id}: {restore_err}");
+                }
             }
+            return Err(err);
         }
+        self.indexer.remove(SearchEntityKind::Session, &session.id);
+        Ok(Some((index, session)))
+    }
+
+    /// 撤销删除：移回暂存的会话目录，会话放回原来的位置。
+    pub async fn restore_session(
+        &self,
+        index: usize,
+        session: SpeechSession,
+        stash_dir: &Path,
+    ) -> Result<(), SpeechError> {
+        ensure_writable()?;
+        let mut guard = self.state.lock().await;
+        if guard
+            .sessions
+            .iter()
+            .any(|existing| existing.id == session.id)
+        {
+            return Ok(());
+        }
+        if stash_dir.exists() {
+            move_dir(stash_dir, &self.sessions_dir.join(&session.id))?;
+        }
+        let index = index.min(guard.sessions.len());
+        guard.sessions.insert(index, session.clone());
+        self.persist_sessions(&guard.sessions)?;
+        self.indexer.upsert(session_search_document(&session));
         Ok(())
     }
 
@@ -468,10 +765,12 @@ impl SpeechManager {
         &self,
         payload: UpdateSpeechSessionPayload,
     ) -> Result<SpeechSession, SpeechError> {
+        ensure_writable()?;
         let UpdateSpeechSessionPayload {
             session_id,
             transcript,
             title,
+            tags,
         } = payload;
 
         let mut guard = self.state.lock().await;
@@ -488,14 +787,25 @@ impl SpeechManager {
             }
         }
 
+        if let Some(tags) = tags {
+            session.tags = normalize_tags(tags);
+        }
+
         if let Some(transcript) = transcript {
             session.transcript = transcript.clone();
             let transcript_path = self.sessions_dir.join(&session.id).join("transcript.txt");
             fs::write(&transcript_path, transcript.as_bytes())?;
+            if let Err(err) = self.discard_draft(&session.id) {
+                log::warn!(
+                    "failed to remove transcript draft for {}: {err}",
+                    session.id
+                );
+            }
         }
 
         let result = session.clone();
         self.persist_sessions(&guard.sessions)?;
+        self.indexer.upsert(session_search_document(&result));
         Ok(result)
     }
 
@@ -511,14 +821,100 @@ impl SpeechManager {
 
     pub async fn transcribe_audio(
         &self,
+        app: &AppHandle,
         payload: TranscribeAudioPayload,
+        calendar_event: Option<CalendarEvent>,
     ) -> Result<SpeechSession, SpeechError> {
         let language = SpeechLanguage::try_from(payload.language.as_str())?;
-        let audio_bytes = decode_audio_base64(&payload.audio_base64)?;
+        let audio = match (payload.audio_path, payload.audio_base64.is_empty()) {
+            (Some(path), true) => {
+                let path = PathBuf::from(path);
+                if !path.is_file() {
+                    return Err(SpeechError::Audio(format!(
+                        "音频文件不存在：{}",
+                        path.display()
+                    )));
+                }
+                AudioInput::File(path)
+            }
+            (None, false) => AudioInput::Bytes(decode_audio_base64(&payload.audio_base64)?),
+            _ => {
+                return Err(SpeechError::Audio(
+                    "audio_base64 和 audio_path 需要且只能提供一个".into(),
+                ))
+            }
+        };
+        let session = self
+            .transcribe_bytes(TranscriptionRequest {
+                audio,
+                language,
+                title: payload.session_title,
+                project: payload.project,
+                calendar_event,
+                channel_mode: payload.channel_mode,
+                vad_enabled: payload.vad_enabled,
+                translate: payload.translate,
+                initial_prompt: prompt::compose(
+                    payload.initial_prompt.as_deref(),
+                    &payload.vocabulary,
+                    language,
+                )?,
+                options: payload.options,
+                retry_of: None,
+                app: Some(app.clone()),
+            })
+            .await?;
+        if !payload.diarize {
+            return Ok(session);
+        }
+        match self.diarize_session(&session.id, None).await {
+            Ok(diarized) => Ok(diarized),
+            Err(err) => {
+                log::warn!(
+                    "speaker diarization failed for session {}: {err}",
+                    session.id
+                );
+                Ok(session)
+            }
+        }
+    }
+
+    async fn transcribe_bytes(
+        &self,
+        request: TranscriptionRequest,
+    ) -> Result<SpeechSession, SpeechError> {
+        ensure_writable()?;
+        let TranscriptionRequest {
+            audio,
+            language,
+            title: title_override,
+            project,
+            calendar_event,
+            channel_mode,
+            vad_enabled,
+            translate,
+            initial_prompt,
+            options,
+            retry_of,
+            app,
+        } = request;
+        options.validate()?;
+        // 英文录音翻译成英文与直接转写相同
+        let translate = translate && language != SpeechLanguage::ENGLISH;
         let cancel_flag = Arc::new(AtomicBool::new(false));
-        let mut active_guard =
-            ActiveTranscriptionHandle::acquire(self.state.clone(), cancel_flag.clone()).await?;
-        let session_id = Uuid::new_v4().to_string();
+        let mut active_guard = ActiveTranscriptionHandle::acquire(
+            self.state.clone(),
+            cancel_flag.clone(),
+            Some(Arc::new(Preemption::default())),
+        )
+        .await?;
+        if let Some(app) = &app {
+            badge::refresh(app).await;
+        }
+        let session_id = match &audio {
+            AudioInput::Recorded(session_id) => session_id.clone(),
+            _ => Uuid::new_v4().to_string(),
+        };
         let session_dir = self.sessions_dir.join(&session_id);
         if let Err(err) = fs::create_dir_all(&session_dir) {
             active_guard.release().await;
@@ -534,30 +930,103 @@ impl SpeechManager {
                 return Err(err.into());
             }
         }
-        if let Err(err) = fs::write(&audio_path, &audio_bytes) {
+        // 压缩格式先转为 WAV，会话目录中的 recording.wav 始终可以直接播放和分析
+        let stored = async_runtime::spawn_blocking({
+            let audio_path = audio_path.clone();
+            move || -> Result<(), SpeechError> {
+                match audio {
+                    AudioInput::Bytes(audio_bytes) => {
+                        fs::write(&audio_path, compressed::into_wav(audio_bytes)?)?;
+                        Ok(())
+                    }
+                    AudioInput::File(source) => compressed::copy_as_wav(&source, &audio_path),
+                    AudioInput::Recorded(_) => Ok(()),
+                }
+            }
+        })
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))
+        .and_then(|result| result);
+        if let Err(err) = stored {
             active_guard.release().await;
             let _ = fs::remove_dir_all(&session_dir);
-            return Err(err.into());
+            return Err(err);
         }
 
-        let model_path = self.model_path.clone();
-        let title_override = payload.session_title.clone();
-        let audio_for_transcription = audio_bytes;
-
-        let transcription_result = match async_runtime::spawn_blocking({
-            let cancel_flag = cancel_flag.clone();
-            move || {
-                transcribe_blocking(&model_path, &audio_for_transcription, language, cancel_flag)
-            }
-        })
-        .await
-        {
-            Ok(result) => result,
-            Err(err) => {
-                active_guard.release().await;
-                let _ = fs::remove_dir_all(&session_dir);
-                return Err(SpeechError::Join(err.to_string()));
-            }
+        let model = active_guard.model.clone();
+        let model_path = self.model_path(&model);
+        let contexts = self.whisper_contexts.clone();
+        let use_gpu = self.transcription_backend().await.use_gpu();
+        let decoding = options.apply(self.decoding_settings().await);
+        let on_segment = app.clone().map(|app| {
+            let session_id = session_id.clone();
+            Arc::new(move |pass: u32, segment: TranscriptSegment| {
+                let _ = app.emit(
+                    TRANSCRIBE_SEGMENT_EVENT,
+                    TranscribeSegmentEvent {
+                        session_id: session_id.clone(),
+                        pass,
+                        segment,
+                    },
+                );
+            }) as SegmentSink
+        });
+        let on_progress = app.map(|app| {
+            let session_id = session_id.clone();
+            Arc::new(
+                move |pass: u32, done: usize, total: usize, chunk_percent: u8| {
+                    let percent = (done * 100 + usize::from(chunk_percent)) / total.max(1);
+                    let _ = app.emit(
+                        TRANSCRIBE_PROGRESS_EVENT,
+                        TranscribeProgressEvent {
+                            session_id: session_id.clone(),
+                            pass,
+                            percent: percent.min(100) as u8,
+                            chunk: (done + 1).min(total),
+                            total_chunks: total,
+                        },
+                    );
+                },
+            ) as ProgressSink
+        });
+        let decoded = match self.ensure_language_supported(&model, language).await {
+            Ok(()) => self.decoded_audio(&session_id, &audio_path).await,
+            Err(err) => Err(err),
+        };
+        let transcription_result = match decoded {
+            Ok(decoded) => match async_runtime::spawn_blocking({
+                let cancel_flag = cancel_flag.clone();
+                let preemption = active_guard.preemption.clone();
+                let task = WhisperTask {
+                    language,
+                    translate,
+                    initial_prompt: initial_prompt.clone(),
+                    options,
+                };
+                move || {
+                    transcribe_blocking(
+                        &contexts,
+                        &model,
+                        &model_path,
+                        use_gpu,
+                        &decoded,
+                        task,
+                        channel_mode,
+                        decoding,
+                        vad_enabled,
+                        cancel_flag,
+                        preemption,
+                        on_segment,
+                        on_progress,
+                    )
+                }
+            })
+            .await
+            {
+                Ok(result) => result,
+                Err(err) => Err(SpeechError::Join(err.to_string())),
+            },
+            Err(err) => Err(err),
         };
 
         let transcription = match transcription_result {
@@ -567,20 +1036,43 @@ impl SpeechManager {
             }
             Err(err) => {
                 active_guard.release().await;
-                let _ = fs::remove_dir_all(&session_dir);
+                if retry_of.is_some() || matches!(err, SpeechError::NoSpeechDetected) {
+                    let _ = fs::remove_dir_all(&session_dir);
+                } else {
+                    let recording = UntranscribedRecording {
+                        id: session_id.clone(),
+                        language,
+                        title: title_override.clone(),
+                        project: project.clone(),
+                        created_at: now_utc(),
+                        reason: err.to_string(),
+                        calendar_event: calendar_event.clone(),
+                        channel_mode,
+                        vad_enabled,
+                        translate,
+                        initial_prompt,
+                        options,
+                        size_bytes: 0,
+                    };
+                    if let Err(keep_err) = self.keep_untranscribed(&session_dir, &recording) {
+                        log::warn!("failed to keep untranscribed recording: {keep_err}");
+                        let _ = fs::remove_dir_all(&session_dir);
+                    }
+                }
                 return Err(err);
             }
         };
 
         let timestamp = Local::now();
-        let default_title = format!(
-            "{}转写 {}",
-            language.display_name(),
-            timestamp.format("%H:%M:%S")
-        );
-        let title = title_override
-            .filter(|t| !t.trim().is_empty())
-            .unwrap_or(default_title);
+        let title = match title_override.filter(|t| !t.trim().is_empty()) {
+            Some(title) => title,
+            None => self.title_template().await.resolve(&TitleContext {
+                timestamp,
+                language,
+                calendar_event: calendar_event.as_ref(),
+                project: project.as_deref(),
+            }),
+        };
 
         let transcript_path = session_dir.join("transcript.txt");
         fs::write(&transcript_path, transcription.transcript.as_bytes())?;
@@ -598,7 +1090,15 @@ impl SpeechManager {
             transcript: transcription.transcript,
             segments: transcription.segments,
             audio_path: audio_relative_path,
-            created_at: timestamp.to_rfc3339(),
+            created_at: to_utc_string(&timestamp),
+            tags: Vec::new(),
+            calendar_event,
+            audio_warnings: transcription.audio_warnings,
+            gpu_fallback: transcription.gpu_fallback,
+            audio_removed_at: None,
+            translated: translate,
+            initial_prompt,
+            transcription_options: Some(options.resolve(&decoding)),
         };
 
         {
@@ -606,13 +1106,19 @@ impl SpeechManager {
             guard.sessions.insert(0, session.clone());
             self.persist_sessions(&guard.sessions)?;
         }
+        self.indexer.upsert(session_search_document(&session));
 
         Ok(session)
     }
 
+    pub async fn search_documents(&self) -> Vec<SearchDocument> {
+        let guard = self.state.lock().await;
+        guard.sessions.iter().map(session_search_document).collect()
+    }
+
     fn persist_sessions(&self, sessions: &[SpeechSession]) -> Result<(), SpeechError> {
         let json = serde_json::to_vec_pretty(sessions)?;
-        fs::write(&self.sessions_file, json)?;
+        write_atomic(&self.sessions_file, &json)?;
         Ok(())
     }
 
@@ -621,7 +1127,11 @@ impl SpeechManager {
         let mut exported = Vec::with_capacity(guard.sessions.len());
         for session in &guard.sessions {
             let audio_path = self.base_dir.join(&session.audio_path);
-            let audio_bytes = fs::read(&audio_path)?;
+            let audio_bytes = if session.audio_removed_at.is_some() {
+                Vec::new()
+            } else {
+                fs::read(&audio_path)?
+            };
             let filename = Path::new(&session.audio_path)
                 .file_name()
                 .and_then(|name| name.to_str())
@@ -644,6 +1154,12 @@ impl SpeechManager {
                 created_at: session.created_at.clone(),
                 audio_filename: filename,
                 audio_base64,
+                tags: session.tags.clone(),
+                calendar_event: session.calendar_event.clone(),
+                audio_removed_at: session.audio_removed_at.clone(),
+                translated: session.translated,
+                initial_prompt: session.initial_prompt.clone(),
+                transcription_options: session.transcription_options,
             });
         }
         Ok(exported)
@@ -651,56 +1167,178 @@ impl SpeechManager {
 
     pub async fn import_sessions_data(
         &self,
+        app: &AppHandle,
         sessions: Vec<SpeechSessionBackup>,
-    ) -> Result<usize, SpeechError> {
+    ) -> Result<ImportSessionsReport, SpeechError> {
+        ensure_writable()?;
+        let mut report = ImportSessionsReport {
+            total: sessions.len(),
+            ..Default::default()
+        };
         if sessions.is_empty() {
-            return Ok(0);
+            return Ok(report);
         }
 
         let mut guard = self.state.lock().await;
-        let mut imported = 0usize;
 
-        for backup in sessions {
-            let audio_bytes = decode_audio_base64(&backup.audio_base64)?;
-            let sanitized_filename = sanitize_audio_filename(&backup.audio_filename);
-            let session_dir = self.sessions_dir.join(&backup.id);
+        for (index, backup) in sessions.into_iter().enumerate() {
+            let session_id = backup.id.clone();
+            let title = backup.title.clone();
+
+            let (status, message) = if backup.id.trim().is_empty() {
+                (ImportItemStatus::Skipped, Some("缺少会话 ID".to_string()))
+            } else if guard.sessions.iter().any(|s| {
+                s.id == backup.id
+                    && cmp_instants(&s.created_at, &backup.created_at).is_eq()
+                    && s.transcript == backup.transcript
+            }) {
+                (ImportItemStatus::Skipped, Some("会话已存在".to_string()))
+            } else {
+                match self.import_single_session(backup) {
+                    Ok(session) => {
+                        self.indexer.upsert(session_search_document(&session));
+                        if let Some(pos) = guard.sessions.iter().position(|s| s.id == session.id) {
+                            guard.sessions.remove(pos);
+                        }
+                        guard.sessions.push(session);
+                        (ImportItemStatus::Imported, None)
+                    }
+                    Err(err) => (ImportItemStatus::Failed, Some(err.to_string())),
+                }
+            };
 
-            if session_dir.exists() {
-                fs::remove_dir_all(&session_dir)?;
+            match status {
+                ImportItemStatus::Imported => report.imported += 1,
+                ImportItemStatus::Skipped => report.skipped += 1,
+                ImportItemStatus::Failed => report.failed.push(ImportFailure {
+                    session_id: session_id.clone(),
+                    title: title.clone(),
+                    reason: message.clone().unwrap_or_default(),
+                }),
             }
-            fs::create_dir_all(&session_dir)?;
 
-            let audio_path = session_dir.join(&sanitized_filename);
-            fs::write(&audio_path, &audio_bytes)?;
-            fs::write(session_dir.join("transcript.txt"), backup.transcript.as_bytes())?;
+            let _ = app.emit(
+                IMPORT_PROGRESS_EVENT,
+                ImportProgressEvent {
+                    current: index + 1,
+                    total: report.total,
+                    session_id,
+                    title,
+                    status,
+                    message,
+                },
+            );
+        }
+
+        if report.imported > 0 {
+            guard
+                .sessions
+                .sort_by(|a, b| cmp_instants(&b.created_at, &a.created_at));
+            self.persist_sessions(&guard.sessions)?;
+        }
+        Ok(report)
+    }
+
+    fn import_single_session(
+        &self,
+        backup: SpeechSessionBackup,
+    ) -> Result<SpeechSession, SpeechError> {
+        let audio_bytes = decode_audio_base64(&backup.audio_base64)?;
+        let sanitized_filename = sanitize_audio_filename(&backup.audio_filename);
+        let session_dir = self.sessions_dir.join(&backup.id);
+        // 先写到同级临时目录，全部写完再替换，写入失败时原会话的文件保持不变
+        let staging_name = format!(".{}.{}.importing", backup.id, Uuid::new_v4());
+        let staging_dir = self.sessions_dir.join(staging_name);
+        fs::create_dir_all(&staging_dir)?;
+
+        let write_result = (|| -> Result<(), SpeechError> {
+            fs::write(staging_dir.join(&sanitized_filename), &audio_bytes)?;
+            fs::write(
+                staging_dir.join("transcript.txt"),
+                backup.transcript.as_bytes(),
+            )?;
             fs::write(
-                session_dir.join("segments.json"),
+                staging_dir.join("segments.json"),
                 serde_json::to_vec_pretty(&backup.segments)?,
             )?;
+            Ok(())
+        })();
+        if let Err(err) = write_result.and_then(|()| replace_dir(&staging_dir, &session_dir)) {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(err);
+        }
 
-            let audio_rel_path = format!("sessions/{}/{}", backup.id, sanitized_filename);
-            let session = SpeechSession {
-                id: backup.id.clone(),
-                title: backup.title.clone(),
-                language: backup.language,
-                transcript: backup.transcript.clone(),
-                segments: backup.segments.clone(),
-                audio_path: audio_rel_path,
-                created_at: backup.created_at.clone(),
-            };
+        let audio_rel_path = format!("sessions/{}/{}", backup.id, sanitized_filename);
+        Ok(SpeechSession {
+            id: backup.id,
+            title: backup.title,
+            language: backup.language,
+            transcript: backup.transcript,
+            segments: backup.segments,
+            audio_path: audio_rel_path,
+            created_at: backup.created_at,
+            tags: normalize_tags(backup.tags),
+            calendar_event: backup.calendar_event,
+            audio_warnings: Vec::new(),
+            audio_removed_at: backup.audio_removed_at,
+            gpu_fallback: None,
+            translated: backup.translated,
+            initial_prompt: backup.initial_prompt,
+            transcription_options: backup.transcription_options,
+        })
+    }
+}
 
-            if let Some(pos) = guard.sessions.iter().position(|s| s.id == session.id) {
-                guard.sessions.remove(pos);
-            }
-            guard.sessions.push(session);
-            imported += 1;
+/// 用 `from` 替换 `to`：旧目录先改名留作备份，替换成功后再删除，失败时改回原名。
+fn replace_dir(from: &Path, to: &Path) -> Result<(), SpeechError> {
+    if !to.exists() {
+        fs::rename(from, to)?;
+        return Ok(());
+    }
+    let mut previous = to.as_os_str().to_os_string();
+    previous.push(format!(".{}.replaced", Uuid::new_v4()));
+    let previous = PathBuf::from(previous);
+    fs::rename(to, &previous)?;
+    if let Err(err) = fs::rename(from, to) {
+        if let Err(revert) = fs::rename(&previous, to) {
+            log::error!(
+                "failed to restore {} after a failed replace: {revert}",
+                to.display()
+            );
         }
+        return Err(err.into());
+    }
+    if let Err(err) = fs::remove_dir_all(&previous) {
+        log::warn!(
+            "failed to remove replaced directory {}: {err}",
+            previous.display()
+        );
+    }
+    Ok(())
+}
 
-        guard
-            .sessions
-            .sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        self.persist_sessions(&guard.sessions)?;
-        Ok(imported)
+pub(super) fn ensure_writable() -> Result<(), SpeechError> {
+    if is_read_only() {
+        return Err(SpeechError::ReadOnly);
+    }
+    Ok(())
+}
+
+/// 离线模式下立即失败，不等连接超时。
+pub(super) fn ensure_online() -> Result<(), SpeechError> {
+    if is_offline() {
+        return Err(SpeechError::Offline);
+    }
+    Ok(())
+}
+
+fn session_search_document(session: &SpeechSession) -> SearchDocument {
+    SearchDocument {
+        kind: SearchEntityKind::Session,
+        id: session.id.clone(),
+        title: session.title.clone(),
+        body: session.transcript.clone(),
+        updated_at: session.created_at.clone(),
     }
 }
 
@@ -715,32 +1353,302 @@ fn decode_audio_base64(data: &str) -> Result<Vec<u8>, SpeechError> {
         .map_err(|err| SpeechError::Audio(format!("Base64 decode failed: {err}")))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn transcribe_blocking(
+    contexts: &Mutex<WhisperContextCache>,
+    model: &SpeechModel,
     model_path: &Path,
-    audio_bytes: &[u8],
-    language: SpeechLanguage,
+    use_gpu: bool,
+    decoded: &DecodedAudio,
+    task: WhisperTask,
+    channel_mode: ChannelMode,
+    decoding: DecodingSettings,
+    vad_enabled: bool,
     cancel_flag: Arc<AtomicBool>,
+    preemption: Option<Arc<Preemption>>,
+    on_segment: Option<SegmentSink>,
+    on_progress: Option<ProgressSink>,
 ) -> Result<TranscriptionResult, SpeechError> {
-    let (samples, sample_rate) = decode_wav_to_mono_f32(audio_bytes)?;
-    let audio = if sample_rate != 16_000 {
-        resample_audio(&samples, sample_rate, 16_000)
-    } else {
-        samples
+    if stub::enabled() {
+        return stub::transcribe(decoded, &task, &cancel_flag, on_segment, on_progress);
+    }
+    let analysis = diagnostics::analyze(decoded);
+    if analysis.voiced_seconds < MIN_VOICED_SECONDS {
+        return Err(SpeechError::NoSpeechDetected);
+    }
+    let tracks = split_tracks(decoded, channel_mode);
+
+    let tracks: Vec<Track> = tracks
+        .into_iter()
+        .map(|(speaker, samples)| {
+            let audio = if decoded.sample_rate != 16_000 {
+                resample_audio(&samples, decoded.sample_rate, 16_000)
+            } else {
+                samples
+            };
+            let trimmed = vad_enabled.then(|| vad::trim_silence(&audio)).flatten();
+            let (audio, timeline) = match trimmed {
+                Some((trimmed, timeline)) => (trimmed, Some(Arc::new(timeline))),
+                None => (audio, None),
+            };
+            Track {
+                audio,
+                origin: TrackOrigin { speaker, timeline },
+            }
+        })
+        .collect();
+
+    let run = |use_gpu, pass| {
+        let ctx = lock_contexts(contexts).load(model, model_path, use_gpu)?;
+        let on_segment = on_segment.as_ref().map(|sink| {
+            let sink = sink.clone();
+            Arc::new(move |_: u32, segment: TranscriptSegment| sink(pass, segment)) as SegmentSink
+        });
+        let on_progress = on_progress.as_ref().map(|sink| {
+            let sink = sink.clone();
+            Arc::new(
+                move |_: u32, done: usize, total: usize, chunk_percent: u8| {
+                    sink(pass, done, total, chunk_percent)
+                },
+            ) as ProgressSink
+        });
+        transcribe_tracks(
+            &ctx,
+            &tracks,
+            &task,
+            &decoding,
+            &cancel_flag,
+            preemption.as_deref(),
+            on_segment,
+            on_progress,
+        )
+    };
+    let (mut segments, gpu_fallback) = match run(use_gpu, 0) {
+        Ok(segments) => (segments, None),
+        Err(SpeechError::TranscriptionCancelled) => {
+            return Err(SpeechError::TranscriptionCancelled)
+        }
+        Err(err) if !use_gpu => return Err(err),
+        Err(gpu_err) => {
+            log::warn!("GPU transcription failed, retrying on CPU: {gpu_err}");
+            let reason = gpu_err.to_string();
+            let segments = run(false, 1).map_err(|cpu_err| match cpu_err {
+                SpeechError::TranscriptionCancelled => cpu_err,
+                _ => gpu_err,
+            })?;
+            (segments, Some(reason))
+        }
     };
+    if tracks.len() > 1 {
+        segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    }
+    if decoding.suppress_hallucinations {
+        segments = decoding::suppress_hallucinations(segments);
+    }
+
+    Ok(TranscriptionResult {
+        transcript: build_transcript(&segments),
+        segments,
+        audio_warnings: analysis.warnings,
+        gpu_fallback,
+    })
+}
+
+/// 重叠切块中的一块，只保留起点落在 `[keep_from, keep_until)` 内的片段，
+/// 重叠区以中点为界分给前后两块，拼接后既不重复也不遗漏。
+#[derive(Debug, Clone, Copy)]
+struct AudioChunk {
+    start: usize,
+    end: usize,
+    keep_from: f32,
+    keep_until: f32,
+}
+
+impl AudioChunk {
+    /// 把块内时间换算为整段音频中的时间；不归本块负责的片段返回 `None`。
+    fn place(&self, segment: TranscriptSegment) -> Option<TranscriptSegment> {
+        let offset = self.start as f32 / WHISPER_SAMPLE_RATE as f32;
+        let start = segment.start + offset;
+        (start >= self.keep_from && start < self.keep_until).then(|| TranscriptSegment {
+            start,
+            end: segment.end + offset,
+            words: segment
+                .words
+                .into_iter()
+                .map(|word| WordTiming {
+                    start: word.start + offset,
+                    end: word.end + offset,
+                    ..word
+                })
+                .collect(),
+            ..segment
+        })
+    }
+}
 
-    let model_str = model_path.to_str().ok_or(SpeechError::InvalidModelPath)?;
-    let ctx_params = WhisperContextParameters::default();
-    let ctx = WhisperContext::new_with_params(model_str, ctx_params)?;
+fn plan_chunks(len: usize) -> Vec<AudioChunk> {
+    let chunk_len = CHUNK_SECONDS * WHISPER_SAMPLE_RATE;
+    let step = (CHUNK_SECONDS - CHUNK_OVERLAP_SECONDS) * WHISPER_SAMPLE_RATE;
+    let half_overlap = CHUNK_OVERLAP_SECONDS as f32 / 2.0;
+
+    let mut chunks: Vec<AudioChunk> = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + chunk_len).min(len);
+        let keep_from = if start == 0 {
+            0.0
+        } else {
+            start as f32 / WHISPER_SAMPLE_RATE as f32 + half_overlap
+        };
+        if let Some(previous) = chunks.last_mut() {
+            previous.keep_until = keep_from;
+        }
+        chunks.push(AudioChunk {
+            start,
+            end,
+            keep_from,
+            keep_until: f32::INFINITY,
+        });
+        if end >= len {
+            return chunks;
+        }
+        start += step;
+    }
+}
+
+/// 一次转写的语言和提示设置，所有音轨和切块共用。
+struct WhisperTask {
+    language: SpeechLanguage,
+    /// 使用翻译模式输出英文。
+    translate: bool,
+    /// 自定义提示，为空时使用默认提示。
+    initial_prompt: Option<String>,
+    /// 温度、单段 token 上限等覆盖项；阈值已合并进 `DecodingSettings`。
+    options: TranscriptionOptions,
+}
+
+/// 待转写的一条音轨；开启静音检测时 `audio` 已跳过长静音。
+struct Track {
+    audio: Vec<f32>,
+    origin: TrackOrigin,
+}
+
+/// 音轨对应的说话人，以及跳过静音后换算回原始时间所需的对应关系。
+#[derive(Clone)]
+struct TrackOrigin {
+    speaker: Option<String>,
+    timeline: Option<Arc<vad::Timeline>>,
+}
+
+impl TrackOrigin {
+    fn finish(&self, segment: TranscriptSegment) -> TranscriptSegment {
+        let segment = TranscriptSegment {
+            speaker: self.speaker.clone(),
+            ..segment
+        };
+        match &self.timeline {
+            Some(timeline) => timeline.restore(segment),
+            None => segment,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn transcribe_tracks(
+    ctx: &WhisperContext,
+    tracks: &[Track],
+    task: &WhisperTask,
+    decoding: &DecodingSettings,
+    cancel_flag: &Arc<AtomicBool>,
+    preemption: Option<&Preemption>,
+    on_segment: Option<SegmentSink>,
+    on_progress: Option<ProgressSink>,
+) -> Result<Vec<TranscriptSegment>, SpeechError> {
+    let plans: Vec<Vec<AudioChunk>> = tracks
+        .iter()
+        .map(|track| plan_chunks(track.audio.len()))
+        .collect();
+    let total: usize = plans.iter().map(Vec::len).sum();
+    let report = |done: usize| {
+        if let Some(sink) = &on_progress {
+            sink(0, done, total, 0);
+        }
+    };
+
+    let mut segments = Vec::new();
+    let mut done = 0;
+    for (track, chunks) in tracks.iter().zip(&plans) {
+        for chunk in chunks {
+            if let Some(preemption) = preemption {
+                preemption.park_if_requested(cancel_flag)?;
+            }
+            report(done);
+            let on_segment = on_segment.as_ref().map(|sink| {
+                let (sink, origin, chunk) = (sink.clone(), track.origin.clone(), *chunk);
+                Arc::new(move |pass: u32, segment: TranscriptSegment| {
+                    if let Some(segment) = chunk.place(segment) {
+                        sink(pass, origin.finish(segment))
+                    }
+                }) as SegmentSink
+            });
+            let on_chunk_progress = on_progress.as_ref().map(|sink| {
+                let sink = sink.clone();
+                Box::new(move |percent: i32| sink(0, done, total, percent.clamp(0, 100) as u8))
+                    as ChunkProgress
+            });
+            let chunk_segments = run_whisper(
+                ctx,
+                &track.audio[chunk.start..chunk.end],
+                task,
+                decoding,
+                cancel_flag,
+                on_segment,
+                on_chunk_progress,
+            )?;
+            segments.extend(
+                chunk_segments
+                    .into_iter()
+                    .filter_map(|segment| chunk.place(segment))
+                    .map(|segment| track.origin.finish(segment)),
+            );
+            done += 1;
+        }
+    }
+    report(done);
+    Ok(segments)
+}
+
+fn run_whisper(
+    ctx: &WhisperContext,
+    audio: &[f32],
+    task: &WhisperTask,
+    decoding: &DecodingSettings,
+    cancel_flag: &Arc<AtomicBool>,
+    on_segment: Option<SegmentSink>,
+    on_progress: Option<ChunkProgress>,
+) -> Result<Vec<TranscriptSegment>, SpeechError> {
     let mut state = ctx.create_state()?;
 
     let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    params.set_language(Some(language.code()));
-    params.set_translate(false);
+    params.set_language(Some(task.language.code()));
+    params.set_translate(task.translate);
     params.set_n_threads(num_cpus::get() as i32);
     params.set_no_context(true);
-
-    if language == SpeechLanguage::Chinese {
-        params.set_initial_prompt("以下是简体中文普通话的句子。");
+    params.set_no_speech_thold(decoding.no_speech_threshold);
+    params.set_entropy_thold(decoding.entropy_threshold);
+    params.set_logprob_thold(decoding.logprob_threshold);
+    params.set_temperature_inc(decoding.temperature_increment);
+    params.set_temperature(task.options.temperature());
+    params.set_max_tokens(task.options.max_tokens_per_segment() as i32);
+    params.set_suppress_blank(task.options.suppress_blanks());
+    params.set_token_timestamps(true);
+
+    let initial_prompt = task
+        .initial_prompt
+        .as_deref()
+        .or_else(|| prompt::default_prompt(task.language, task.translate));
+    if let Some(initial_prompt) = initial_prompt {
+        params.set_initial_prompt(initial_prompt);
     }
 
     let cancel_for_callback = cancel_flag.clone();
@@ -750,7 +1658,32 @@ fn transcribe_blocking(
     params.set_abort_callback_safe::<Option<Box<dyn FnMut() -> bool>>, Box<dyn FnMut() -> bool>>(
         Some(callback),
     );
-    match state.full(params, &audio) {
+    if let Some(sink) = on_segment {
+        let segment_callback: Box<dyn FnMut(SegmentCallbackData)> =
+            Box::new(move |data: SegmentCallbackData| {
+                sink(
+                    0,
+                    TranscriptSegment {
+                        start: data.start_timestamp as f32 / 100.0,
+                        end: data.end_timestamp as f32 / 100.0,
+                        text: data.text.trim().to_string(),
+                        speaker: None,
+                        words: Vec::new(),
+                        confidence: None,
+                    },
+                )
+            });
+        params.set_segment_callback_safe_lossy::<
+            Option<Box<dyn FnMut(SegmentCallbackData)>>,
+            Box<dyn FnMut(SegmentCallbackData)>,
+        >(Some(segment_callback));
+    }
+    if let Some(progress_callback) = on_progress {
+        params.set_progress_callback_safe::<Option<ChunkProgress>, ChunkProgress>(Some(
+            progress_callback,
+        ));
+    }
+    match state.full(params, audio) {
         Ok(_) => {}
         Err(err) => {
             if cancel_flag.load(Ordering::Relaxed) {
@@ -760,38 +1693,109 @@ fn transcribe_blocking(
         }
     }
 
-    let mut transcript = String::new();
     let mut segments = Vec::new();
     let num_segments = state.full_n_segments();
     for i in 0..num_segments {
         if let Some(segment) = state.get_segment(i) {
             let text_value = segment.to_str_lossy()?.trim().to_string();
-            if !text_value.is_empty() {
-                if !transcript.is_empty() {
-                    transcript.push('\n');
-                }
-                transcript.push_str(&text_value);
-            }
-
             let start = segment.start_timestamp() as f32 / 100.0;
             let end = segment.end_timestamp() as f32 / 100.0;
+            let tokens: Vec<_> = (0..segment.n_tokens())
+                .filter_map(|j| segment.get_token(j))
+                .map(|token| token.token_data())
+                .collect();
             segments.push(TranscriptSegment {
                 start,
                 end,
                 text: text_value,
+                speaker: None,
+                words: words::collect_words(
+                    ctx,
+                    tokens.iter().map(|data| (data.id, data.t0, data.t1)),
+                ),
+                confidence: words::mean_probability(
+                    ctx,
+                    tokens.iter().map(|data| (data.id, data.p)),
+                ),
             });
         }
     }
 
-    Ok(TranscriptionResult {
-        transcript,
-        segments,
-    })
+    Ok(segments)
+}
+
+fn build_transcript(segments: &[TranscriptSegment]) -> String {
+    let mut transcript = String::new();
+    for segment in segments {
+        if segment.text.is_empty() {
+            continue;
+        }
+        if !transcript.is_empty() {
+            transcript.push('\n');
+        }
+        if let Some(speaker) = &segment.speaker {
+            transcript.push_str(speaker);
+            transcript.push_str("：");
+        }
+        transcript.push_str(&segment.text);
+    }
+    transcript
 }
 
-fn decode_wav_to_mono_f32(audio_bytes: &[u8]) -> Result<(Vec<f32>, u32), SpeechError> {
-    let cursor = Cursor::new(audio_bytes);
-    let mut reader = hound::WavReader::new(cursor)?;
+/// 按声道选择拆分出待转写的音轨；双人模式下每个声道单独转写并标注说话人。
+fn split_tracks(audio: &DecodedAudio, mode: ChannelMode) -> Vec<(Option<String>, Vec<f32>)> {
+    let channels = audio.channels.max(1);
+    let extract = |channel: usize| -> Vec<f32> {
+        audio
+            .samples
+            .iter()
+            .skip(channel.min(channels - 1))
+            .step_by(channels)
+            .copied()
+            .collect()
+    };
+
+    match mode {
+        _ if channels == 1 => vec![(None, audio.samples.clone())],
+        ChannelMode::Mix => vec![(None, reduce_channels(&audio.samples, channels))],
+        ChannelMode::Left => vec![(None, extract(0))],
+        ChannelMode::Right => vec![(None, extract(1))],
+        ChannelMode::Speakers => vec![
+            (Some("左声道".to_string()), extract(0)),
+            (Some("右声道".to_string()), extract(1)),
+        ],
+    }
+}
+
+struct DecodedAudio {
+    samples: Vec<f32>,
+    channels: usize,
+    sample_rate: u32,
+}
+
+/// 按文件内容判断格式：WAV 用 hound 读取，MP3、M4A、OGG、FLAC 等用 symphonia 解码。
+fn decode_audio(audio_bytes: &[u8]) -> Result<DecodedAudio, SpeechError> {
+    if compressed::is_wav(audio_bytes) {
+        decode_wav(audio_bytes)
+    } else {
+        compressed::decode_compressed(audio_bytes)
+    }
+}
+
+/// 直接从磁盘流式解码，不把整个文件读入内存。
+fn decode_audio_file(path: &Path) -> Result<DecodedAudio, SpeechError> {
+    if compressed::is_wav_file(path)? {
+        read_wav(hound::WavReader::open(path)?)
+    } else {
+        compressed::decode_compressed_file(path)
+    }
+}
+
+fn decode_wav(audio_bytes: &[u8]) -> Result<DecodedAudio, SpeechError> {
+    read_wav(hound::WavReader::new(Cursor::new(audio_bytes))?)
+}
+
+fn read_wav<R: io::Read>(mut reader: hound::WavReader<R>) -> Result<DecodedAudio, SpeechError> {
     let spec = reader.spec();
     let channels = spec.channels as usize;
     if channels == 0 {
@@ -800,40 +1804,28 @@ fn decode_wav_to_mono_f32(audio_bytes: &[u8]) -> Result<(Vec<f32>, u32), SpeechE
 
     let sample_rate = spec.sample_rate;
 
-    let mono = match spec.sample_format {
-        hound::SampleFormat::Float => {
-            let samples: Vec<f32> = reader
-                .samples::<f32>()
-                .map(|s| s.map_err(|e| SpeechError::Audio(e.to_string())))
-                .collect::<Result<_, _>>()?;
-            if channels == 1 {
-                samples
-            } else {
-                samples
-                    .chunks(channels)
-                    .map(|chunk| chunk.iter().copied().sum::<f32>() / channels as f32)
-                    .collect()
-            }
-        }
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map_err(|e| SpeechError::Audio(e.to_string())))
+            .collect::<Result<Vec<f32>, _>>()?,
         hound::SampleFormat::Int => match spec.bits_per_sample {
             8 => {
                 let samples: Vec<i8> = reader
                     .samples::<i8>()
                     .map(|s| s.map_err(|e| SpeechError::Audio(e.to_string())))
                     .collect::<Result<_, _>>()?;
-                let floats: Vec<f32> = samples.iter().map(|v| *v as f32 / i8::MAX as f32).collect();
-                reduce_channels(&floats, channels)
+                samples.iter().map(|v| *v as f32 / i8::MAX as f32).collect()
             }
             16 => {
                 let samples: Vec<i16> = reader
                     .samples::<i16>()
                     .map(|s| s.map_err(|e| SpeechError::Audio(e.to_string())))
                     .collect::<Result<_, _>>()?;
-                let floats: Vec<f32> = samples
+                samples
                     .iter()
                     .map(|v| *v as f32 / i16::MAX as f32)
-                    .collect();
-                reduce_channels(&floats, channels)
+                    .collect()
             }
             24 | 32 => {
                 let samples: Vec<i32> = reader
@@ -841,14 +1833,17 @@ fn decode_wav_to_mono_f32(audio_bytes: &[u8]) -> Result<(Vec<f32>, u32), SpeechE
                     .map(|s| s.map_err(|e| SpeechError::Audio(e.to_string())))
                     .collect::<Result<_, _>>()?;
                 let scale = 2_i32.pow(spec.bits_per_sample as u32 - 1) as f32;
-                let floats: Vec<f32> = samples.iter().map(|v| *v as f32 / scale).collect();
-                reduce_channels(&floats, channels)
+                samples.iter().map(|v| *v as f32 / scale).collect()
             }
             bits => return Err(SpeechError::UnsupportedBitDepth(bits)),
         },
     };
 
-    Ok((mono, sample_rate))
+    Ok(DecodedAudio {
+        samples,
+        channels,
+        sample_rate,
+    })
 }
 
 fn reduce_channels(samples: &[f32], channels: usize) -> Vec<f32> {
@@ -884,6 +1879,17 @@ fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     output
 }
 
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let trimmed = tag.trim();
+        if !trimmed.is_empty() && !normalized.iter().any(|t| t == trimmed) {
+            normalized.push(trimmed.to_string());
+        }
+    }
+    normalized
+}
+
 fn sanitize_audio_filename(input: &str) -> String {
     let fallback = "recording.wav";
     let trimmed = input.trim();
@@ -905,14 +1911,6 @@ fn sanitize_audio_filename(input: &str) -> String {
     }
 }
 
-#[tauri::command]
-pub async fn ensure_speech_model(
-    state: tauri::State<'_, SpeechManager>,
-    app: AppHandle,
-) -> Result<ModelStatusResponse, String> {
-    state.ensure_model(&app).await.map_err(|e| e.to_string())
-}
-
 #[tauri::command]
 pub async fn list_speech_sessions(
     state: tauri::State<'_, SpeechManager>,
@@ -923,10 +1921,12 @@ pub async fn list_speech_sessions(
 #[tauri::command]
 pub async fn delete_speech_session(
     state: tauri::State<'_, SpeechManager>,
+    links: tauri::State<'_, LinkStore>,
+    journal: tauri::State<'_, OperationJournal>,
     session_id: String,
-) -> Result<(), String> {
-    state
-        .delete_session(&session_id)
+) -> Result<Vec<EntityLink>, String> {
+    journal
+        .delete_session(&state, &links, &session_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -942,16 +1942,45 @@ pub async fn update_speech_session(
         .map_err(|e| e.to_string())
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuFallbackEvent {
+    pub session_id: String,
+    pub reason: String,
+}
+
+/// 通知前端本次转写因 GPU 失败改用 CPU 完成，耗时会比平时长。
+pub(super) fn emit_gpu_fallback(app: &AppHandle, session: &SpeechSession) {
+    if let Some(reason) = &session.gpu_fallback {
+        let _ = app.emit(
+            GPU_FALLBACK_EVENT,
+            GpuFallbackEvent {
+                session_id: session.id.clone(),
+                reason: reason.clone(),
+            },
+        );
+    }
+}
+
 #[tauri::command]
 pub async fn transcribe_audio(
+    app: AppHandle,
     state: tauri::State<'_, SpeechManager>,
+    calendar: tauri::State<'_, CalendarManager>,
     payload: TranscribeAudioPayload,
 ) -> Result<TranscribeAudioResponse, String> {
-    state
-        .transcribe_audio(payload)
-        .await
-        .map(|session| TranscribeAudioResponse { session })
-        .map_err(|e| e.to_string())
+    let recorded_at = payload
+        .recorded_at
+        .as_deref()
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+        .map(|value| value.with_timezone(&chrono::Utc))
+        .unwrap_or_else(chrono::Utc::now);
+    let calendar_event = calendar.event_at(recorded_at).await;
+    let result = state.transcribe_audio(&app, payload, calendar_event).await;
+    badge::refresh(&app).await;
+    let session = result.map_err(|e| e.to_string())?;
+    emit_gpu_fallback(&app, &session);
+    notify_transcription_finished(&app, &session);
+    Ok(TranscribeAudioResponse { session })
 }
 
 #[tauri::command]
@@ -1010,10 +2039,11 @@ pub async fn export_speech_sessions(
 #[tauri::command]
 pub async fn import_speech_sessions(
     state: tauri::State<'_, SpeechManager>,
+    app: AppHandle,
     sessions: Vec<SpeechSessionBackup>,
-) -> Result<usize, String> {
+) -> Result<ImportSessionsReport, String> {
     state
-        .import_sessions_data(sessions)
+        .import_sessions_data(&app, sessions)
         .await
         .map_err(|e| e.to_string())
 }
@@ -0,0 +1,173 @@
+use std::fs;
+
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, ETAG};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{
+    context_cache::lock_contexts, ensure_online, ensure_writable, hf_token::load_token,
+    models::partial_download_path, SpeechError, SpeechManager, SpeechModel,
+};
+use crate::datetime::now_utc;
+use crate::storage::write_atomic;
+
+/// 下载完成时记录的远端文件标识，用于之后判断下载源上的文件是否有更新。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteModelInfo {
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub content_length: Option<u64>,
+    pub downloaded_at: String,
+}
+
+impl RemoteModelInfo {
+    pub(super) fn from_headers(headers: &HeaderMap, content_length: Option<u64>) -> Self {
+        Self {
+            etag: header_etag(headers),
+            content_length,
+            downloaded_at: now_utc(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelUpdateCheck {
+    pub model: SpeechModel,
+    pub update_available: bool,
+    /// 注册表固定了校验和的模型只随应用更新，不会从下载源重新下载。
+    pub pinned: bool,
+    pub local: Option<RemoteModelInfo>,
+    pub remote_etag: Option<String>,
+    pub remote_content_length: Option<u64>,
+    /// 已在后台开始重新下载，进度和结果通过模型下载事件通知。
+    pub redownloading: bool,
+}
+
+/// 弱校验前缀 `W/` 和引号不影响比较。
+fn header_etag(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(ETAG)?.to_str().ok()?;
+    Some(value.trim_start_matches("W/").trim_matches('"').to_string())
+}
+
+fn header_content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
+
+/// 两边都有 ETag 时以 ETag 为准，否则比较文件大小。
+fn has_changed(
+    local: Option<&RemoteModelInfo>,
+    local_size: u64,
+    etag: Option<&str>,
+    length: Option<u64>,
+) -> bool {
+    if let (Some(local_etag), Some(etag)) = (local.and_then(|info| info.etag.as_deref()), etag) {
+        return local_etag != etag;
+    }
+    let local_length = local
+        .and_then(|info| info.content_length)
+        .unwrap_or(local_size);
+    length.is_some_and(|length| length != local_length)
+}
+
+impl SpeechManager {
+    /// 下载并校验通过后保存远端标识；模型文件已替换，内存中的旧上下文随之失效。
+    pub(super) async fn record_model_download(&self, model: &SpeechModel, info: RemoteModelInfo) {
+        let mut guard = self.state.lock().await;
+        guard
+            .model_config
+            .downloads
+            .insert(model.id().to_string(), info);
+        if let Err(err) = serde_json::to_vec_pretty(&guard.model_config)
+            .map_err(SpeechError::from)
+            .and_then(|content| Ok(write_atomic(&self.model_config_file, &content)?))
+        {
+            log::warn!("failed to record download of model {}: {err}", model.id());
+        }
+        drop(guard);
+        lock_contexts(&self.whisper_contexts).invalidate(model);
+    }
+
+    /// 用 HEAD 请求对比下载源上的文件和本地记录；`redownload` 为 true 且有更新时在后台重新下载。
+    pub async fn check_model_update(
+        &self,
+        app: &AppHandle,
+        model_id: &str,
+        redownload: bool,
+    ) -> Result<ModelUpdateCheck, SpeechError> {
+        let model = self.resolve_model(model_id).await?;
+        let model_path = self.model_path(&model);
+        if !model_path.exists() {
+            return Err(SpeechError::ModelNotDownloaded(model.id().to_string()));
+        }
+        ensure_online()?;
+        let (http, source, local) = {
+            let guard = self.state.lock().await;
+            (
+                guard.http.clone(),
+                guard.model_config.source.clone(),
+                guard.model_config.downloads.get(model.id()).cloned(),
+            )
+        };
+        let url = model
+            .download_url(&source)
+            .ok_or_else(|| SpeechError::CustomModelMissing(model.id().to_string()))?;
+
+        let mut request = http.head(&url);
+        if let Some(token) = load_token().await {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(SpeechError::Audio(format!(
+                "检查模型更新失败，状态码 {}",
+                response.status()
+            )));
+        }
+        let remote_etag = header_etag(response.headers());
+        let remote_content_length = header_content_length(response.headers());
+        let pinned = model.sha256().is_some();
+        let local_size = fs::metadata(&model_path)?.len();
+        let update_available = !pinned
+            && has_changed(
+                local.as_ref(),
+                local_size,
+                remote_etag.as_deref(),
+                remote_content_length,
+            );
+
+        let downloading = self.state.lock().await.model_download.is_some();
+        let redownloading = if redownload && update_available && !downloading {
+            ensure_writable()?;
+            // 旧版本留下的 .part 不能用于续传
+            let _ = fs::remove_file(partial_download_path(&model_path));
+            self.spawn_model_download(app, &model).await
+        } else {
+            false
+        };
+
+        Ok(ModelUpdateCheck {
+            model,
+            update_available,
+            pinned,
+            local,
+            remote_etag,
+            remote_content_length,
+            redownloading,
+        })
+    }
+}
+
+/// 检查模型在下载源上是否有新版本；下载完成前旧文件保持可用。
+#[tauri::command]
+pub async fn check_model_update(
+    state: tauri::State<'_, SpeechManager>,
+    app: AppHandle,
+    model_id: String,
+    redownload: Option<bool>,
+) -> Result<ModelUpdateCheck, String> {
+    state
+        .check_model_update(&app, &model_id, redownload.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
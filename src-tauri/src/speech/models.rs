@@ -0,0 +1,1384 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures_util::StreamExt;
+use reqwest::{header::RANGE, Client, Proxy, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sysinfo::System;
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+
+use super::{
+    acceleration::{Acceleration, TranscriptionBackend},
+    context_cache::lock_contexts,
+    custom_models::{read_ggml_header, CustomModel},
+    ensure_online, ensure_writable,
+    hf_token::load_token,
+    model_update::RemoteModelInfo,
+    stub, SpeechError, SpeechLanguage, SpeechManager,
+};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+
+const DEFAULT_MODEL_HOST: &str = "https://huggingface.co";
+const MODEL_REPO_PATH: &str = "ggerganov/whisper.cpp/resolve/main";
+const MODELS_DIR: &str = "models";
+const MODEL_CONFIG_FILENAME: &str = "config.json";
+const DEFAULT_MODEL_ID: &str = "small";
+const BUNDLED_MODEL_RELATIVE_PATH: &str = "models/ggml-small.bin";
+/// 注册表中的大小按 MiB 取整，比较时留出余量。
+const BUNDLED_SIZE_TOLERANCE: f64 = 0.02;
+const MODEL_PROGRESS_EVENT: &str = "speech://model-progress";
+const MODEL_STATUS_EVENT: &str = "speech://model-status";
+/// 下载进度事件的最小间隔；逐块发送会让前端忙于处理事件。
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+/// 下载速度的指数平滑系数，越小越平稳。
+const SPEED_SMOOTHING: f64 = 0.3;
+const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+const MAX_DOWNLOAD_RETRIES: u32 = 10;
+/// 重试间隔从 1 秒起逐次翻倍，最长 30 秒。
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+const MIB: u64 = 1024 * 1024;
+
+/// 可下载模型的元数据；`ram_bytes` 为转写时的大致内存占用，供前端提示低内存设备选择量化版本。
+#[derive(Debug, Serialize)]
+pub struct ModelSpec {
+    pub id: &'static str,
+    pub size_bytes: u64,
+    pub ram_bytes: u64,
+    pub quantization: Option<&'static str>,
+    pub languages: ModelLanguages,
+    /// 官方仓库中对应文件的 SHA-256，未收录的变体跳过校验。
+    pub sha256: Option<&'static str>,
+    /// 不在 whisper.cpp 仓库中的模型（如 distil-whisper）相对下载源根地址的路径。
+    #[serde(skip)]
+    pub remote_path: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelLanguages {
+    Multilingual,
+    English,
+}
+
+const MODEL_REGISTRY: &[ModelSpec] = &[
+    ModelSpec {
+        id: "tiny",
+        size_bytes: 75 * MIB,
+        ram_bytes: 273 * MIB,
+        quantization: None,
+        languages: ModelLanguages::Multilingual,
+        sha256: Some("be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21"),
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "tiny-q5_1",
+        size_bytes: 31 * MIB,
+        ram_bytes: 150 * MIB,
+        quantization: Some("q5_1"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "tiny-q8_0",
+        size_bytes: 42 * MIB,
+        ram_bytes: 170 * MIB,
+        quantization: Some("q8_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "tiny.en",
+        size_bytes: 75 * MIB,
+        ram_bytes: 273 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "base",
+        size_bytes: 142 * MIB,
+        ram_bytes: 388 * MIB,
+        quantization: None,
+        languages: ModelLanguages::Multilingual,
+        sha256: Some("60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe"),
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "base-q5_1",
+        size_bytes: 57 * MIB,
+        ram_bytes: 220 * MIB,
+        quantization: Some("q5_1"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "base-q8_0",
+        size_bytes: 78 * MIB,
+        ram_bytes: 250 * MIB,
+        quantization: Some("q8_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "base.en",
+        size_bytes: 142 * MIB,
+        ram_bytes: 388 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "small",
+        size_bytes: 466 * MIB,
+        ram_bytes: 852 * MIB,
+        quantization: None,
+        languages: ModelLanguages::Multilingual,
+        sha256: Some("1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b"),
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "small-q5_1",
+        size_bytes: 181 * MIB,
+        ram_bytes: 420 * MIB,
+        quantization: Some("q5_1"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "small-q8_0",
+        size_bytes: 252 * MIB,
+        ram_bytes: 500 * MIB,
+        quantization: Some("q8_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "small.en",
+        size_bytes: 466 * MIB,
+        ram_bytes: 852 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "medium",
+        size_bytes: 1533 * MIB,
+        ram_bytes: 2100 * MIB,
+        quantization: None,
+        languages: ModelLanguages::Multilingual,
+        sha256: Some("6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208"),
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "medium-q5_0",
+        size_bytes: 514 * MIB,
+        ram_bytes: 900 * MIB,
+        quantization: Some("q5_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "medium-q8_0",
+        size_bytes: 785 * MIB,
+        ram_bytes: 1200 * MIB,
+        quantization: Some("q8_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "medium.en",
+        size_bytes: 1533 * MIB,
+        ram_bytes: 2100 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "large-v3",
+        size_bytes: 2952 * MIB,
+        ram_bytes: 3900 * MIB,
+        quantization: None,
+        languages: ModelLanguages::Multilingual,
+        sha256: Some("64d182b440b98d5203c4f9bd541544d84c605196c4f7b845dfa11fb23594d1e2"),
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "large-v3-q5_0",
+        size_bytes: 1031 * MIB,
+        ram_bytes: 1600 * MIB,
+        quantization: Some("q5_0"),
+        languages: ModelLanguages::Multilingual,
+        sha256: None,
+        remote_path: None,
+    },
+    ModelSpec {
+        id: "distil-small.en",
+        size_bytes: 321 * MIB,
+        ram_bytes: 600 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: Some("distil-whisper/distil-small.en/resolve/main/ggml-distil-small.en.bin"),
+    },
+    ModelSpec {
+        id: "distil-medium.en",
+        size_bytes: 753 * MIB,
+        ram_bytes: 1200 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: Some("distil-whisper/distil-medium.en/resolve/main/ggml-medium-32-2.en.bin"),
+    },
+    ModelSpec {
+        id: "distil-large-v3",
+        size_bytes: 1449 * MIB,
+        ram_bytes: 2000 * MIB,
+        quantization: None,
+        languages: ModelLanguages::English,
+        sha256: None,
+        remote_path: Some(
+            "distil-whisper/distil-large-v3-ggml/resolve/main/ggml-distil-large-v3.bin",
+        ),
+    },
+];
+
+/// 模型 id：注册表中的内置模型（如 `small`、`small-q5_1`）或导入的本地模型（`custom-` 前缀）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SpeechModel(String);
+
+impl SpeechModel {
+    pub(super) fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    fn spec(&self) -> Option<&'static ModelSpec> {
+        MODEL_REGISTRY.iter().find(|spec| spec.id == self.0)
+    }
+
+    pub fn filename(&self) -> String {
+        format!("ggml-{}.bin", self.id())
+    }
+
+    /// 导入的本地模型没有下载地址。
+    pub(super) fn download_url(&self, source: &ModelSource) -> Option<String> {
+        let spec = self.spec()?;
+        let host = source.mirror_url.as_deref().unwrap_or(DEFAULT_MODEL_HOST);
+        Some(match spec.remote_path {
+            Some(path) => format!("{host}/{path}?download=1"),
+            None => format!("{host}/{MODEL_REPO_PATH}/{}?download=1", self.filename()),
+        })
+    }
+
+    /// 仅支持英语的模型（`.en` 及 distil-whisper 等）不能用于其他语言；导入的模型由调用方按文件头判断。
+    pub(super) fn supports_language(&self, language: SpeechLanguage) -> bool {
+        self.spec().map_or(true, |spec| {
+            spec.languages == ModelLanguages::Multilingual || language == SpeechLanguage::ENGLISH
+        })
+    }
+
+    pub(super) fn sha256(&self) -> Option<&'static str> {
+        self.spec().and_then(|spec| spec.sha256)
+    }
+
+    /// whisper.cpp 在模型旁查找的 Core ML 编码器目录名，量化版本与原始模型共用同一个编码器。
+    pub(super) fn coreml_encoder_name(&self) -> Option<String> {
+        let spec = self.spec().filter(|spec| spec.remote_path.is_none())?;
+        let base = match spec.quantization {
+            Some(quantization) => spec
+                .id
+                .strip_suffix(quantization)
+                .and_then(|id| id.strip_suffix('-'))
+                .unwrap_or(spec.id),
+            None => spec.id,
+        };
+        Some(format!("ggml-{base}-encoder.mlmodelc"))
+    }
+
+    pub(super) fn coreml_encoder_url(&self, source: &ModelSource) -> Option<String> {
+        let name = self.coreml_encoder_name()?;
+        let host = source.mirror_url.as_deref().unwrap_or(DEFAULT_MODEL_HOST);
+        Some(format!("{host}/{MODEL_REPO_PATH}/{name}.zip?download=1"))
+    }
+}
+
+impl Default for SpeechModel {
+    fn default() -> Self {
+        Self::new(DEFAULT_MODEL_ID)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ModelConfig {
+    #[serde(default)]
+    pub active: SpeechModel,
+    #[serde(default)]
+    pub source: ModelSource,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<CustomModel>,
+    #[serde(default)]
+    pub backend: TranscriptionBackend,
+    /// 按模型 id 记录的下载来源信息，用于检查更新。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub downloads: BTreeMap<String, RemoteModelInfo>,
+    /// 旧版本的 GPU 开关，读取时换算为 `backend`。
+    #[serde(default, skip_serializing)]
+    hardware_acceleration: Option<bool>,
+}
+
+impl Default for ModelConfig {
+    fn default() -> Self {
+        Self {
+            active: SpeechModel::default(),
+            source: ModelSource::default(),
+            custom: Vec::new(),
+            backend: TranscriptionBackend::default(),
+            downloads: BTreeMap::new(),
+            hardware_acceleration: None,
+        }
+    }
+}
+
+/// 模型下载源：`mirror_url` 替换 huggingface.co（如 https://hf-mirror.com），`proxy` 作用于所有下载请求。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelSource {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 下载中断后连续重试的次数，为空时使用默认值。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+}
+
+impl ModelSource {
+    fn normalized(
+        mirror_url: Option<String>,
+        proxy: Option<String>,
+        max_retries: Option<u32>,
+    ) -> Result<Self, SpeechError> {
+        let clean = |value: Option<String>| {
+            value
+                .map(|value| value.trim().trim_end_matches('/').to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let mirror_url = clean(mirror_url);
+        if let Some(url) = &mirror_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(SpeechError::InvalidModelSource(format!(
+                    "镜像地址必须以 http:// 或 https:// 开头：{url}"
+                )));
+            }
+        }
+        if max_retries.is_some_and(|retries| retries > MAX_DOWNLOAD_RETRIES) {
+            return Err(SpeechError::InvalidModelSource(format!(
+                "重试次数不能超过 {MAX_DOWNLOAD_RETRIES}"
+            )));
+        }
+        Ok(Self {
+            mirror_url,
+            proxy: clean(proxy),
+            max_retries,
+        })
+    }
+
+    pub(super) fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(DEFAULT_DOWNLOAD_RETRIES)
+    }
+}
+
+/// 与给定模型共用同一个 Core ML 编码器的注册表模型（包括它自己）。
+pub(super) fn models_sharing_encoder(name: &str) -> impl Iterator<Item = SpeechModel> + '_ {
+    MODEL_REGISTRY
+        .iter()
+        .map(|spec| SpeechModel::new(spec.id))
+        .filter(move |model| model.coreml_encoder_name().as_deref() == Some(name))
+}
+
+/// 内存占用至少留出一半余量，避免转写时挤占系统和其他应用。
+const RECOMMEND_MEMORY_HEADROOM: f64 = 1.5;
+/// 纯 CPU 推理时 medium 及以上的模型在核心数少的机器上会慢到不可用。
+const LARGE_MODEL_RAM_BYTES: u64 = 1500 * MIB;
+const LARGE_MODEL_MIN_CORES: usize = 8;
+
+/// 选出可用内存和 CPU 核心数能流畅运行的最大模型；用 GPU 转写时不受核心数限制。
+fn recommend_model(available_memory: u64, cpu_cores: usize, use_gpu: bool) -> SpeechModel {
+    MODEL_REGISTRY
+        .iter()
+        .filter(|spec| spec.languages == ModelLanguages::Multilingual)
+        .filter(|spec| spec.ram_bytes as f64 * RECOMMEND_MEMORY_HEADROOM <= available_memory as f64)
+        .filter(|spec| {
+            use_gpu || spec.ram_bytes < LARGE_MODEL_RAM_BYTES || cpu_cores >= LARGE_MODEL_MIN_CORES
+        })
+        .max_by_key(|spec| spec.ram_bytes)
+        .or_else(|| MODEL_REGISTRY.iter().min_by_key(|spec| spec.ram_bytes))
+        .map(|spec| SpeechModel::new(spec.id))
+        .unwrap_or_default()
+}
+
+/// 按下载源设置构建共享的 HTTP 客户端。
+pub(super) fn build_http_client(source: &ModelSource) -> Result<Client, SpeechError> {
+    let mut builder = Client::builder();
+    if let Some(proxy) = &source.proxy {
+        let proxy = Proxy::all(proxy.as_str())
+            .map_err(|err| SpeechError::InvalidModelSource(format!("代理地址无效：{err}")))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder.build()?)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelStatusResponse {
+    pub ready: bool,
+    pub downloaded: bool,
+    pub model: SpeechModel,
+    pub model_path: Option<String>,
+    pub acceleration: Acceleration,
+}
+
+impl ModelStatusResponse {
+    fn ready(
+        model: &SpeechModel,
+        path: &Path,
+        downloaded: bool,
+        acceleration: Acceleration,
+    ) -> Self {
+        Self {
+            ready: true,
+            downloaded,
+            model: model.clone(),
+            model_path: Some(path.to_string_lossy().into_owned()),
+            acceleration,
+        }
+    }
+}
+
+/// 模型管理页面的一行：注册表信息（导入的模型没有）加上本地安装状态。
+#[derive(Debug, Serialize)]
+pub struct InstalledModel {
+    pub id: SpeechModel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<&'static ModelSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<CustomModel>,
+    pub downloaded: bool,
+    pub active: bool,
+    pub on_disk_bytes: Option<u64>,
+    pub model_path: String,
+}
+
+/// 注册表中的模型及按当前下载源解析出的地址。
+#[derive(Debug, Serialize)]
+pub struct ModelRegistryEntry {
+    #[serde(flatten)]
+    pub spec: &'static ModelSpec,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelRecommendation {
+    pub model: SpeechModel,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    pub cpu_cores: usize,
+    /// 所选后端在本构建中可用 GPU 加速。
+    pub gpu: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelDownloadProgress {
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub resumed_from_bytes: u64,
+    pub bytes_per_second: u64,
+    /// 总大小未知或尚无速度时为空。
+    pub eta_seconds: Option<u64>,
+}
+
+/// 按间隔节流进度事件，并计算平滑后的下载速度和剩余时间。
+struct ProgressTracker {
+    resumed_from: u64,
+    last: (Instant, u64),
+    bytes_per_second: Option<f64>,
+}
+
+impl ProgressTracker {
+    fn new(resumed_from: u64) -> Self {
+        Self {
+            resumed_from,
+            last: (Instant::now(), resumed_from),
+            bytes_per_second: None,
+        }
+    }
+
+    /// 距上次发送不足间隔时返回 None；下载到总大小时总会返回。
+    fn update(&mut self, downloaded: u64, total: Option<u64>) -> Option<ModelDownloadProgress> {
+        let now = Instant::now();
+        let (since, bytes_then) = self.last;
+        let elapsed = now.duration_since(since);
+        if elapsed < PROGRESS_EMIT_INTERVAL && total != Some(downloaded) {
+            return None;
+        }
+        let current = (downloaded - bytes_then) as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        let speed = match self.bytes_per_second {
+            Some(previous) => previous + SPEED_SMOOTHING * (current - previous),
+            None => current,
+        };
+        self.bytes_per_second = Some(speed);
+        self.last = (now, downloaded);
+        Some(ModelDownloadProgress {
+            downloaded_bytes: downloaded,
+            total_bytes: total,
+            resumed_from_bytes: self.resumed_from,
+            bytes_per_second: speed.round() as u64,
+            eta_seconds: total
+                .filter(|_| speed >= 1.0)
+                .map(|total| (total.saturating_sub(downloaded) as f64 / speed).ceil() as u64),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelStatusKind {
+    Exists,
+    Downloading,
+    Finished,
+    Failed,
+    Cancelled,
+    Paused,
+    /// 下载中断，等待后自动续传。
+    Retrying,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStatusEvent {
+    pub status: ModelStatusKind,
+    pub model: SpeechModel,
+    pub model_path: Option<String>,
+    pub message: Option<String>,
+}
+
+/// 创建 models 目录并读取当前选用的模型。
+pub(super) fn load_model_config(
+    base_dir: &Path,
+) -> Result<(PathBuf, PathBuf, ModelConfig), SpeechError> {
+    let models_dir = base_dir.join(MODELS_DIR);
+    prepare_dir(&models_dir)?;
+
+    let config_file = models_dir.join(MODEL_CONFIG_FILENAME);
+    let mut config: ModelConfig = load_json(&config_file, ModelConfig::default);
+    if config.hardware_acceleration.take() == Some(false) {
+        config.backend = TranscriptionBackend::Cpu;
+    }
+    Ok((models_dir, config_file, config))
+}
+
+impl SpeechManager {
+    pub(super) fn model_path(&self, model: &SpeechModel) -> PathBuf {
+        self.models_dir.join(model.filename())
+    }
+
+    pub(super) async fn active_model(&self) -> SpeechModel {
+        let guard = self.state.lock().await;
+        guard.model_config.active.clone()
+    }
+
+    /// 校验 id 属于注册表或已导入的本地模型。
+    pub(super) async fn resolve_model(&self, model_id: &str) -> Result<SpeechModel, SpeechError> {
+        let model = SpeechModel::new(model_id);
+        if model.spec().is_some() {
+            return Ok(model);
+        }
+        let guard = self.state.lock().await;
+        if guard
+            .model_config
+            .custom
+            .iter()
+            .any(|custom| custom.id == model_id)
+        {
+            return Ok(model);
+        }
+        Err(SpeechError::UnknownModel(model_id.to_string()))
+    }
+
+    /// 注册表模型按元数据判断，导入的模型按导入时读取的文件头判断。
+    pub(super) async fn ensure_language_supported(
+        &self,
+        model: &SpeechModel,
+        language: SpeechLanguage,
+    ) -> Result<(), SpeechError> {
+        let supported = if model.spec().is_some() {
+            model.supports_language(language)
+        } else {
+            let guard = self.state.lock().await;
+            language == SpeechLanguage::ENGLISH
+                || !guard
+                    .model_config
+                    .custom
+                    .iter()
+                    .any(|custom| custom.id == model.id() && custom.english_only)
+        };
+        if supported {
+            return Ok(());
+        }
+        Err(SpeechError::LanguageNotSupported {
+            model: model.id().to_string(),
+            language: language.display_name(),
+        })
+    }
+
+    pub async fn set_model(&self, model_id: &str) -> Result<ModelStatusResponse, SpeechError> {
+        ensure_writable()?;
+        let model = self.resolve_model(model_id).await?;
+        {
+            let mut guard = self.state.lock().await;
+            let previous = std::mem::replace(&mut guard.model_config.active, model.clone());
+            write_atomic(
+                &self.model_config_file,
+                &serde_json::to_vec_pretty(&guard.model_config)?,
+            )?;
+            if previous != model {
+                lock_contexts(&self.whisper_contexts).invalidate(&previous);
+            }
+        }
+
+        let path = self.model_path(&model);
+        Ok(ModelStatusResponse {
+            ready: path.exists(),
+            downloaded: false,
+            acceleration: self.acceleration(&model).await,
+            model,
+            model_path: Some(path.to_string_lossy().into_owned()),
+        })
+    }
+
+    pub async fn model_source(&self) -> ModelSource {
+        let guard = self.state.lock().await;
+        guard.model_config.source.clone()
+    }
+
+    pub async fn set_model_source(
+        &self,
+        mirror_url: Option<String>,
+        proxy: Option<String>,
+        max_retries: Option<u32>,
+    ) -> Result<ModelSource, SpeechError> {
+        ensure_writable()?;
+        let source = ModelSource::normalized(mirror_url, proxy, max_retries)?;
+        let http = build_http_client(&source)?;
+
+        let mut guard = self.state.lock().await;
+        guard.model_config.source = source.clone();
+        guard.http = http;
+        write_atomic(
+            &self.model_config_file,
+            &serde_json::to_vec_pretty(&guard.model_config)?,
+        )?;
+        Ok(source)
+    }
+
+    pub async fn list_models(&self) -> Vec<InstalledModel> {
+        let (active, custom) = {
+            let guard = self.state.lock().await;
+            (
+                guard.model_config.active.clone(),
+                guard.model_config.custom.clone(),
+            )
+        };
+        let builtin = MODEL_REGISTRY
+            .iter()
+            .map(|spec| (SpeechModel::new(spec.id), Some(spec), None));
+        let imported = custom
+            .into_iter()
+            .map(|custom| (SpeechModel::new(custom.id.clone()), None, Some(custom)));
+
+        builtin
+            .chain(imported)
+            .map(|(model, spec, custom)| {
+                let path = self.model_path(&model);
+                let on_disk_bytes = fs::metadata(&path).ok().map(|meta| meta.len());
+                InstalledModel {
+                    active: model == active,
+                    id: model,
+                    spec,
+                    custom,
+                    downloaded: on_disk_bytes.is_some(),
+                    on_disk_bytes,
+                    model_path: path.to_string_lossy().into_owned(),
+                }
+            })
+            .collect()
+    }
+
+    pub async fn cancel_model_download(&self) -> bool {
+        let guard = self.state.lock().await;
+        if let Some(control) = guard.model_download.as_ref() {
+            control.cancel.store(true, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 暂停后台下载并保留 `.part`，之后可用 `resume_model_download` 断点续传。
+    pub async fn pause_model_download(&self) -> bool {
+        let guard = self.state.lock().await;
+        if let Some(control) = guard.model_download.as_ref() {
+            control.pause.store(true, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 删除已下载的模型文件（含未完成的 `.part`），返回释放的字节数。
+    pub async fn delete_model(&self, model_id: &str) -> Result<u64, SpeechError> {
+        ensure_writable()?;
+        let model = self.resolve_model(model_id).await?;
+
+        let mut guard = self.state.lock().await;
+        if guard
+            .active_transcription
+            .as_ref()
+            .is_some_and(|active| active.model == model)
+        {
+            return Err(SpeechError::ModelInUse(model.id().to_string()));
+        }
+        if guard
+            .model_download
+            .as_ref()
+            .is_some_and(|control| control.model == model)
+        {
+            return Err(SpeechError::ModelDownloading(model.id().to_string()));
+        }
+
+        lock_contexts(&self.whisper_contexts).invalidate(&model);
+        let model_path = self.model_path(&model);
+        let mut freed = 0;
+        for path in [partial_download_path(&model_path), model_path] {
+            // 软链接导入的模型只删除链接，不计入释放的空间
+            if let Ok(meta) = fs::symlink_metadata(&path) {
+                fs::remove_file(&path)?;
+                if meta.is_file() {
+                    freed += meta.len();
+                }
+            }
+        }
+        freed += self.remove_unused_coreml_encoder(&model);
+
+        let had_download = guard.model_config.downloads.remove(model.id()).is_some();
+        if model.spec().is_none() || had_download {
+            guard
+                .model_config
+                .custom
+                .retain(|custom| custom.id != model.id());
+            write_atomic(
+                &self.model_config_file,
+                &serde_json::to_vec_pretty(&guard.model_config)?,
+            )?;
+        }
+        Ok(freed)
+    }
+
+    /// 模型已存在或使用内置模型时直接返回；否则在后台开始下载并立即返回，进度和结果通过事件通知。
+    pub async fn ensure_model(&self, app: &AppHandle) -> Result<ModelStatusResponse, SpeechError> {
+        let model = self.active_model().await;
+        let model_path = self.model_path(&model);
+        let acceleration = self.acceleration(&model).await;
+
+        if model_path.exists() {
+            emit_model_status(app, &model, &model_path, ModelStatusKind::Exists, None);
+            #[cfg(feature = "coreml")]
+            self.spawn_coreml_encoder_download(app, &model);
+            return Ok(ModelStatusResponse::ready(
+                &model,
+                &model_path,
+                false,
+                acceleration,
+            ));
+        }
+        // 模拟转写不需要模型文件，不触发下载
+        if stub::enabled() {
+            return Ok(ModelStatusResponse::ready(
+                &model,
+                &model_path,
+                false,
+                acceleration,
+            ));
+        }
+        if model.spec().is_none() {
+            return Err(SpeechError::CustomModelMissing(model.id().to_string()));
+        }
+
+        ensure_writable()?;
+        if let Some(parent) = model_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if model.id() == DEFAULT_MODEL_ID
+            && self.try_copy_bundled_model(app, &model, &model_path)?
+        {
+            // 内置模型损坏时删除复制的文件并改为下载，否则每次启动都会卡在这里
+            if let Err(err) = verify_model_file(&model, &model_path).await {
+                log::warn!("bundled model failed verification, downloading instead: {err}");
+                let _ = fs::remove_file(&model_path);
+            }
+        }
+        if model_path.exists() {
+            emit_model_status(
+                app,
+                &model,
+                &model_path,
+                ModelStatusKind::Finished,
+                Some("使用内置模型".into()),
+            );
+            #[cfg(feature = "coreml")]
+            self.spawn_coreml_encoder_download(app, &model);
+            return Ok(ModelStatusResponse::ready(
+                &model,
+                &model_path,
+                false,
+                acceleration,
+            ));
+        }
+
+        ensure_online()?;
+        let pending = ModelStatusResponse {
+            ready: false,
+            downloaded: false,
+            model: model.clone(),
+            model_path: Some(model_path.to_string_lossy().into_owned()),
+            acceleration,
+        };
+        self.spawn_model_download(app, &model).await;
+        Ok(pending)
+    }
+
+    /// 在后台下载模型，完成后才替换已有文件；已有下载在进行时返回 false。
+    pub(super) async fn spawn_model_download(&self, app: &AppHandle, model: &SpeechModel) -> bool {
+        let control = Arc::new(DownloadControl::new(model.clone()));
+        {
+            let mut guard = self.state.lock().await;
+            if guard.model_download.is_some() {
+                return false;
+            }
+            guard.model_download = Some(control.clone());
+        }
+        let model_path = self.model_path(model);
+        emit_model_status(app, model, &model_path, ModelStatusKind::Downloading, None);
+
+        let app_handle = app.clone();
+        let model = model.clone();
+        async_runtime::spawn(async move {
+            let manager = app_handle.state::<SpeechManager>();
+            manager
+                .run_model_download(&app_handle, &model, &control)
+                .await;
+        });
+        true
+    }
+
+    async fn run_model_download(
+        &self,
+        app: &AppHandle,
+        model: &SpeechModel,
+        control: &DownloadControl,
+    ) {
+        let model_path = self.model_path(model);
+        let result = match self.download_model(app, model, &model_path, control).await {
+            Ok(info) => match verify_model_file(model, &model_path).await {
+                Ok(()) => {
+                    self.record_model_download(model, info).await;
+                    Ok(())
+                }
+                Err(err) => {
+                    let _ = fs::remove_file(&model_path);
+                    Err(err)
+                }
+            },
+            Err(err) => Err(err),
+        };
+        #[cfg(feature = "coreml")]
+        if result.is_ok() {
+            if let Err(err) = self.download_coreml_encoder(model).await {
+                log::warn!(
+                    "failed to download Core ML encoder for {}: {err}",
+                    model.id()
+                );
+            }
+        }
+        self.state.lock().await.model_download = None;
+
+        match result {
+            Ok(()) => {
+                emit_model_status(app, model, &model_path, ModelStatusKind::Finished, None);
+            }
+            Err(SpeechError::ModelDownloadPaused) => {
+                emit_model_status(app, model, &model_path, ModelStatusKind::Paused, None);
+            }
+            Err(SpeechError::ModelDownloadCancelled) => {
+                let _ = fs::remove_file(partial_download_path(&model_path));
+                emit_model_status(app, model, &model_path, ModelStatusKind::Cancelled, None);
+            }
+            Err(err) => {
+                log::error!("failed to download model {}: {err}", model.id());
+                emit_model_status(
+                    app,
+                    model,
+                    &model_path,
+                    ModelStatusKind::Failed,
+                    Some(err.to_string()),
+                );
+            }
+        }
+    }
+
+    fn try_copy_bundled_model(
+        &self,
+        app: &AppHandle,
+        model: &SpeechModel,
+        model_path: &Path,
+    ) -> Result<bool, SpeechError> {
+        let mut candidate_files: Vec<PathBuf> = Vec::new();
+
+        if let Ok(resource_dir) = app.path().resource_dir() {
+            let search_dirs = [
+                resource_dir.clone(),
+                resource_dir.join("resources"),
+                resource_dir.join("Resources"),
+                resource_dir.join("../resources"),
+                resource_dir.join("../Resources"),
+            ];
+
+            for dir in search_dirs {
+                candidate_files.push(dir.join(BUNDLED_MODEL_RELATIVE_PATH));
+            }
+        }
+
+        if let Some(manifest_dir) = option_env!("CARGO_MANIFEST_DIR") {
+            candidate_files.push(
+                Path::new(manifest_dir)
+                    .join("resources")
+                    .join(BUNDLED_MODEL_RELATIVE_PATH),
+            );
+        }
+
+        candidate_files.push(Path::new("resources").join(BUNDLED_MODEL_RELATIVE_PATH));
+        candidate_files.push(
+            Path::new("src-tauri")
+                .join("resources")
+                .join(BUNDLED_MODEL_RELATIVE_PATH),
+        );
+
+        for candidate in candidate_files {
+            if !candidate.exists() {
+                continue;
+            }
+            if let Err(err) = validate_bundled_model(model, &candidate) {
+                log::warn!("ignoring bundled model at {}: {err}", candidate.display());
+                continue;
+            }
+            // 先复制到临时文件，复制中断时不会留下看似存在的模型文件
+            let temp_path = model_path.with_extension("bin.copying");
+            if let Err(err) =
+                fs::copy(&candidate, &temp_path).and_then(|_| fs::rename(&temp_path, model_path))
+            {
+                let _ = fs::remove_file(&temp_path);
+                return Err(err.into());
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// 下载到 `.part` 临时文件，中断后再次调用时用 Range 请求从已下载的位置继续。
+    /// 网络错误和服务端 5xx / 429 按指数退避重试，每次重试都从 `.part` 已有的位置续传。
+    async fn download_model(
+        &self,
+        app: &AppHandle,
+        model: &SpeechModel,
+        model_path: &Path,
+        control: &DownloadControl,
+    ) -> Result<RemoteModelInfo, SpeechError> {
+        let part_path = partial_download_path(model_path);
+        let (http, url, max_retries) = {
+            let guard = self.state.lock().await;
+            let source = &guard.model_config.source;
+            (
+                guard.http.clone(),
+                model.download_url(source),
+                source.max_retries(),
+            )
+        };
+        let url = url.ok_or_else(|| SpeechError::CustomModelMissing(model.id().to_string()))?;
+        let token = load_token().await;
+
+        let mut failures = 0;
+        let info = loop {
+            let before = part_len(&part_path);
+            let attempt = download_attempt(app, &http, &url, token.as_deref(), &part_path, control);
+            let err = match attempt.await {
+                Ok(info) => break info,
+                Err(SpeechError::Network(err)) if is_retryable(&err) => err,
+                Err(err) => return Err(err),
+            };
+            // 本次尝试有进展时重新计数，只限制连续失败的次数
+            if part_len(&part_path) > before {
+                failures = 0;
+            }
+            failures += 1;
+            if failures > max_retries {
+                return Err(SpeechError::Network(err));
+            }
+            let delay = retry_delay(failures);
+            log::warn!(
+                "download of model {} interrupted ({err}), retry {failures}/{max_retries} in {delay:?}",
+                model.id()
+            );
+            emit_model_status(
+                app,
+                model,
+                model_path,
+                ModelStatusKind::Retrying,
+                Some(format!(
+                    "下载中断，{} 秒后第 {failures} 次重试",
+                    delay.as_secs()
+                )),
+            );
+            wait_for_retry(delay, control).await?;
+        };
+
+        fs::rename(&part_path, model_path)?;
+        Ok(info)
+    }
+}
+
+fn part_len(part_path: &Path) -> u64 {
+    fs::metadata(part_path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+/// 连接失败、超时、读取中断和服务端临时错误可以重试；4xx 等错误重试也不会成功。
+fn is_retryable(err: &reqwest::Error) -> bool {
+    err.status().map_or(true, |status| {
+        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+    })
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// 等待期间也响应取消和暂停。
+async fn wait_for_retry(delay: Duration, control: &DownloadControl) -> Result<(), SpeechError> {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        if control.cancel.load(Ordering::Relaxed) {
+            return Err(SpeechError::ModelDownloadCancelled);
+        }
+        if control.pause.load(Ordering::Relaxed) {
+            return Err(SpeechError::ModelDownloadPaused);
+        }
+        tokio::time::sleep(PROGRESS_EMIT_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// 一次下载请求：从 `.part` 的当前长度续传，数据直接追加写入，失败时已写入的部分保留给下次重试。
+async fn download_attempt(
+    app: &AppHandle,
+    http: &Client,
+    url: &str,
+    token: Option<&str>,
+    part_path: &Path,
+    control: &DownloadControl,
+) -> Result<RemoteModelInfo, SpeechError> {
+    let get = || match token {
+        Some(token) => http.get(url).bearer_auth(token),
+        None => http.get(url),
+    };
+    let mut offset = part_len(part_path);
+    let mut request = get();
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = request.send().await?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        fs::remove_file(part_path)?;
+        offset = 0;
+        response = get().send().await?;
+    }
+    let status = response.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(SpeechError::Credential(format!(
+            "下载源拒绝访问（{status}），请检查 Hugging Face 访问令牌"
+        )));
+    }
+    // 4xx / 5xx 作为网络错误返回，服务器错误和限流由 `is_retryable` 决定重试
+    response.error_for_status_ref()?;
+    if !status.is_success() {
+        return Err(SpeechError::ModelDownloadFailed(status.to_string()));
+    }
+
+    let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+    if !resumed {
+        offset = 0;
+    }
+    let total = response.content_length().map(|length| length + offset);
+    let info = RemoteModelInfo::from_headers(response.headers(), total);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)?;
+    let mut downloaded: u64 = offset;
+    let mut tracker = ProgressTracker::new(offset);
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        if control.cancel.load(Ordering::Relaxed) {
+            return Err(SpeechError::ModelDownloadCancelled);
+        }
+        if control.pause.load(Ordering::Relaxed) {
+            file.flush()?;
+            return Err(SpeechError::ModelDownloadPaused);
+        }
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        if let Some(progress) = tracker.update(downloaded, total) {
+            let _ = app.emit(MODEL_PROGRESS_EVENT, &progress);
+        }
+    }
+
+    file.flush()?;
+    Ok(info)
+}
+
+/// 后台下载的控制标记，由下载循环在每个数据块之间检查。
+#[derive(Debug)]
+pub(super) struct DownloadControl {
+    model: SpeechModel,
+    cancel: AtomicBool,
+    pause: AtomicBool,
+}
+
+impl DownloadControl {
+    fn new(model: SpeechModel) -> Self {
+        Self {
+            model,
+            cancel: AtomicBool::new(false),
+            pause: AtomicBool::new(false),
+        }
+    }
+}
+
+fn emit_model_status(
+    app: &AppHandle,
+    model: &SpeechModel,
+    model_path: &Path,
+    status: ModelStatusKind,
+    message: Option<String>,
+) {
+    let _ = app.emit(
+        MODEL_STATUS_EVENT,
+        ModelStatusEvent {
+            status,
+            model: model.clone(),
+            model_path: Some(model_path.to_string_lossy().into_owned()),
+            message,
+        },
+    );
+}
+
+pub(super) fn file_sha256(path: &Path) -> io::Result<String> {
+    let mut reader = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 打包资源被截断时文件依然存在；复制前检查 ggml 文件头，并要求大小与注册表记录相差不超过 2%。
+fn validate_bundled_model(model: &SpeechModel, path: &Path) -> Result<(), SpeechError> {
+    read_ggml_header(path)?;
+    let Some(spec) = model.spec() else {
+        return Ok(());
+    };
+    let size = fs::metadata(path)?.len();
+    let expected = spec.size_bytes as f64;
+    if (size as f64 - expected).abs() > expected * BUNDLED_SIZE_TOLERANCE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected size {size}, expected about {}", spec.size_bytes),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// 校验失败时返回 `ChecksumMismatch`，由调用方删除损坏的文件。
+async fn verify_model_file(model: &SpeechModel, path: &Path) -> Result<(), SpeechError> {
+    let Some(expected) = model.sha256() else {
+        log::warn!(
+            "no pinned checksum for model {}, skipping verification",
+            model.id()
+        );
+        return Ok(());
+    };
+    let path = path.to_path_buf();
+    let actual = async_runtime::spawn_blocking(move || file_sha256(&path))
+        .await
+        .map_err(|e| SpeechError::Join(e.to_string()))??;
+    if actual != expected {
+        return Err(SpeechError::ChecksumMismatch(model.id().to_string()));
+    }
+    Ok(())
+}
+
+pub(super) fn partial_download_path(model_path: &Path) -> PathBuf {
+    let mut name = model_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    model_path.with_file_name(name)
+}
+
+#[tauri::command]
+pub async fn ensure_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+    app: AppHandle,
+) -> Result<ModelStatusResponse, String> {
+    state.ensure_model(&app).await.map_err(|e| e.to_string())
+}
+
+/// 切换当前使用的模型；目标模型尚未下载时需再调用 `ensure_speech_model`。
+#[tauri::command]
+pub async fn set_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+    model: String,
+) -> Result<ModelStatusResponse, String> {
+    state.set_model(&model).await.map_err(|e| e.to_string())
+}
+
+/// 所有可下载的模型及其体积、内存需求和下载地址（已套用镜像设置），供前端展示模型选择器。
+#[tauri::command]
+pub async fn get_model_registry(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<ModelRegistryEntry>, String> {
+    let source = state.model_source().await;
+    Ok(MODEL_REGISTRY
+        .iter()
+        .filter_map(|spec| {
+            let url = SpeechModel::new(spec.id).download_url(&source)?;
+            Some(ModelRegistryEntry { spec, url })
+        })
+        .collect())
+}
+
+/// 根据本机内存、CPU 和转写后端推荐默认模型，首次启动时用于预选。
+#[tauri::command]
+pub async fn recommend_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<ModelRecommendation, String> {
+    let mut system = System::new();
+    system.refresh_memory();
+    let cpu_cores = num_cpus::get();
+    let available_memory_bytes = system.available_memory();
+    let gpu = state.transcription_backend().await.use_gpu();
+    Ok(ModelRecommendation {
+        model: recommend_model(available_memory_bytes, cpu_cores, gpu),
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes,
+        cpu_cores,
+        gpu,
+    })
+}
+
+#[tauri::command]
+pub async fn list_speech_models(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<InstalledModel>, String> {
+    Ok(state.list_models().await)
+}
+
+#[tauri::command]
+pub async fn cancel_model_download(state: tauri::State<'_, SpeechManager>) -> Result<bool, String> {
+    Ok(state.cancel_model_download().await)
+}
+
+#[tauri::command]
+pub async fn pause_model_download(state: tauri::State<'_, SpeechManager>) -> Result<bool, String> {
+    Ok(state.pause_model_download().await)
+}
+
+/// 从已下载的位置继续当前模型的下载。
+#[tauri::command]
+pub async fn resume_model_download(
+    state: tauri::State<'_, SpeechManager>,
+    app: AppHandle,
+) -> Result<ModelStatusResponse, String> {
+    state.ensure_model(&app).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_speech_model(
+    state: tauri::State<'_, SpeechManager>,
+    model_id: String,
+) -> Result<u64, String> {
+    state
+        .delete_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_model_source(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<ModelSource, String> {
+    Ok(state.model_source().await)
+}
+
+/// 切换模型镜像、代理和下载重试次数，传入空值恢复为直连 huggingface.co 和默认重试次数；对下一次下载立即生效。
+#[tauri::command]
+pub async fn set_model_source(
+    state: tauri::State<'_, SpeechManager>,
+    mirror_url: Option<String>,
+    proxy: Option<String>,
+    max_retries: Option<u32>,
+) -> Result<ModelSource, String> {
+    state
+        .set_model_source(mirror_url, proxy, max_retries)
+        .await
+        .map_err(|e| e.to_string())
+}
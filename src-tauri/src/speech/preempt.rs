@@ -0,0 +1,86 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Condvar, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
+
+use super::SpeechError;
+
+/// 暂停期间检查取消标记的间隔。
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 听写抢占文件转写：听写发出请求后，文件转写在当前切块结束时暂停，听写完成后从下一块继续。
+#[derive(Debug, Default)]
+pub(super) struct Preemption {
+    state: Mutex<PreemptionState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct PreemptionState {
+    requested: bool,
+    parked: bool,
+    finished: bool,
+}
+
+impl Preemption {
+    fn lock(&self) -> MutexGuard<'_, PreemptionState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 已有听写在等待或文件转写已结束时返回 false。
+    pub(super) fn request(&self) -> bool {
+        let mut state = self.lock();
+        if state.requested || state.finished {
+            return false;
+        }
+        state.requested = true;
+        true
+    }
+
+    /// 阻塞到文件转写暂停；它在暂停前就结束时返回 false。
+    pub(super) fn wait_parked(&self) -> bool {
+        let mut state = self.lock();
+        while !state.parked && !state.finished {
+            state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.parked
+    }
+
+    /// 听写结束，让暂停的文件转写继续。
+    pub(super) fn release(&self) {
+        self.lock().requested = false;
+        self.changed.notify_all();
+    }
+
+    /// 文件转写结束，不会再暂停。
+    pub(super) fn finish(&self) {
+        self.lock().finished = true;
+        self.changed.notify_all();
+    }
+
+    /// 文件转写在切块之间调用：有听写请求时暂停到它结束，暂停期间仍可取消。
+    pub(super) fn park_if_requested(&self, cancel_flag: &AtomicBool) -> Result<(), SpeechError> {
+        let mut state = self.lock();
+        if !state.requested {
+            return Ok(());
+        }
+        state.parked = true;
+        self.changed.notify_all();
+        while state.requested {
+            if cancel_flag.load(Ordering::Relaxed) {
+                state.parked = false;
+                return Err(SpeechError::TranscriptionCancelled);
+            }
+            state = self
+                .changed
+                .wait_timeout(state, CANCEL_POLL_INTERVAL)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        state.parked = false;
+        Ok(())
+    }
+}
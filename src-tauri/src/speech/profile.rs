@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    acceleration::TranscriptionBackend, decoding::DecodingSettings, title_template::TitleTemplate,
+    SpeechError, SpeechManager, SpeechModel,
+};
+
+/// 可在设备间复制的语音设置。代理地址可能带账号密码、导入的模型依赖本机路径，均不包含在内。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechSettingsProfile {
+    pub model: SpeechModel,
+    #[serde(default)]
+    pub mirror_url: Option<String>,
+    #[serde(default)]
+    pub backend: TranscriptionBackend,
+    #[serde(default)]
+    pub decoding: DecodingSettings,
+    #[serde(default)]
+    pub title_template: TitleTemplate,
+}
+
+impl SpeechManager {
+    pub async fn settings_profile(&self) -> SpeechSettingsProfile {
+        let guard = self.state.lock().await;
+        SpeechSettingsProfile {
+            model: guard.model_config.active.clone(),
+            mirror_url: guard.model_config.source.mirror_url.clone(),
+            backend: guard.model_config.backend,
+            decoding: guard.decoding,
+            title_template: guard.title_template.clone(),
+        }
+    }
+
+    /// 逐项应用导入的设置，本机不存在的模型跳过并返回提示，其余校验失败时中止。
+    pub async fn apply_settings_profile(
+        &self,
+        profile: SpeechSettingsProfile,
+    ) -> Result<Vec<String>, SpeechError> {
+        let mut skipped = Vec::new();
+        self.set_decoding_settings(profile.decoding).await?;
+        self.set_title_template(profile.title_template).await?;
+        let source = self.model_source().await;
+        self.set_model_source(profile.mirror_url, source.proxy, source.max_retries)
+            .await?;
+        match self.set_transcription_backend(profile.backend).await {
+            Ok(_) => {}
+            Err(SpeechError::BackendUnavailable(backend)) => {
+                skipped.push(format!(
+                    "本机版本不支持 {} 后端，保留当前设置",
+                    backend.label()
+                ));
+            }
+            Err(err) => return Err(err),
+        }
+        match self.set_model(profile.model.id()).await {
+            Ok(_) => {}
+            Err(SpeechError::UnknownModel(id)) => {
+                skipped.push(format!("模型 {id} 在本机不可用，保留当前模型"));
+            }
+            Err(err) => return Err(err),
+        }
+        Ok(skipped)
+    }
+}
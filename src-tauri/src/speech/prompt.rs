@@ -0,0 +1,46 @@
+use super::{SpeechError, SpeechLanguage};
+
+/// whisper 只使用提示末尾约 224 个 token，过长的提示会把前面的内容挤掉。
+pub(super) const MAX_PROMPT_CHARS: usize = 600;
+
+/// 未指定提示时的默认提示；中文提示会让翻译结果偏向中文，翻译时不使用。
+pub(super) fn default_prompt(language: SpeechLanguage, translate: bool) -> Option<&'static str> {
+    (language == SpeechLanguage::CHINESE && !translate).then_some("以下是简体中文普通话的句子。")
+}
+
+/// 把自定义提示和词汇表（人名、术语、产品名）拼成一条提示；都为空时返回 `None`，使用默认提示。
+pub(super) fn compose(
+    initial_prompt: Option<&str>,
+    vocabulary: &[String],
+    language: SpeechLanguage,
+) -> Result<Option<String>, SpeechError> {
+    let mut terms: Vec<&str> = Vec::new();
+    for term in vocabulary.iter().map(|term| term.trim()) {
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(prompt) = initial_prompt
+        .map(str::trim)
+        .filter(|prompt| !prompt.is_empty())
+    {
+        parts.push(prompt.to_string());
+    }
+    if !terms.is_empty() {
+        // 用与录音相同语言的句式，避免提示把识别结果带偏到其他语言
+        parts.push(if language == SpeechLanguage::CHINESE {
+            format!("词汇：{}。", terms.join("、"))
+        } else {
+            format!("Vocabulary: {}.", terms.join(", "))
+        });
+    }
+    if parts.is_empty() {
+        return Ok(None);
+    }
+    let prompt = parts.join(" ");
+    if prompt.chars().count() > MAX_PROMPT_CHARS {
+        return Err(SpeechError::InitialPromptTooLong(MAX_PROMPT_CHARS));
+    }
+    Ok(Some(prompt))
+}
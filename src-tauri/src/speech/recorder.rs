@@ -0,0 +1,985 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    FromSample, Sample, SampleFormat, SizedSample, StreamConfig,
+};
+use hound::{SampleFormat as WavSampleFormat, WavSpec, WavWriter};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::{
+    ensure_writable, prompt, AudioInput, ChannelMode, RecordingSettings, SpeechError,
+    SpeechLanguage, SpeechManager, SpeechSession, TranscribeAudioResponse, TranscriptionOptions,
+    TranscriptionRequest, VoiceMemo,
+};
+use crate::badge;
+use crate::calendar::{CalendarEvent, CalendarManager};
+use crate::datetime::now_utc;
+use crate::links::{EntityKind, EntityRef, LinkStore};
+use crate::notifications::notify_transcription_finished;
+
+const RECORDING_FILENAME: &str = "recording.wav";
+const GAIN_CURVE_FILENAME: &str = "gain.json";
+const PART_LINK_LABEL: &str = "下一段";
+/// 录音线程检查当前文件是否录满的间隔。
+const ROLLOVER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// 自动增益的目标电平，约 -20 dBFS。
+const AGC_TARGET_RMS: f32 = 0.1;
+/// 最多放大 20 dB；只提升偏小的音量，不压低正常音量。
+const AGC_MAX_GAIN: f32 = 10.0;
+/// 低于此电平视为静音，保持当前增益，避免把底噪放大。
+const AGC_NOISE_FLOOR: f32 = 0.005;
+/// 增益每秒最多升高一倍（约 6 dB）；降低不受限制，避免削波。
+const AGC_RISE_PER_SECOND: f32 = 2.0;
+/// 回声消除滤波器覆盖的时长，包括扬声器播放到麦克风采集之间的延迟和房间混响。
+const AEC_TAIL_SECONDS: f32 = 0.128;
+/// NLMS 步长；越大收敛越快，但双方同时说话时越容易把人声当作回声削掉。
+const AEC_STEP: f32 = 0.3;
+/// 麦克风电平超过参考峰值的一半时认为本地有人说话，暂停滤波器的自适应。
+const AEC_DOUBLE_TALK: f32 = 0.5;
+/// 参考信号的平均电平低于此值时视为扬声器没有声音，不更新滤波器。
+const AEC_SILENCE: f32 = 0.001;
+/// 参考缓冲最多保留的秒数，暂停录音或两个设备时钟不一致时丢掉过旧的参考。
+const AEC_REFERENCE_SECONDS: f32 = 0.5;
+
+type Writer = WavWriter<BufWriter<File>>;
+type SharedWriter = Arc<Mutex<Option<Writer>>>;
+
+/// 把设备的多声道、任意采样率输入实时转换为录音设置的采样率和单声道。
+struct StreamResampler {
+    channels: usize,
+    step: f64,
+    position: f64,
+    pending: Vec<f32>,
+}
+
+impl StreamResampler {
+    fn new(channels: u16, sample_rate: u32, target_rate: u32) -> Self {
+        Self {
+            channels: usize::from(channels.max(1)),
+            step: f64::from(sample_rate) / f64::from(target_rate),
+            position: 0.0,
+            pending: Vec::new(),
+        }
+    }
+
+    fn process<T>(&mut self, input: &[T], output: &mut Vec<f32>)
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        self.pending
+            .extend(input.chunks(self.channels).map(|frame| {
+                frame
+                    .iter()
+                    .map(|sample| f32::from_sample(*sample))
+                    .sum::<f32>()
+                    / frame.len() as f32
+            }));
+        while self.position + 1.0 < self.pending.len() as f64 {
+            let index = self.position as usize;
+            let frac = (self.position - index as f64) as f32;
+            let (s0, s1) = (self.pending[index], self.pending[index + 1]);
+            output.push(s0 + (s1 - s0) * frac);
+            self.position += self.step;
+        }
+        let consumed = (self.position as usize).min(self.pending.len());
+        self.pending.drain(..consumed);
+        self.position -= consumed as f64;
+    }
+}
+
+/// 按块估计音量并调整增益，增益降低时立即跟上，升高时缓慢变化，避免音量忽大忽小。
+struct AutoGain {
+    gain: f32,
+    sample_rate: f32,
+    /// 距上次记录增益已处理的样本数，每秒记录一次。
+    since_point: usize,
+}
+
+impl AutoGain {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            gain: 1.0,
+            sample_rate: sample_rate as f32,
+            since_point: 0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32], curve: &Mutex<Vec<f32>>) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|sample| sample * sample).sum::<f32>()
+            / samples.len() as f32)
+            .sqrt();
+        if rms > AGC_NOISE_FLOOR {
+            let wanted = (AGC_TARGET_RMS / rms).clamp(1.0, AGC_MAX_GAIN);
+            let rise = AGC_RISE_PER_SECOND.powf(samples.len() as f32 / self.sample_rate);
+            self.gain = wanted.min(self.gain * rise);
+        }
+        for sample in samples.iter_mut() {
+            *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+        }
+        self.since_point += samples.len();
+        let per_point = self.sample_rate as usize;
+        if self.since_point >= per_point {
+            self.since_point -= per_point;
+            curve
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(self.gain);
+        }
+    }
+}
+
+/// 以扬声器正在播放的声音为参考，用 NLMS 自适应滤波估计麦克风中的回声并减去。
+struct EchoCanceller {
+    weights: Vec<f32>,
+    /// 最近的参考样本存两份，`head` 起的连续 `weights.len()` 个样本即为从旧到新的窗口。
+    history: Vec<f32>,
+    head: usize,
+    min_energy: f32,
+}
+
+impl EchoCanceller {
+    fn new(sample_rate: u32) -> Self {
+        let taps = ((sample_rate as f32 * AEC_TAIL_SECONDS) as usize).max(1);
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; taps * 2],
+            head: 0,
+            min_energy: taps as f32 * AEC_SILENCE * AEC_SILENCE,
+        }
+    }
+
+    /// 每个麦克风样本取一个参考样本；参考不够时按静音处理，此时声音原样通过。
+    fn process(&mut self, samples: &mut [f32], reference: &Mutex<VecDeque<f32>>) {
+        let mut reference = reference.lock().unwrap_or_else(|e| e.into_inner());
+        let taps = self.weights.len();
+        for sample in samples.iter_mut() {
+            let far = reference.pop_front().unwrap_or(0.0);
+            self.history[self.head] = far;
+            self.history[self.head + taps] = far;
+            self.head = (self.head + 1) % taps;
+            let window = &self.history[self.head..self.head + taps];
+
+            let (estimate, peak, energy) = window.iter().zip(&self.weights).fold(
+                (0.0_f32, 0.0_f32, 0.0_f32),
+                |(estimate, peak, energy), (x, w)| {
+                    (estimate + x * w, peak.max(x.abs()), energy + x * x)
+                },
+            );
+            let error = *sample - estimate;
+            if energy > self.min_energy && sample.abs() < AEC_DOUBLE_TALK * peak {
+                let step = AEC_STEP * error / energy;
+                for (weight, x) in self.weights.iter_mut().zip(window) {
+                    *weight += step * x;
+                }
+            }
+            *sample = error.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// 录音线程与命令之间共享的状态；cpal 的 Stream 不能跨线程，只能留在录音线程里。
+#[derive(Default)]
+struct RecordingShared {
+    paused: AtomicBool,
+    samples_written: AtomicU64,
+    error: Mutex<Option<String>>,
+    /// 开启自动增益时每秒的增益倍数。
+    gain_curve: Mutex<Vec<f32>>,
+    /// 还没有开始写文件时最近几秒的声音，开始录音时写在文件开头。
+    pre_roll: Mutex<VecDeque<f32>>,
+    /// 已经开始写的文件，最后一个为正在写的文件。
+    parts: Mutex<Vec<RecordingPart>>,
+    /// 开启回声消除时从扬声器回环采集到、还没有被麦克风回调用掉的声音。
+    echo_reference: Mutex<VecDeque<f32>>,
+}
+
+/// 一个录音文件；录满设置的时长后切换到新的会话目录继续录。
+struct RecordingPart {
+    session_id: String,
+    audio_path: PathBuf,
+    /// 本文件第一个样本在整段录音中的位置。
+    first_sample: u64,
+    /// 切换到下一个文件时记下本文件的增益曲线。
+    gain_curve: Vec<f32>,
+}
+
+/// 打开着的麦克风；开始写文件之前的声音只保留在预录缓冲中。
+struct Capture {
+    settings: RecordingSettings,
+    writer: SharedWriter,
+    shared: Arc<RecordingShared>,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<Result<(), SpeechError>>,
+}
+
+struct ActiveRecording {
+    session_id: String,
+    started_at: String,
+    capture: Capture,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordingStatus {
+    /// 第一个文件所在的会话。
+    pub session_id: String,
+    pub started_at: String,
+    pub paused: bool,
+    pub duration_seconds: f64,
+    /// 按单个文件的时长切分出的文件数。
+    pub parts: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordedAudio {
+    pub session_id: String,
+    /// 相对数据目录的路径，与 `SpeechSession::audio_path` 一致。
+    pub audio_path: String,
+    pub duration_seconds: f64,
+    /// 开启自动增益时每秒的增益倍数，便于排查音量问题。
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gain_curve: Vec<f32>,
+    /// 录音超过单个文件的时长时切分出的后续文件，按时间顺序，各自位于新的会话目录。
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<RecordedAudio>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscribeRecordingPayload {
+    pub session_id: String,
+    pub language: String,
+    #[serde(default)]
+    pub session_title: Option<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    /// 转写前跳过较长的静音。
+    #[serde(default)]
+    pub vad_enabled: bool,
+    /// 翻译成英文。
+    #[serde(default)]
+    pub translate: bool,
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    #[serde(default)]
+    pub vocabulary: Vec<String>,
+    #[serde(default)]
+    pub options: TranscriptionOptions,
+}
+
+/// 后端直接录音，按录音设置写入会话目录下的单声道 WAV，前端不再需要采集音频并 base64 传输。
+#[derive(Default)]
+pub struct SpeechRecorder {
+    active: Mutex<Option<ActiveRecording>>,
+    /// 录音控件打开、尚未开始录音时提前打开的麦克风。
+    armed: Mutex<Option<Capture>>,
+}
+
+fn recording_error(err: impl ToString) -> SpeechError {
+    SpeechError::Recording(err.to_string())
+}
+
+fn write_sample(writer: &mut Writer, bits_per_sample: u16, sample: f32) -> hound::Result<()> {
+    match bits_per_sample {
+        16 => writer.write_sample(i16::from_sample(sample)),
+        24 => writer.write_sample((sample.clamp(-1.0, 1.0) * 8_388_607.0) as i32),
+        _ => writer.write_sample(sample),
+    }
+}
+
+fn max_sample_rate(device: &cpal::Device) -> Option<u32> {
+    device
+        .supported_input_configs()
+        .ok()?
+        .map(|config| config.max_sample_rate().0)
+        .max()
+}
+
+/// 默认麦克风支持的最高采样率，没有麦克风时为 `None`。
+pub(super) fn max_input_sample_rate() -> Option<u32> {
+    max_sample_rate(&cpal::default_host().default_input_device()?)
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    settings: RecordingSettings,
+    writer: SharedWriter,
+    shared: Arc<RecordingShared>,
+) -> Result<cpal::Stream, SpeechError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut resampler =
+        StreamResampler::new(config.channels, config.sample_rate.0, settings.sample_rate);
+    let mut echo_canceller = settings
+        .echo_cancellation
+        .then(|| EchoCanceller::new(settings.sample_rate));
+    let mut auto_gain = settings
+        .auto_gain
+        .then(|| AutoGain::new(settings.sample_rate));
+    let pre_roll_capacity = settings.pre_roll_seconds as usize * settings.sample_rate as usize;
+    let mut converted = Vec::new();
+    let error_shared = shared.clone();
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _| {
+                if shared.paused.load(Ordering::Relaxed) {
+                    return;
+                }
+                converted.clear();
+                resampler.process(data, &mut converted);
+                // 先消除回声再调增益，增益变化不影响回声路径的估计
+                if let Some(echo_canceller) = echo_canceller.as_mut() {
+                    echo_canceller.process(&mut converted, &shared.echo_reference);
+                }
+                if let Some(auto_gain) = auto_gain.as_mut() {
+                    auto_gain.process(&mut converted, &shared.gain_curve);
+                }
+                let mut guard = writer.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(writer) = guard.as_mut() else {
+                    if pre_roll_capacity > 0 {
+                        let mut pre_roll =
+                            shared.pre_roll.lock().unwrap_or_else(|e| e.into_inner());
+                        pre_roll.extend(&converted);
+                        let excess = pre_roll.len().saturating_sub(pre_roll_capacity);
+                        pre_roll.drain(..excess);
+                    }
+                    return;
+                };
+                for sample in &converted {
+                    if let Err(err) = write_sample(writer, settings.bits_per_sample, *sample) {
+                        *shared.error.lock().unwrap_or_else(|e| e.into_inner()) =
+                            Some(err.to_string());
+                        return;
+                    }
+                }
+                shared
+                    .samples_written
+                    .fetch_add(converted.len() as u64, Ordering::Relaxed);
+            },
+            move |err| {
+                log::error!("microphone stream error: {err}");
+                *error_shared.error.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some(err.to_string());
+            },
+            None,
+        )
+        .map_err(recording_error)
+}
+
+/// 把扬声器回环采集到的声音转换为录音的采样率和单声道，放进回声消除的参考缓冲。
+fn build_reference_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    sample_rate: u32,
+    shared: Arc<RecordingShared>,
+) -> Result<cpal::Stream, SpeechError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut resampler = StreamResampler::new(config.channels, config.sample_rate.0, sample_rate);
+    let capacity = (sample_rate as f32 * AEC_REFERENCE_SECONDS) as usize;
+    let mut converted = Vec::new();
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _| {
+                converted.clear();
+                resampler.process(data, &mut converted);
+                let mut reference = shared
+                    .echo_reference
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                reference.extend(&converted);
+                let excess = reference.len().saturating_sub(capacity);
+                reference.drain(..excess);
+            },
+            move |err| log::warn!("echo reference stream error: {err}"),
+            None,
+        )
+        .map_err(recording_error)
+}
+
+/// 回环采集默认输出设备正在播放的声音，作为回声消除的参考；目前只有 Windows (WASAPI) 支持。
+fn open_reference_stream(
+    settings: RecordingSettings,
+    shared: Arc<RecordingShared>,
+) -> Result<cpal::Stream, SpeechError> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| recording_error("没有找到可用的扬声器"))?;
+    let supported = device.default_output_config().map_err(recording_error)?;
+    let config = supported.config();
+    let rate = settings.sample_rate;
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_reference_stream::<f32>(&device, &config, rate, shared),
+        SampleFormat::I16 => build_reference_stream::<i16>(&device, &config, rate, shared),
+        SampleFormat::U16 => build_reference_stream::<u16>(&device, &config, rate, shared),
+        SampleFormat::I32 => build_reference_stream::<i32>(&device, &config, rate, shared),
+        other => Err(recording_error(format!("不支持的采样格式：{other}"))),
+    }?;
+    stream.play().map_err(recording_error)?;
+    Ok(stream)
+}
+
+fn wav_spec(settings: RecordingSettings) -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate: settings.sample_rate,
+        bits_per_sample: settings.bits_per_sample,
+        sample_format: if settings.bits_per_sample == 32 {
+            WavSampleFormat::Float
+        } else {
+            WavSampleFormat::Int
+        },
+    }
+}
+
+/// 打开默认麦克风，`writer` 为空时声音进入预录缓冲；返回的 Stream 必须留在创建它的线程上。
+fn open_stream(
+    settings: RecordingSettings,
+    writer: SharedWriter,
+    shared: Arc<RecordingShared>,
+) -> Result<cpal::Stream, SpeechError> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| recording_error("没有找到可用的麦克风"))?;
+    let supported = device.default_input_config().map_err(recording_error)?;
+    // 设置保存后可能换了麦克风，开始录音时再检查一次
+    if let Some(max) = max_sample_rate(&device).filter(|max| settings.sample_rate > *max) {
+        return Err(recording_error(format!(
+            "麦克风最高只支持 {max} Hz，请在录音设置中调低采样率"
+        )));
+    }
+    let config = supported.config();
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, settings, writer, shared),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, settings, writer, shared),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, settings, writer, shared),
+        SampleFormat::I32 => build_stream::<i32>(&device, &config, settings, writer, shared),
+        other => Err(recording_error(format!("不支持的采样格式：{other}"))),
+    }?;
+    stream.play().map_err(recording_error)?;
+    Ok(stream)
+}
+
+/// 增益曲线保存在录音旁边，只用于诊断，保存失败不影响录音。
+fn save_gain_curve(audio_path: &Path, gain_curve: &[f32]) -> Result<(), SpeechError> {
+    if let Some(session_dir) = audio_path.parent() {
+        fs::write(
+            session_dir.join(GAIN_CURVE_FILENAME),
+            serde_json::to_vec(gain_curve)?,
+        )?;
+    }
+    Ok(())
+}
+
+fn lock_parts(shared: &RecordingShared) -> std::sync::MutexGuard<'_, Vec<RecordingPart>> {
+    shared.parts.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 正在写的文件已经录下的样本数，还没有开始写文件时为 `None`。
+fn current_part_samples(shared: &RecordingShared) -> Option<u64> {
+    let written = shared.samples_written.load(Ordering::Relaxed);
+    lock_parts(shared)
+        .last()
+        .map(|part| written.saturating_sub(part.first_sample))
+}
+
+/// 在新的会话目录中创建下一个文件，并在写入锁内换下当前文件，前后两个文件之间不丢声音。
+fn roll_over(
+    settings: RecordingSettings,
+    sessions_dir: &Path,
+    writer: &SharedWriter,
+    shared: &RecordingShared,
+) -> Result<(), SpeechError> {
+    let session_id = Uuid::new_v4().to_string();
+    let session_dir = sessions_dir.join(&session_id);
+    fs::create_dir_all(&session_dir)?;
+    let audio_path = session_dir.join(RECORDING_FILENAME);
+    let next = WavWriter::create(&audio_path, wav_spec(settings))?;
+    let (previous, first_sample) = {
+        let mut guard = writer.lock().unwrap_or_else(|e| e.into_inner());
+        let first_sample = shared.samples_written.load(Ordering::Relaxed);
+        (guard.replace(next), first_sample)
+    };
+    if let Some(previous) = previous {
+        previous.finalize()?;
+    }
+    let gain_curve =
+        std::mem::take(&mut *shared.gain_curve.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut parts = lock_parts(shared);
+    if let Some(last) = parts.last_mut() {
+        last.gain_curve = gain_curve;
+    }
+    parts.push(RecordingPart {
+        session_id,
+        audio_path,
+        first_sample,
+        gain_curve: Vec::new(),
+    });
+    Ok(())
+}
+
+/// 录音线程：通过 `ready` 报告麦克风是否打开成功，之后一直录到收到停止信号。
+fn record(
+    settings: RecordingSettings,
+    sessions_dir: PathBuf,
+    writer: SharedWriter,
+    shared: Arc<RecordingShared>,
+    stop: mpsc::Receiver<()>,
+    ready: mpsc::Sender<Result<(), SpeechError>>,
+) -> Result<(), SpeechError> {
+    let stream = match open_stream(settings, writer.clone(), shared.clone()) {
+        Ok(opened) => {
+            let _ = ready.send(Ok(()));
+            opened
+        }
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            return Ok(());
+        }
+    };
+    // 参考打不开时没有回声可减，麦克风的声音原样写入
+    let reference = if settings.echo_cancellation {
+        match open_reference_stream(settings, shared.clone()) {
+            Ok(opened) => Some(opened),
+            Err(err) => {
+                log::warn!("echo cancellation unavailable: {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut part_limit = (settings.max_part_minutes > 0)
+        .then(|| u64::from(settings.max_part_minutes) * 60 * u64::from(settings.sample_rate));
+    // 发送端被丢弃（应用退出）时同样结束录音
+    while let Err(mpsc::RecvTimeoutError::Timeout) = stop.recv_timeout(ROLLOVER_CHECK_INTERVAL) {
+        let Some(limit) = part_limit else {
+            continue;
+        };
+        if current_part_samples(&shared).is_some_and(|samples| samples >= limit) {
+            if let Err(err) = roll_over(settings, &sessions_dir, &writer, &shared) {
+                // 切换失败时继续写当前文件，不再尝试切分
+                log::warn!("failed to roll over recording: {err}");
+                part_limit = None;
+            }
+        }
+    }
+    drop(reference);
+    drop(stream);
+    let writer = writer.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(writer) = writer {
+        writer.finalize()?;
+    }
+    Ok(())
+}
+
+impl Capture {
+    /// 在录音线程中打开麦克风，麦克风打开成功后返回；切分出的文件写在 `sessions_dir` 下。
+    fn open(settings: RecordingSettings, sessions_dir: &Path) -> Result<Self, SpeechError> {
+        let writer: SharedWriter = Arc::new(Mutex::new(None));
+        let shared = Arc::new(RecordingShared::default());
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("speech-recorder".into())
+            .spawn({
+                let (writer, shared) = (writer.clone(), shared.clone());
+                let sessions_dir = sessions_dir.to_path_buf();
+                move || record(settings, sessions_dir, writer, shared, stop_rx, ready_tx)
+            })?;
+
+        let opened = ready_rx
+            .recv()
+            .unwrap_or_else(|_| Err(recording_error("录音线程意外退出")));
+        if let Err(err) = opened {
+            let _ = thread.join();
+            return Err(err);
+        }
+        Ok(Self {
+            settings,
+            writer,
+            shared,
+            stop: stop_tx,
+            thread,
+        })
+    }
+
+    /// 开始写入 `audio_path`，预录缓冲中的声音写在最前面。
+    fn begin(&self, session_id: &str, audio_path: &Path) -> Result<(), SpeechError> {
+        let mut writer = WavWriter::create(audio_path, wav_spec(self.settings))?;
+        lock_parts(&self.shared).push(RecordingPart {
+            session_id: session_id.to_string(),
+            audio_path: audio_path.to_path_buf(),
+            first_sample: 0,
+            gain_curve: Vec::new(),
+        });
+        // 先在锁外写入大部分缓冲，避免长时间阻塞音频回调；持锁时只补上这期间新进来的声音
+        self.write_pre_roll(&mut writer)?;
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        self.write_pre_roll(&mut writer)?;
+        *guard = Some(writer);
+        drop(guard);
+
+        // 增益曲线只保留写入文件的部分
+        let mut curve = self
+            .shared
+            .gain_curve
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let written_seconds = (self.duration_seconds().ceil() as usize).min(curve.len());
+        let excess = curve.len() - written_seconds;
+        curve.drain(..excess);
+        Ok(())
+    }
+
+    fn write_pre_roll(&self, writer: &mut Writer) -> Result<(), SpeechError> {
+        let buffered: Vec<f32> = self
+            .shared
+            .pre_roll
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+            .collect();
+        for sample in &buffered {
+            write_sample(writer, self.settings.bits_per_sample, *sample)?;
+        }
+        self.shared
+            .samples_written
+            .fetch_add(buffered.len() as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn duration_seconds(&self) -> f64 {
+        self.shared.samples_written.load(Ordering::Relaxed) as f64
+            / f64::from(self.settings.sample_rate)
+    }
+
+    /// 关闭麦克风并写完文件；录音过程中出错时返回错误。
+    fn close(self) -> Result<(), SpeechError> {
+        let _ = self.stop.send(());
+        self.thread
+            .join()
+            .map_err(|_| recording_error("录音线程异常退出"))??;
+        if let Some(err) = self
+            .shared
+            .error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            return Err(SpeechError::Recording(err));
+        }
+        Ok(())
+    }
+}
+
+impl SpeechRecorder {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<ActiveRecording>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_armed(&self) -> std::sync::MutexGuard<'_, Option<Capture>> {
+        self.armed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 录音控件打开时提前打开麦克风，缓存最近几秒的声音；未开启预录或正在录音时不做任何事。
+    pub async fn arm(&self, speech: &SpeechManager) -> Result<(), SpeechError> {
+        let settings = speech.recording_settings().await;
+        if settings.pre_roll_seconds == 0 || self.lock().is_some() {
+            return Ok(());
+        }
+        let mut armed = self.lock_armed();
+        if armed.is_none() {
+            *armed = Some(Capture::open(settings, &speech.sessions_dir)?);
+        }
+        Ok(())
+    }
+
+    /// 录音控件关闭时调用，丢弃预录缓冲并关闭麦克风。
+    pub fn disarm(&self) {
+        if let Some(capture) = self.lock_armed().take() {
+            if let Err(err) = capture.close() {
+                log::warn!("failed to close armed microphone: {err}");
+            }
+        }
+    }
+
+    pub async fn start(&self, speech: &SpeechManager) -> Result<RecordingStatus, SpeechError> {
+        ensure_writable()?;
+        let settings = speech.recording_settings().await;
+        let mut active = self.lock();
+        if active.is_some() {
+            return Err(SpeechError::RecordingInProgress);
+        }
+
+        // 提前打开的麦克风沿用打开时的设置，缓冲的声音写在录音开头
+        let capture = match self.lock_armed().take() {
+            Some(capture) => capture,
+            None => Capture::open(settings, &speech.sessions_dir)?,
+        };
+        let session_id = Uuid::new_v4().to_string();
+        let session_dir = speech.sessions_dir.join(&session_id);
+        let audio_path = session_dir.join(RECORDING_FILENAME);
+        let begun = fs::create_dir_all(&session_dir)
+            .map_err(SpeechError::from)
+            .and_then(|()| capture.begin(&session_id, &audio_path));
+        if let Err(err) = begun {
+            let _ = capture.close();
+            let _ = fs::remove_dir_all(&session_dir);
+            return Err(err);
+        }
+
+        let recording = ActiveRecording {
+            session_id,
+            started_at: now_utc(),
+            capture,
+        };
+        let status = recording.status();
+        *active = Some(recording);
+        Ok(status)
+    }
+
+    pub fn set_paused(&self, paused: bool) -> Result<RecordingStatus, SpeechError> {
+        let active = self.lock();
+        let recording = active.as_ref().ok_or(SpeechError::NoActiveRecording)?;
+        recording
+            .capture
+            .shared
+            .paused
+            .store(paused, Ordering::Relaxed);
+        Ok(recording.status())
+    }
+
+    pub fn status(&self) -> Option<RecordingStatus> {
+        self.lock().as_ref().map(ActiveRecording::status)
+    }
+
+    /// 停止并写完 WAV 文件；录音过程中出错时返回错误，已录下的部分保留在会话目录中。
+    pub fn stop(&self, speech: &SpeechManager) -> Result<RecordedAudio, SpeechError> {
+        let recording = self.lock().take().ok_or(SpeechError::NoActiveRecording)?;
+        let shared = recording.capture.shared.clone();
+        let sample_rate = f64::from(recording.capture.settings.sample_rate);
+        recording.capture.close()?;
+
+        let total = shared.samples_written.load(Ordering::Relaxed);
+        let mut parts = std::mem::take(&mut *lock_parts(&shared));
+        if let Some(last) = parts.last_mut() {
+            last.gain_curve =
+                std::mem::take(&mut *shared.gain_curve.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+        let ends: Vec<u64> = parts
+            .iter()
+            .skip(1)
+            .map(|part| part.first_sample)
+            .chain([total])
+            .collect();
+        let mut recorded = parts.into_iter().zip(ends).map(|(part, end)| {
+            if !part.gain_curve.is_empty() {
+                if let Err(err) = save_gain_curve(&part.audio_path, &part.gain_curve) {
+                    log::warn!("failed to save gain curve: {err}");
+                }
+            }
+            let audio_path = part
+                .audio_path
+                .strip_prefix(&speech.base_dir)
+                .unwrap_or(&part.audio_path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            RecordedAudio {
+                session_id: part.session_id,
+                audio_path,
+                duration_seconds: end.saturating_sub(part.first_sample) as f64 / sample_rate,
+                gain_curve: part.gain_curve,
+                parts: Vec::new(),
+            }
+        });
+        let mut first = recorded.next().ok_or(SpeechError::NoActiveRecording)?;
+        first.parts = recorded.collect();
+        Ok(first)
+    }
+}
+
+impl ActiveRecording {
+    fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            session_id: self.session_id.clone(),
+            started_at: self.started_at.clone(),
+            paused: self.capture.shared.paused.load(Ordering::Relaxed),
+            duration_seconds: self.capture.duration_seconds(),
+            parts: lock_parts(&self.capture.shared).len(),
+        }
+    }
+}
+
+impl SpeechManager {
+    /// 取出录好的音频作为语音备忘（如语音速记），并删除录音时创建的会话目录。
+    pub fn take_recorded_memo(&self, session_id: &str) -> Result<VoiceMemo, SpeechError> {
+        let session_dir = self.sessions_dir.join(session_id);
+        let audio_path = session_dir.join(RECORDING_FILENAME);
+        if Uuid::parse_str(session_id).is_err() || !audio_path.exists() {
+            return Err(SpeechError::RecordingNotFound(session_id.to_string()));
+        }
+        let memo = VoiceMemo::from_bytes(fs::read(&audio_path)?);
+        if let Err(err) = fs::remove_dir_all(&session_dir) {
+            log::warn!("failed to remove recording {session_id}: {err}");
+        }
+        memo
+    }
+
+    /// 转写 `stop_recording` 录好的音频，会话沿用录音时创建的目录。
+    async fn transcribe_recording(
+        &self,
+        app: &AppHandle,
+        payload: TranscribeRecordingPayload,
+        calendar_event: Option<CalendarEvent>,
+    ) -> Result<SpeechSession, SpeechError> {
+        let language = SpeechLanguage::try_from(payload.language.as_str())?;
+        if Uuid::parse_str(&payload.session_id).is_err()
+            || !self
+                .sessions_dir
+                .join(&payload.session_id)
+                .join(RECORDING_FILENAME)
+                .exists()
+        {
+            return Err(SpeechError::RecordingNotFound(payload.session_id));
+        }
+        self.transcribe_bytes(TranscriptionRequest {
+            audio: AudioInput::Recorded(payload.session_id),
+            language,
+            title: payload.session_title,
+            project: payload.project,
+            calendar_event,
+            channel_mode: ChannelMode::Mix,
+            vad_enabled: payload.vad_enabled,
+            translate: payload.translate,
+            initial_prompt: prompt::compose(
+                payload.initial_prompt.as_deref(),
+                &payload.vocabulary,
+                language,
+            )?,
+            options: payload.options,
+            retry_of: None,
+            app: Some(app.clone()),
+        })
+        .await
+    }
+}
+
+/// 录音控件打开时调用，开启预录后开始录音时会带上此前几秒的声音；停止录音后需要重新调用。
+#[tauri::command]
+pub async fn arm_recording(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+) -> Result<(), String> {
+    recorder.arm(&speech).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn disarm_recording(recorder: tauri::State<'_, SpeechRecorder>) -> Result<(), String> {
+    recorder.disarm();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_recording(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+) -> Result<RecordingStatus, String> {
+    recorder.start(&speech).await.map_err(|e| e.to_string())
+}
+
+/// `paused` 为 false 时继续录音；暂停期间的声音不会写入文件。
+#[tauri::command]
+pub async fn pause_recording(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    paused: Option<bool>,
+) -> Result<RecordingStatus, String> {
+    recorder
+        .set_paused(paused.unwrap_or(true))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_recording_status(
+    recorder: tauri::State<'_, SpeechRecorder>,
+) -> Result<Option<RecordingStatus>, String> {
+    Ok(recorder.status())
+}
+
+/// 切分出的文件依次链接到下一个，作为同一段录音的章节。
+async fn link_parts(links: &LinkStore, recorded: &RecordedAudio) {
+    let mut previous = &recorded.session_id;
+    for part in &recorded.parts {
+        let link = links
+            .create(
+                EntityRef {
+                    kind: EntityKind::Session,
+                    id: previous.clone(),
+                },
+                EntityRef {
+                    kind: EntityKind::Session,
+                    id: part.session_id.clone(),
+                },
+                Some(PART_LINK_LABEL.to_string()),
+            )
+            .await;
+        if let Err(err) = link {
+            log::warn!("failed to link recording parts: {err}");
+        }
+        previous = &part.session_id;
+    }
+}
+
+#[tauri::command]
+pub async fn stop_recording(
+    recorder: tauri::State<'_, SpeechRecorder>,
+    speech: tauri::State<'_, SpeechManager>,
+    links: tauri::State<'_, LinkStore>,
+) -> Result<RecordedAudio, String> {
+    let recorded = recorder.stop(&speech).map_err(|e| e.to_string())?;
+    link_parts(&links, &recorded).await;
+    Ok(recorded)
+}
+
+#[tauri::command]
+pub async fn transcribe_recording(
+    app: AppHandle,
+    speech: tauri::State<'_, SpeechManager>,
+    calendar: tauri::State<'_, CalendarManager>,
+    payload: TranscribeRecordingPayload,
+) -> Result<TranscribeAudioResponse, String> {
+    let calendar_event = calendar.event_at(chrono::Utc::now()).await;
+    let result = speech
+        .transcribe_recording(&app, payload, calendar_event)
+        .await;
+    badge::refresh(&app).await;
+    let session = result.map_err(|e| e.to_string())?;
+    super::emit_gpu_fallback(&app, &session);
+    notify_transcription_finished(&app, &session);
+    Ok(TranscribeAudioResponse { session })
+}
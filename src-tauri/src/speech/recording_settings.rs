@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ensure_writable, recorder::max_input_sample_rate, SpeechError, SpeechManager};
+use crate::storage::write_atomic;
+
+const SAMPLE_RATES: &[u32] = &[8_000, 16_000, 22_050, 32_000, 44_100, 48_000];
+const MAX_PRE_ROLL_SECONDS: u32 = 30;
+const MAX_PART_MINUTES: u32 = 24 * 60;
+
+/// 录音文件的容器格式。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingContainer {
+    #[default]
+    Wav,
+    Flac,
+    Opus,
+}
+
+/// 后端录音的设置，开始录音时读取，录音过程中修改不影响正在进行的录音。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RecordingSettings {
+    /// 实时自动增益，提升音量较小的说话人；每秒的增益记录在会话目录的 `gain.json` 中。
+    #[serde(default)]
+    pub auto_gain: bool,
+    /// 会议时外放对方声音的场景，以扬声器的回环声音为参考消除麦克风中的回声，避免转写出重复的内容。
+    #[serde(default)]
+    pub echo_cancellation: bool,
+    /// 录音文件的采样率；whisper 只用 16 kHz，更高的采样率只让回放更清晰，文件也更大。
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u32,
+    /// 16 或 24 位整数，32 为浮点。
+    #[serde(default = "default_bits_per_sample")]
+    pub bits_per_sample: u16,
+    /// 目前只能是 WAV，FLAC 和 Opus 在校验时拒绝。
+    #[serde(default)]
+    pub container: RecordingContainer,
+    /// 录音控件打开期间缓存的秒数，开始录音时写在录音开头；0 表示不预录。
+    #[serde(default)]
+    pub pre_roll_seconds: u32,
+    /// 单个文件的最长分钟数，录满后切换到新的会话继续录，前后文件互相链接；0 表示不切分。
+    #[serde(default)]
+    pub max_part_minutes: u32,
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            auto_gain: false,
+            echo_cancellation: false,
+            sample_rate: default_sample_rate(),
+            bits_per_sample: default_bits_per_sample(),
+            container: RecordingContainer::Wav,
+            pre_roll_seconds: 0,
+            max_part_minutes: 0,
+        }
+    }
+}
+
+fn default_sample_rate() -> u32 {
+    16_000
+}
+
+fn default_bits_per_sample() -> u16 {
+    16
+}
+
+impl RecordingSettings {
+    /// 麦克风的最高采样率低于设置时无法录音；没有麦克风时只检查取值本身。
+    pub(super) fn validate(&self) -> Result<(), SpeechError> {
+        if !SAMPLE_RATES.contains(&self.sample_rate) {
+            return Err(SpeechError::InvalidRecordingSettings(format!(
+                "不支持的采样率：{} Hz",
+                self.sample_rate
+            )));
+        }
+        if ![16, 24, 32].contains(&self.bits_per_sample) {
+            return Err(SpeechError::InvalidRecordingSettings(format!(
+                "不支持的位深：{} 位",
+                self.bits_per_sample
+            )));
+        }
+        // 会话的回放、音频检查、说话人分离和转写都直接读取 recording.wav
+        if self.container != RecordingContainer::Wav {
+            return Err(SpeechError::InvalidRecordingSettings(
+                "录音目前只能保存为 WAV，暂不支持 FLAC 和 Opus".into(),
+            ));
+        }
+        if self.pre_roll_seconds > MAX_PRE_ROLL_SECONDS {
+            return Err(SpeechError::InvalidRecordingSettings(format!(
+                "预录时长不能超过 {MAX_PRE_ROLL_SECONDS} 秒"
+            )));
+        }
+        if self.max_part_minutes > MAX_PART_MINUTES {
+            return Err(SpeechError::InvalidRecordingSettings(format!(
+                "单个文件的时长不能超过 {MAX_PART_MINUTES} 分钟"
+            )));
+        }
+        // 回声消除需要回环采集扬声器的声音，cpal 只在 WASAPI 上支持
+        if self.echo_cancellation && !cfg!(target_os = "windows") {
+            return Err(SpeechError::InvalidRecordingSettings(
+                "当前系统不支持采集扬声器的声音，无法开启回声消除".into(),
+            ));
+        }
+        if let Some(max) = max_input_sample_rate().filter(|max| self.sample_rate > *max) {
+            return Err(SpeechError::InvalidRecordingSettings(format!(
+                "麦克风最高只支持 {max} Hz"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl SpeechManager {
+    pub async fn recording_settings(&self) -> RecordingSettings {
+        let guard = self.state.lock().await;
+        guard.recording
+    }
+
+    pub async fn set_recording_settings(
+        &self,
+        settings: RecordingSettings,
+    ) -> Result<RecordingSettings, SpeechError> {
+        ensure_writable()?;
+        settings.validate()?;
+        let mut guard = self.state.lock().await;
+        guard.recording = settings;
+        write_atomic(&self.recording_file, &serde_json::to_vec_pretty(&settings)?)?;
+        Ok(settings)
+    }
+}
+
+#[tauri::command]
+pub async fn get_recording_settings(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<RecordingSettings, String> {
+    Ok(state.recording_settings().await)
+}
+
+#[tauri::command]
+pub async fn set_recording_settings(
+    state: tauri::State<'_, SpeechManager>,
+    settings: RecordingSettings,
+) -> Result<RecordingSettings, String> {
+    state
+        .set_recording_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
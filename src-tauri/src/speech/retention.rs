@@ -0,0 +1,385 @@
+use std::{fs, path::Path, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use uuid::Uuid;
+
+use super::{ensure_writable, lock_cache, SpeechError, SpeechManager, SpeechSession};
+use crate::datetime::{now_utc, to_utc_string};
+use crate::links::{EntityKind, EntityRef, LinkStore};
+use crate::search::SearchEntityKind;
+use crate::storage::{is_read_only, write_atomic};
+
+const RETENTION_INTERVAL: StdDuration = StdDuration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RetentionScope {
+    All,
+    Tag { tag: String },
+    Untagged,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    DeleteAudio,
+    DeleteSession,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub id: String,
+    pub name: String,
+    pub scope: RetentionScope,
+    pub older_than_days: u32,
+    pub action: RetentionAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveRetentionPolicyPayload {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub scope: RetentionScope,
+    pub older_than_days: u32,
+    pub action: RetentionAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionCandidate {
+    pub policy_id: String,
+    pub policy_name: String,
+    pub session_id: String,
+    pub session_title: String,
+    pub created_at: String,
+    pub action: RetentionAction,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub evaluated_at: String,
+    pub candidates: Vec<RetentionCandidate>,
+    pub freed_bytes: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RetentionScope {
+    fn matches(&self, session: &SpeechSession) -> bool {
+        match self {
+            RetentionScope::All => true,
+            RetentionScope::Tag { tag } => session.tags.iter().any(|t| t == tag),
+            RetentionScope::Untagged => session.tags.is_empty(),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    fn applies_to(&self, session: &SpeechSession, now: DateTime<Local>) -> bool {
+        if !self.enabled || !self.scope.matches(session) {
+            return false;
+        }
+        if self.action == RetentionAction::DeleteAudio && session.audio_removed_at.is_some() {
+            return false;
+        }
+        let Ok(created_at) = DateTime::parse_from_rfc3339(&session.created_at) else {
+            return false;
+        };
+        now.signed_duration_since(created_at) >= Duration::days(i64::from(self.older_than_days))
+    }
+}
+
+impl SpeechManager {
+    /// 启动后台任务，定期按保留策略清理过期的录音与转写记录。
+    pub fn spawn_retention_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            loop {
+                if is_read_only() {
+                    break;
+                }
+                let speech = app.state::<SpeechManager>();
+                let links = app.state::<LinkStore>();
+                if let Err(err) = run_retention(&speech, &links, false).await {
+                    log::warn!("failed to apply retention policies: {err}");
+                }
+                tokio::time::sleep(RETENTION_INTERVAL).await;
+            }
+        });
+    }
+
+    pub async fn list_retention_policies(&self) -> Vec<RetentionPolicy> {
+        let guard = self.state.lock().await;
+        guard.retention_policies.clone()
+    }
+
+    /// 合并导入的保留策略，已存在的 id 保持不变，返回新增数量。
+    pub async fn merge_retention_policies(
+        &self,
+        policies: Vec<RetentionPolicy>,
+    ) -> Result<usize, SpeechError> {
+        let mut guard = self.state.lock().await;
+        let before = guard.retention_policies.len();
+        for policy in policies {
+            if !guard.retention_policies.iter().any(|p| p.id == policy.id) {
+                guard.retention_policies.push(policy);
+            }
+        }
+        let added = guard.retention_policies.len() - before;
+        if added > 0 {
+            self.persist_retention_policies(&guard.retention_policies)?;
+        }
+        Ok(added)
+    }
+
+    pub async fn save_retention_policy(
+        &self,
+        payload: SaveRetentionPolicyPayload,
+    ) -> Result<RetentionPolicy, SpeechError> {
+        ensure_writable()?;
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(SpeechError::InvalidRetentionPolicy("名称不能为空".into()));
+        }
+        if payload.older_than_days == 0 {
+            return Err(SpeechError::InvalidRetentionPolicy(
+                "保留天数必须大于 0".into(),
+            ));
+        }
+        if matches!(&payload.scope, RetentionScope::Tag { tag } if tag.trim().is_empty()) {
+            return Err(SpeechError::InvalidRetentionPolicy("标签不能为空".into()));
+        }
+
+        let mut guard = self.state.lock().await;
+        let policy = match payload.id {
+            Some(id) => {
+                let existing = guard
+                    .retention_policies
+                    .iter_mut()
+                    .find(|policy| policy.id == id)
+                    .ok_or_else(|| SpeechError::RetentionPolicyNotFound(id.clone()))?;
+                existing.name = name.to_string();
+                existing.scope = payload.scope;
+                existing.older_than_days = payload.older_than_days;
+                existing.action = payload.action;
+                existing.enabled = payload.enabled;
+                existing.clone()
+            }
+            None => {
+                let policy = RetentionPolicy {
+                    id: Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    scope: payload.scope,
+                    older_than_days: payload.older_than_days,
+                    action: payload.action,
+                    enabled: payload.enabled,
+                    created_at: now_utc(),
+                };
+                guard.retention_policies.push(policy.clone());
+                policy
+            }
+        };
+
+        self.persist_retention_policies(&guard.retention_policies)?;
+        Ok(policy)
+    }
+
+    pub async fn delete_retention_policy(&self, policy_id: &str) -> Result<(), SpeechError> {
+        ensure_writable()?;
+        let mut guard = self.state.lock().await;
+        let before = guard.retention_policies.len();
+        guard
+            .retention_policies
+            .retain(|policy| policy.id != policy_id);
+        if guard.retention_policies.len() != before {
+            self.persist_retention_policies(&guard.retention_policies)?;
+        }
+        Ok(())
+    }
+
+    /// 计算各策略命中的记录；同一记录同时命中删除音频和删除记录时以删除记录为准。
+    /// `dry_run` 为 true 时只返回预览，不做任何修改。
+    pub async fn apply_retention_policies(
+        &self,
+        dry_run: bool,
+    ) -> Result<RetentionReport, SpeechError> {
+        if !dry_run {
+            ensure_writable()?;
+        }
+        let now = Local::now();
+        let mut guard = self.state.lock().await;
+
+        let mut candidates: Vec<RetentionCandidate> = Vec::new();
+        for session in &guard.sessions {
+            let matched = guard
+                .retention_policies
+                .iter()
+                .filter(|policy| policy.applies_to(session, now))
+                .max_by_key(|policy| policy.action == RetentionAction::DeleteSession);
+            let Some(policy) = matched else {
+                continue;
+            };
+            let freed_bytes = match policy.action {
+                RetentionAction::DeleteAudio => {
+                    fs::metadata(self.base_dir.join(&session.audio_path))
+                        .map(|meta| meta.len())
+                        .unwrap_or(0)
+                }
+                RetentionAction::DeleteSession => dir_size(&self.sessions_dir.join(&session.id)),
+            };
+            candidates.push(RetentionCandidate {
+                policy_id: policy.id.clone(),
+                policy_name: policy.name.clone(),
+                session_id: session.id.clone(),
+                session_title: session.title.clone(),
+                created_at: session.created_at.clone(),
+                action: policy.action,
+                freed_bytes,
+            });
+        }
+
+        if !dry_run && !candidates.is_empty() {
+            let removed_at = to_utc_string(&now);
+            for candidate in &candidates {
+                match candidate.action {
+                    RetentionAction::DeleteSession => {
+                        guard
+                            .sessions
+                            .retain(|session| session.id != candidate.session_id);
+                        self.indexer
+                            .remove(SearchEntityKind::Session, &candidate.session_id);
+                        lock_cache(&self.audio_cache).invalidate(&candidate.session_id);
+                        let session_dir = self.sessions_dir.join(&candidate.session_id);
+                        if session_dir.exists() {
+                            if let Err(err) = fs::remove_dir_all(&session_dir) {
+                                log::warn!(
+                                    "failed to remove session {}: {err}",
+                                    candidate.session_id
+                                );
+                            }
+                        }
+                    }
+                    RetentionAction::DeleteAudio => {
+                        let Some(session) = guard
+                            .sessions
+                            .iter_mut()
+                            .find(|session| session.id == candidate.session_id)
+                        else {
+                            continue;
+                        };
+                        let audio_path = self.base_dir.join(&session.audio_path);
+                        if audio_path.exists() {
+                            if let Err(err) = fs::remove_file(&audio_path) {
+                                log::warn!("failed to remove audio of {}: {err}", session.id);
+                                continue;
+                            }
+                        }
+                        lock_cache(&self.audio_cache).invalidate(&session.id);
+                        session.audio_removed_at = Some(removed_at.clone());
+                    }
+                }
+            }
+            self.persist_sessions(&guard.sessions)?;
+        }
+
+        Ok(RetentionReport {
+            dry_run,
+            evaluated_at: to_utc_string(&now),
+            freed_bytes: candidates.iter().map(|c| c.freed_bytes).sum(),
+            candidates,
+        })
+    }
+
+    fn persist_retention_policies(&self, policies: &[RetentionPolicy]) -> Result<(), SpeechError> {
+        let json = serde_json::to_vec_pretty(policies)?;
+        write_atomic(&self.retention_file, &json)?;
+        Ok(())
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+async fn run_retention(
+    speech: &SpeechManager,
+    links: &LinkStore,
+    dry_run: bool,
+) -> Result<RetentionReport, SpeechError> {
+    let report = speech.apply_retention_policies(dry_run).await?;
+    if !dry_run {
+        for candidate in &report.candidates {
+            if candidate.action != RetentionAction::DeleteSession {
+                continue;
+            }
+            let entity = EntityRef {
+                kind: EntityKind::Session,
+                id: candidate.session_id.clone(),
+            };
+            if let Err(err) = links.remove_entity(&entity).await {
+                log::warn!("failed to remove links of {}: {err}", candidate.session_id);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn list_retention_policies(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<RetentionPolicy>, String> {
+    Ok(state.list_retention_policies().await)
+}
+
+#[tauri::command]
+pub async fn save_retention_policy(
+    state: tauri::State<'_, SpeechManager>,
+    payload: SaveRetentionPolicyPayload,
+) -> Result<RetentionPolicy, String> {
+    state
+        .save_retention_policy(payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_retention_policy(
+    state: tauri::State<'_, SpeechManager>,
+    policy_id: String,
+) -> Result<(), String> {
+    state
+        .delete_retention_policy(&policy_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `dry_run` 为 true 时仅预览将被清理的记录。
+#[tauri::command]
+pub async fn run_retention_policies(
+    state: tauri::State<'_, SpeechManager>,
+    links: tauri::State<'_, LinkStore>,
+    dry_run: bool,
+) -> Result<RetentionReport, String> {
+    run_retention(&state, &links, dry_run)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,74 @@
+use std::{fs, path::Path};
+
+use super::{SpeechError, SpeechManager, TranscriptSegment};
+
+const CSV_HEADER: &str = "start,end,duration,speaker,confidence,text";
+/// Excel 没有 BOM 时按系统代码页解析，中文会乱码。
+const UTF8_BOM: &str = "\u{feff}";
+
+fn escape_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 时间以秒为单位保留三位小数；没有说话人或置信度时留空。
+fn build_csv(segments: &[TranscriptSegment]) -> String {
+    let mut csv = String::from(UTF8_BOM);
+    csv.push_str(CSV_HEADER);
+    csv.push_str("\r\n");
+    for segment in segments {
+        let confidence = segment
+            .confidence
+            .map(|value| format!("{value:.3}"))
+            .unwrap_or_default();
+        csv.push_str(&format!(
+            "{:.3},{:.3},{:.3},{},{},{}\r\n",
+            segment.start,
+            segment.end,
+            (segment.end - segment.start).max(0.0),
+            escape_field(segment.speaker.as_deref().unwrap_or_default()),
+            confidence,
+            escape_field(&segment.text),
+        ));
+    }
+    csv
+}
+
+impl SpeechManager {
+    pub async fn export_segments_csv(
+        &self,
+        session_id: &str,
+        path: &Path,
+    ) -> Result<(), SpeechError> {
+        let segments = {
+            let guard = self.state.lock().await;
+            guard
+                .sessions
+                .iter()
+                .find(|session| session.id == session_id)
+                .map(|session| session.segments.clone())
+                .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, build_csv(&segments))?;
+        Ok(())
+    }
+}
+
+/// 把会话片段导出为 CSV（开始、结束、时长、说话人、置信度、文字），便于在 Excel 中做质性编码。
+#[tauri::command]
+pub async fn export_segments_csv(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+    path: String,
+) -> Result<(), String> {
+    state
+        .export_segments_csv(&session_id, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
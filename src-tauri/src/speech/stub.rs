@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    build_transcript, DecodedAudio, ProgressSink, SegmentSink, SpeechError, SpeechLanguage,
+    TranscriptSegment, TranscriptionResult, WhisperTask,
+};
+
+/// 调试构建中设为 1 时转写不加载模型，直接返回固定的文字，供前端开发和端到端测试走通整个流程。
+pub(super) const STUB_ENV: &str = "KK_STUB_TRANSCRIPTION";
+/// 每段固定文字对应的录音长度（秒）。
+const SEGMENT_SECONDS: f32 = 5.0;
+
+const CHINESE_LINES: &[&str] = &[
+    "这是一段模拟的转写文字。",
+    "当前没有加载语音模型。",
+    "每五秒录音生成一段文字。",
+];
+
+const ENGLISH_LINES: &[&str] = &[
+    "This is a simulated transcript.",
+    "No speech model has been loaded.",
+    "One segment is generated for every five seconds of audio.",
+];
+
+pub(super) fn enabled() -> bool {
+    cfg!(debug_assertions) && std::env::var(STUB_ENV).is_ok_and(|value| value == "1")
+}
+
+/// 按录音长度生成片段，同样发送实时片段和进度，翻译时输出英文。
+pub(super) fn transcribe(
+    decoded: &DecodedAudio,
+    task: &WhisperTask,
+    cancel_flag: &AtomicBool,
+    on_segment: Option<SegmentSink>,
+    on_progress: Option<ProgressSink>,
+) -> Result<TranscriptionResult, SpeechError> {
+    let frames = decoded.samples.len() / decoded.channels.max(1);
+    let duration = frames as f32 / decoded.sample_rate.max(1) as f32;
+    let lines = if task.language == SpeechLanguage::CHINESE && !task.translate {
+        CHINESE_LINES
+    } else {
+        ENGLISH_LINES
+    };
+    let total = ((duration / SEGMENT_SECONDS).ceil() as usize).max(1);
+
+    let mut segments = Vec::with_capacity(total);
+    for index in 0..total {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err(SpeechError::TranscriptionCancelled);
+        }
+        if let Some(sink) = &on_progress {
+            sink(0, index, total, 0);
+        }
+        let start = index as f32 * SEGMENT_SECONDS;
+        let segment = TranscriptSegment {
+            start,
+            end: (start + SEGMENT_SECONDS).min(duration).max(start),
+            text: lines[index % lines.len()].to_string(),
+            speaker: None,
+            words: Vec::new(),
+            confidence: None,
+        };
+        if let Some(sink) = &on_segment {
+            sink(0, segment.clone());
+        }
+        segments.push(segment);
+    }
+    if let Some(sink) = &on_progress {
+        sink(0, total, total, 0);
+    }
+
+    Ok(TranscriptionResult {
+        transcript: build_transcript(&segments),
+        segments,
+        audio_warnings: Vec::new(),
+        gpu_fallback: None,
+    })
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashMap, fs};
+
+use serde::Serialize;
+
+use super::{ensure_writable, SpeechError, SpeechManager, SpeechSession, TranscriptSegment};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentIssueKind {
+    /// 时间为负数或不是有效数字，改为 0。
+    Invalid,
+    /// 超出录音长度，截到录音末尾。
+    OutOfRange,
+    /// 结束早于开始，交换两者。
+    Reversed,
+    /// 与同一说话人的上一段重叠，上一段截到本段开始。
+    Overlap,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentIssue {
+    pub index: usize,
+    pub kind: SegmentIssueKind,
+    /// 修复前的时间。
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SegmentValidationReport {
+    /// 录音已删除或无法解码时为空，此时不检查是否超出录音长度。
+    pub audio_seconds: Option<f32>,
+    pub issues: Vec<SegmentIssue>,
+    /// 修复后的会话，没有问题时与原会话相同。
+    pub session: SpeechSession,
+}
+
+fn sanitize(value: f32) -> f32 {
+    if value.is_finite() {
+        value.max(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// 就地修复片段时间，返回发现的问题；片段顺序和文字保持不变。
+fn repair(segments: &mut [TranscriptSegment], audio_seconds: Option<f32>) -> Vec<SegmentIssue> {
+    let limit = audio_seconds.unwrap_or(f32::INFINITY);
+    let mut issues = Vec::new();
+    for (index, segment) in segments.iter_mut().enumerate() {
+        let (start, end) = (segment.start, segment.end);
+        let mut report = |kind| {
+            issues.push(SegmentIssue {
+                index,
+                kind,
+                start,
+                end,
+            })
+        };
+        if sanitize(start) != start || sanitize(end) != end {
+            report(SegmentIssueKind::Invalid);
+            segment.start = sanitize(start);
+            segment.end = sanitize(end);
+        }
+        if segment.start > limit || segment.end > limit {
+            report(SegmentIssueKind::OutOfRange);
+            segment.start = segment.start.min(limit);
+            segment.end = segment.end.min(limit);
+        }
+        if segment.end < segment.start {
+            report(SegmentIssueKind::Reversed);
+            std::mem::swap(&mut segment.start, &mut segment.end);
+        }
+    }
+
+    // 分轨转写时不同说话人的片段本来就可能重叠，只比较同一说话人的相邻片段
+    let mut previous_by_speaker: HashMap<Option<&str>, usize> = HashMap::new();
+    let mut trims = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let speaker = segment.speaker.as_deref();
+        if let Some(&previous) = previous_by_speaker.get(&speaker) {
+            let previous_segment = &segments[previous];
+            if previous_segment.start <= segment.start && previous_segment.end > segment.start {
+                issues.push(SegmentIssue {
+                    index,
+                    kind: SegmentIssueKind::Overlap,
+                    start: segment.start,
+                    end: segment.end,
+                });
+                trims.push((previous, segment.start));
+            }
+        }
+        previous_by_speaker.insert(speaker, index);
+    }
+    for (index, end) in trims {
+        segments[index].end = end;
+    }
+
+    if !issues.is_empty() {
+        for segment in segments.iter_mut() {
+            for word in &mut segment.words {
+                word.start = sanitize(word.start).clamp(segment.start, segment.end);
+                word.end = sanitize(word.end).clamp(word.start, segment.end);
+            }
+        }
+    }
+    issues
+}
+
+impl SpeechManager {
+    pub async fn validate_segments(
+        &self,
+        session_id: &str,
+    ) -> Result<SegmentValidationReport, SpeechError> {
+        let (audio_path, audio_removed) = {
+            let guard = self.state.lock().await;
+            let session = guard
+                .sessions
+                .iter()
+                .find(|session| session.id == session_id)
+                .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?;
+            (
+                self.base_dir.join(&session.audio_path),
+                session.audio_removed_at.is_some(),
+            )
+        };
+        let audio_seconds = if audio_removed {
+            None
+        } else {
+            match self.decoded_audio(session_id, &audio_path).await {
+                Ok(decoded) => Some(
+                    (decoded.samples.len() / decoded.channels.max(1)) as f32
+                        / decoded.sample_rate.max(1) as f32,
+                ),
+                Err(err) => {
+                    log::warn!("failed to read audio length of session {session_id}: {err}");
+                    None
+                }
+            }
+        };
+
+        let mut guard = self.state.lock().await;
+        let session = guard
+            .sessions
+            .iter_mut()
+            .find(|session| session.id == session_id)
+            .ok_or_else(|| SpeechError::SessionNotFound(session_id.to_string()))?;
+        let mut segments = session.segments.clone();
+        let issues = repair(&mut segments, audio_seconds);
+        if !issues.is_empty() {
+            ensure_writable()?;
+            // 只改时间，文字稿不受影响
+            fs::write(
+                self.sessions_dir.join(&session.id).join("segments.json"),
+                serde_json::to_vec_pretty(&segments)?,
+            )?;
+            session.segments = segments;
+        }
+        let session = session.clone();
+        if !issues.is_empty() {
+            self.persist_sessions(&guard.sessions)?;
+        }
+        Ok(SegmentValidationReport {
+            audio_seconds,
+            issues,
+            session,
+        })
+    }
+}
+
+/// 检查并修复片段时间（负数、超出录音长度、首尾颠倒、同一说话人的片段重叠），
+/// 避免导出的字幕因 whisper 偶发的时间戳错误而无法使用。
+#[tauri::command]
+pub async fn validate_segments(
+    state: tauri::State<'_, SpeechManager>,
+    session_id: String,
+) -> Result<SegmentValidationReport, String> {
+    state
+        .validate_segments(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Datelike, Local, Weekday};
+use serde::{Deserialize, Serialize};
+
+use super::{ensure_writable, SpeechError, SpeechLanguage, SpeechManager};
+use crate::{calendar::CalendarEvent, storage::write_atomic};
+
+const DEFAULT_TEMPLATE: &str = "{language}转写 {time}";
+const MAX_TEMPLATE_CHARS: usize = 120;
+const VARIABLES: &[&str] = &["date", "time", "weekday", "language", "event", "project"];
+
+/// 未指定标题时用于生成会话标题的模板，如 `{date} {project} 会议`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TitleTemplate {
+    #[serde(default = "default_template")]
+    pub template: String,
+}
+
+impl Default for TitleTemplate {
+    fn default() -> Self {
+        Self {
+            template: default_template(),
+        }
+    }
+}
+
+fn default_template() -> String {
+    DEFAULT_TEMPLATE.to_string()
+}
+
+/// 渲染模板时可用的变量取值。
+pub(super) struct TitleContext<'a> {
+    pub timestamp: DateTime<Local>,
+    pub language: SpeechLanguage,
+    pub calendar_event: Option<&'a CalendarEvent>,
+    pub project: Option<&'a str>,
+}
+
+impl TitleTemplate {
+    fn validate(&self) -> Result<(), SpeechError> {
+        let template = self.template.trim();
+        if template.is_empty() {
+            return Err(SpeechError::InvalidTitleTemplate("模板不能为空".into()));
+        }
+        if template.chars().count() > MAX_TEMPLATE_CHARS {
+            return Err(SpeechError::InvalidTitleTemplate(format!(
+                "模板不能超过 {MAX_TEMPLATE_CHARS} 个字符"
+            )));
+        }
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Err(SpeechError::InvalidTitleTemplate("花括号未闭合".into()));
+            };
+            let name = &rest[start + 1..start + len];
+            if !VARIABLES.contains(&name) {
+                return Err(SpeechError::InvalidTitleTemplate(format!(
+                    "未知变量 {{{name}}}"
+                )));
+            }
+            rest = &rest[start + len + 1..];
+        }
+        Ok(())
+    }
+
+    fn uses(&self, variable: &str) -> bool {
+        self.template.contains(&format!("{{{variable}}}"))
+    }
+
+    /// 模板不含 `{event}` 时仍优先使用日历事件标题，保持原有行为。
+    pub(super) fn resolve(&self, context: &TitleContext<'_>) -> String {
+        let event = context
+            .calendar_event
+            .map(|event| event.summary.trim())
+            .filter(|summary| !summary.is_empty());
+        if let Some(summary) = event.filter(|_| !self.uses("event")) {
+            return summary.to_string();
+        }
+
+        let rendered = render(&self.template, context, event.unwrap_or_default());
+        if rendered.is_empty() {
+            render(DEFAULT_TEMPLATE, context, "")
+        } else {
+            rendered
+        }
+    }
+}
+
+fn render(template: &str, context: &TitleContext<'_>, event: &str) -> String {
+    let rendered = template
+        .replace("{date}", &context.timestamp.format("%Y-%m-%d").to_string())
+        .replace("{time}", &context.timestamp.format("%H:%M:%S").to_string())
+        .replace("{weekday}", weekday_name(context.timestamp.weekday()))
+        .replace("{language}", context.language.display_name())
+        .replace("{event}", event)
+        .replace("{project}", context.project.unwrap_or_default().trim());
+    // 空变量会留下多余的空格，合并成一个。
+    rendered.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "周一",
+        Weekday::Tue => "周二",
+        Weekday::Wed => "周三",
+        Weekday::Thu => "周四",
+        Weekday::Fri => "周五",
+        Weekday::Sat => "周六",
+        Weekday::Sun => "周日",
+    }
+}
+
+impl SpeechManager {
+    pub async fn title_template(&self) -> TitleTemplate {
+        let guard = self.state.lock().await;
+        guard.title_template.clone()
+    }
+
+    pub async fn set_title_template(
+        &self,
+        template: TitleTemplate,
+    ) -> Result<TitleTemplate, SpeechError> {
+        ensure_writable()?;
+        let template = TitleTemplate {
+            template: template.template.trim().to_string(),
+        };
+        template.validate()?;
+        let mut guard = self.state.lock().await;
+        write_atomic(
+            &self.title_template_file,
+            &serde_json::to_vec_pretty(&template)?,
+        )?;
+        guard.title_template = template.clone();
+        Ok(template)
+    }
+}
+
+#[tauri::command]
+pub async fn get_title_template(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<TitleTemplate, String> {
+    Ok(state.title_template().await)
+}
+
+#[tauri::command]
+pub async fn set_title_template(
+    state: tauri::State<'_, SpeechManager>,
+    template: TitleTemplate,
+) -> Result<TitleTemplate, String> {
+    state
+        .set_title_template(template)
+        .await
+        .map_err(|e| e.to_string())
+}
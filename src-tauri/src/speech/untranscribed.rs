@@ -0,0 +1,266 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+use uuid::Uuid;
+
+use super::{
+    emit_gpu_fallback, ensure_writable, AudioInput, ChannelMode, SpeechError, SpeechLanguage,
+    SpeechManager, SpeechSession, TranscribeAudioResponse, TranscriptionOptions,
+    TranscriptionRequest,
+};
+use crate::badge;
+use crate::calendar::CalendarEvent;
+use crate::datetime::{cmp_instants, now_utc};
+use crate::notifications::notify_transcription_finished;
+
+const UNTRANSCRIBED_DIR: &str = "untranscribed";
+const RECORDING_FILENAME: &str = "recording.wav";
+const META_FILENAME: &str = "meta.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UntranscribedRecording {
+    pub id: String,
+    pub language: SpeechLanguage,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    pub created_at: String,
+    pub reason: String,
+    #[serde(default)]
+    pub calendar_event: Option<CalendarEvent>,
+    #[serde(default)]
+    pub channel_mode: ChannelMode,
+    #[serde(default)]
+    pub vad_enabled: bool,
+    #[serde(default)]
+    pub translate: bool,
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    #[serde(default)]
+    pub options: TranscriptionOptions,
+    #[serde(default)]
+    pub size_bytes: u64,
+}
+
+/// 启动时把崩溃或强退后遗留在 sessions 目录中、尚未写入转写结果的录音移到待转写区。
+pub(super) fn recover_orphaned_recordings(
+    base_dir: &Path,
+    sessions_dir: &Path,
+    sessions: &[SpeechSession],
+) {
+    let Ok(entries) = fs::read_dir(sessions_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let Some(id) = dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !dir.is_dir()
+            || sessions.iter().any(|session| session.id == id)
+            || !dir.join(RECORDING_FILENAME).exists()
+            || dir.join("transcript.txt").exists()
+        {
+            continue;
+        }
+
+        let recording = UntranscribedRecording {
+            id: id.to_string(),
+            language: SpeechLanguage::CHINESE,
+            title: None,
+            project: None,
+            created_at: now_utc(),
+            reason: "应用在转写完成前退出".into(),
+            calendar_event: None,
+            channel_mode: ChannelMode::default(),
+            vad_enabled: false,
+            translate: false,
+            initial_prompt: None,
+            options: TranscriptionOptions::default(),
+            size_bytes: 0,
+        };
+        if let Err(err) = move_to_untranscribed(base_dir, &dir, &recording) {
+            log::warn!("failed to recover orphaned recording {id}: {err}");
+        }
+    }
+}
+
+fn move_to_untranscribed(
+    base_dir: &Path,
+    session_dir: &Path,
+    recording: &UntranscribedRecording,
+) -> Result<(), SpeechError> {
+    let root = base_dir.join(UNTRANSCRIBED_DIR);
+    fs::create_dir_all(&root)?;
+    let target = root.join(&recording.id);
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    fs::rename(session_dir, &target)?;
+    fs::write(
+        target.join(META_FILENAME),
+        serde_json::to_vec_pretty(recording)?,
+    )?;
+    Ok(())
+}
+
+impl SpeechManager {
+    fn untranscribed_path(&self, recording_id: &str) -> Result<PathBuf, SpeechError> {
+        if Uuid::parse_str(recording_id).is_err() {
+            return Err(SpeechError::UntranscribedNotFound(recording_id.to_string()));
+        }
+        let dir = self.base_dir.join(UNTRANSCRIBED_DIR).join(recording_id);
+        if !dir.join(RECORDING_FILENAME).exists() {
+            return Err(SpeechError::UntranscribedNotFound(recording_id.to_string()));
+        }
+        Ok(dir)
+    }
+
+    pub(super) fn keep_untranscribed(
+        &self,
+        session_dir: &Path,
+        recording: &UntranscribedRecording,
+    ) -> Result<(), SpeechError> {
+        move_to_untranscribed(&self.base_dir, session_dir, recording)
+    }
+
+    pub fn list_untranscribed(&self) -> Result<Vec<UntranscribedRecording>, SpeechError> {
+        let root = self.base_dir.join(UNTRANSCRIBED_DIR);
+        if !root.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut recordings = Vec::new();
+        for entry in fs::read_dir(&root)?.flatten() {
+            let dir = entry.path();
+            let Ok(content) = fs::read(dir.join(META_FILENAME)) else {
+                continue;
+            };
+            let Ok(mut recording) = serde_json::from_slice::<UntranscribedRecording>(&content)
+            else {
+                continue;
+            };
+            recording.size_bytes = fs::metadata(dir.join(RECORDING_FILENAME))
+                .map(|meta| meta.len())
+                .unwrap_or(0);
+            recordings.push(recording);
+        }
+        recordings.sort_by(|a, b| cmp_instants(&b.created_at, &a.created_at));
+        Ok(recordings)
+    }
+
+    pub fn notify_untranscribed(&self, app: &AppHandle) {
+        let count = self
+            .list_untranscribed()
+            .map(|list| list.len())
+            .unwrap_or(0);
+        if count == 0 {
+            return;
+        }
+        let _ = app
+            .notification()
+            .builder()
+            .title("有录音尚未转写")
+            .body(format!(
+                "{count} 条录音还没有转写，可在语音转写页面继续处理"
+            ))
+            .show();
+    }
+
+    pub async fn transcribe_untranscribed(
+        &self,
+        recording_id: &str,
+    ) -> Result<SpeechSession, SpeechError> {
+        let dir = self.untranscribed_path(recording_id)?;
+        let mut recording: UntranscribedRecording =
+            serde_json::from_slice(&fs::read(dir.join(META_FILENAME))?)?;
+
+        let result = self
+            .transcribe_bytes(TranscriptionRequest {
+                audio: AudioInput::File(dir.join(RECORDING_FILENAME)),
+                language: recording.language,
+                title: recording.title.clone(),
+                project: recording.project.clone(),
+                calendar_event: recording.calendar_event.clone(),
+                channel_mode: recording.channel_mode,
+                vad_enabled: recording.vad_enabled,
+                translate: recording.translate,
+                initial_prompt: recording.initial_prompt.clone(),
+                options: recording.options,
+                retry_of: Some(recording.id.clone()),
+                app: None,
+            })
+            .await;
+
+        match &result {
+            Ok(_) => {
+                fs::remove_dir_all(&dir)?;
+            }
+            Err(err) => {
+                recording.reason = err.to_string();
+                let _ = fs::write(
+                    dir.join(META_FILENAME),
+                    serde_json::to_vec_pretty(&recording)?,
+                );
+            }
+        }
+        result
+    }
+
+    /// 正在转写和转写失败待重试的录音数量，用于应用图标角标。
+    pub async fn pending_job_count(&self) -> usize {
+        let active = self.state.lock().await.active_transcription.is_some();
+        let waiting = self
+            .list_untranscribed()
+            .map(|list| list.len())
+            .unwrap_or(0);
+        usize::from(active) + waiting
+    }
+
+    pub fn discard_untranscribed(&self, recording_id: &str) -> Result<(), SpeechError> {
+        ensure_writable()?;
+        let dir = self.untranscribed_path(recording_id)?;
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn list_untranscribed_recordings(
+    state: tauri::State<'_, SpeechManager>,
+) -> Result<Vec<UntranscribedRecording>, String> {
+    state.list_untranscribed().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn transcribe_untranscribed_recording(
+    app: AppHandle,
+    state: tauri::State<'_, SpeechManager>,
+    recording_id: String,
+) -> Result<TranscribeAudioResponse, String> {
+    let result = state.transcribe_untranscribed(&recording_id).await;
+    badge::refresh(&app).await;
+    let session = result.map_err(|e| e.to_string())?;
+    emit_gpu_fallback(&app, &session);
+    notify_transcription_finished(&app, &session);
+    Ok(TranscribeAudioResponse { session })
+}
+
+#[tauri::command]
+pub async fn discard_untranscribed_recording(
+    app: AppHandle,
+    state: tauri::State<'_, SpeechManager>,
+    recording_id: String,
+) -> Result<(), String> {
+    state
+        .discard_untranscribed(&recording_id)
+        .map_err(|e| e.to_string())?;
+    badge::refresh(&app).await;
+    Ok(())
+}
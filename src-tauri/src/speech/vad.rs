@@ -0,0 +1,119 @@
+use super::{TranscriptSegment, WordTiming, WHISPER_SAMPLE_RATE};
+
+const FRAME_SECONDS: f32 = 0.03;
+/// 连续静音超过该时长才跳过，句间的自然停顿保持不变。
+const MIN_SILENCE_SECONDS: f32 = 2.0;
+/// 跳过静音时在语音前后各保留的时长，避免切掉轻声的开头和结尾。
+const PADDING_SECONDS: f32 = 0.3;
+/// 以最安静的一成帧估计底噪，高出底噪该值才算语音。
+const NOISE_MARGIN_DB: f32 = 10.0;
+const MIN_THRESHOLD_DBFS: f32 = -50.0;
+const MAX_THRESHOLD_DBFS: f32 = -30.0;
+
+/// 去掉静音后的音频中每一段保留区间的起点，以及它在原始音频中的起点（秒）。
+#[derive(Debug, Clone)]
+pub(super) struct Timeline {
+    spans: Vec<(f32, f32)>,
+}
+
+impl Timeline {
+    /// 把去掉静音后的时间换算回原始音频中的时间。
+    fn original(&self, time: f32) -> f32 {
+        let span = self
+            .spans
+            .iter()
+            .rev()
+            .find(|(trimmed_start, _)| *trimmed_start <= time)
+            .or(self.spans.first());
+        match span {
+            Some((trimmed_start, original_start)) => original_start + (time - trimmed_start),
+            None => time,
+        }
+    }
+
+    pub(super) fn restore(&self, segment: TranscriptSegment) -> TranscriptSegment {
+        TranscriptSegment {
+            start: self.original(segment.start),
+            end: self.original(segment.end),
+            words: segment
+                .words
+                .into_iter()
+                .map(|word| WordTiming {
+                    start: self.original(word.start),
+                    end: self.original(word.end),
+                    ..word
+                })
+                .collect(),
+            ..segment
+        }
+    }
+}
+
+fn frame_dbfs(frame: &[f32]) -> f32 {
+    let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32).sqrt();
+    20.0 * rms.max(1e-10).log10()
+}
+
+/// 跳过较长的静音区间，返回拼接后的音频和时间对应关系；没有可跳过的静音时返回 `None`。
+pub(super) fn trim_silence(audio: &[f32]) -> Option<(Vec<f32>, Timeline)> {
+    let frame_len = (WHISPER_SAMPLE_RATE as f32 * FRAME_SECONDS) as usize;
+    let levels: Vec<f32> = audio.chunks(frame_len).map(frame_dbfs).collect();
+    if levels.is_empty() {
+        return None;
+    }
+    let mut sorted = levels.clone();
+    sorted.sort_by(f32::total_cmp);
+    let noise_floor = sorted[sorted.len() / 10];
+    let threshold = (noise_floor + NOISE_MARGIN_DB).clamp(MIN_THRESHOLD_DBFS, MAX_THRESHOLD_DBFS);
+
+    let min_silence = (MIN_SILENCE_SECONDS / FRAME_SECONDS) as usize;
+    let padding = (PADDING_SECONDS * WHISPER_SAMPLE_RATE as f32) as usize;
+    let mut removed: Vec<(usize, usize)> = Vec::new();
+    let mut run_start = None;
+    for (index, level) in levels.iter().chain([&f32::INFINITY]).enumerate() {
+        match (*level < threshold, run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                run_start = None;
+                if index - start < min_silence {
+                    continue;
+                }
+                let from = if start == 0 {
+                    0
+                } else {
+                    start * frame_len + padding
+                };
+                let to = if index == levels.len() {
+                    audio.len()
+                } else {
+                    index * frame_len - padding
+                };
+                if from < to {
+                    removed.push((from, to));
+                }
+            }
+            _ => {}
+        }
+    }
+    if removed.is_empty() {
+        return None;
+    }
+
+    let mut trimmed = Vec::with_capacity(audio.len());
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    for (from, to) in removed.into_iter().chain([(audio.len(), audio.len())]) {
+        if cursor < from {
+            spans.push((
+                trimmed.len() as f32 / WHISPER_SAMPLE_RATE as f32,
+                cursor as f32 / WHISPER_SAMPLE_RATE as f32,
+            ));
+            trimmed.extend_from_slice(&audio[cursor..from]);
+        }
+        cursor = to;
+    }
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some((trimmed, Timeline { spans }))
+}
@@ -0,0 +1,72 @@
+use std::str;
+
+use whisper_rs::WhisperContext;
+
+use super::WordTiming;
+
+/// 把一段中的 token（id 和以 10 毫秒为单位的起止时间）合并为词：以空格开头的 token 开始新词，
+/// 中文等不以空格分词的文字每个 token 单独成词。一个汉字可能被拆成多个 token，拼成完整字符后才输出。
+pub(super) fn collect_words(
+    ctx: &WhisperContext,
+    tokens: impl IntoIterator<Item = (i32, i64, i64)>,
+) -> Vec<WordTiming> {
+    let eot = ctx.token_eot();
+    let mut words: Vec<WordTiming> = Vec::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut pending_start = 0;
+    for (id, t0, t1) in tokens {
+        // 时间戳、语言标记等特殊 token 的 id 都不小于 EOT
+        if id >= eot {
+            continue;
+        }
+        let Ok(bytes) = ctx.token_to_cstr(id).map(|token| token.to_bytes()) else {
+            continue;
+        };
+        if pending.is_empty() {
+            pending_start = t0;
+        }
+        pending.extend_from_slice(bytes);
+        let piece = match str::from_utf8(&pending) {
+            Ok(piece) => piece.to_string(),
+            Err(err) if err.error_len().is_none() => continue,
+            Err(_) => String::from_utf8_lossy(&pending).into_owned(),
+        };
+        pending.clear();
+
+        let (start, end) = (pending_start as f32 / 100.0, t1 as f32 / 100.0);
+        match words.last_mut() {
+            Some(word) if continues_word(&word.text, &piece) => {
+                word.text.push_str(&piece);
+                word.end = end;
+            }
+            _ => words.push(WordTiming {
+                text: piece,
+                start,
+                end,
+            }),
+        }
+    }
+
+    for word in &mut words {
+        word.text = word.text.trim().to_string();
+    }
+    words.retain(|word| !word.text.is_empty());
+    words
+}
+
+/// 文字 token 概率的平均值，时间戳等特殊 token 不计入；没有文字 token 时为空。
+pub(super) fn mean_probability(
+    ctx: &WhisperContext,
+    tokens: impl IntoIterator<Item = (i32, f32)>,
+) -> Option<f32> {
+    let eot = ctx.token_eot();
+    let (sum, count) = tokens
+        .into_iter()
+        .filter(|(id, _)| *id < eot)
+        .fold((0.0, 0), |(sum, count), (_, p)| (sum + p, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+fn continues_word(word: &str, piece: &str) -> bool {
+    !piece.starts_with(char::is_whitespace) && word.is_ascii() && piece.is_ascii()
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::storage::{is_read_only, probe_data_dir, set_read_only};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AppMode {
+    Normal,
+    ReadOnly,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppStatusResponse {
+    pub mode: AppMode,
+    pub version: String,
+    pub data_dir: Option<String>,
+    pub read_only_reason: Option<String>,
+    /// 只读模式下数据目录已恢复可写，重启应用即可回到正常模式。
+    pub restart_recommended: bool,
+}
+
+pub struct AppStatus {
+    version: String,
+    data_dir: Option<PathBuf>,
+    read_only_reason: Option<String>,
+}
+
+impl AppStatus {
+    /// 检查数据目录是否可用；不可用时切换到只读模式而不是让启动失败。
+    pub fn detect(app: &AppHandle) -> Self {
+        let version = app.package_info().version.to_string();
+        let data_dir = match app.path().app_local_data_dir() {
+            Ok(dir) => dir,
+            Err(err) => {
+                set_read_only(true);
+                return Self {
+                    version,
+                    data_dir: None,
+                    read_only_reason: Some(format!("无法确定数据目录：{err}")),
+                };
+            }
+        };
+
+        let read_only_reason = match probe_data_dir(&data_dir) {
+            Ok(()) => None,
+            Err(err) => {
+                log::error!(
+                    "data directory {} is unavailable, starting read-only: {err}",
+                    data_dir.display()
+                );
+                set_read_only(true);
+                Some(format!("数据目录不可用：{err}"))
+            }
+        };
+
+        Self {
+            version,
+            data_dir: Some(data_dir),
+            read_only_reason,
+        }
+    }
+
+    /// 启动后续步骤（如数据迁移）失败时切换到只读模式，避免以不一致的结构写入数据。
+    pub fn enter_read_only(&mut self, reason: String) {
+        set_read_only(true);
+        self.read_only_reason = Some(reason);
+    }
+
+    pub fn snapshot(&self) -> AppStatusResponse {
+        let read_only = is_read_only();
+        AppStatusResponse {
+            mode: if read_only {
+                AppMode::ReadOnly
+            } else {
+                AppMode::Normal
+            },
+            version: self.version.clone(),
+            data_dir: self
+                .data_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().into_owned()),
+            read_only_reason: self.read_only_reason.clone(),
+            restart_recommended: read_only
+                && self
+                    .data_dir
+                    .as_deref()
+                    .is_some_and(|dir| probe_data_dir(dir).is_ok()),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_app_status(
+    status: tauri::State<'_, AppStatus>,
+) -> Result<AppStatusResponse, String> {
+    Ok(status.snapshot())
+}
@@ -0,0 +1,473 @@
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration as StdDuration,
+};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const LOCK_FILENAME: &str = ".instance-lock.json";
+/// 由操作系统加锁的文件，不能与心跳文件共用：心跳通过改名写入，会换掉加锁的文件。
+pub const EXCLUSIVE_LOCK_FILENAME: &str = ".instance.lock";
+const HEARTBEAT_INTERVAL: StdDuration = StdDuration::from_secs(30);
+const LOCK_STALE_SECONDS: i64 = 90;
+const SYNC_PROVIDERS: &[(&str, &str)] = &[
+    ("dropbox", "Dropbox"),
+    ("onedrive", "OneDrive"),
+    ("google drive", "Google Drive"),
+    ("googledrive", "Google Drive"),
+    ("icloud", "iCloud Drive"),
+    ("mobile documents", "iCloud Drive"),
+    ("nextcloud", "Nextcloud"),
+    ("owncloud", "ownCloud"),
+    ("syncthing", "Syncthing"),
+    ("坚果云", "坚果云"),
+    ("baidunetdisk", "百度网盘"),
+];
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "cifs",
+    "smbfs",
+    "smb3",
+    "nfs",
+    "nfs4",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "fuse.sshfs",
+];
+const CONFLICT_MARKERS: &[&str] = &[
+    "conflicted copy",
+    "conflict copy",
+    ".sync-conflict-",
+    "(case conflict)",
+    "冲突副本",
+    "的冲突",
+];
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+static LOAD_FAILURES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceLock {
+    pub instance_id: String,
+    pub hostname: String,
+    pub pid: u32,
+    pub heartbeat_at: String,
+}
+
+impl InstanceLock {
+    fn is_fresh(&self, now: DateTime<Local>) -> bool {
+        DateTime::parse_from_rfc3339(&self.heartbeat_at)
+            .is_ok_and(|at| now.signed_duration_since(at) < Duration::seconds(LOCK_STALE_SECONDS))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageLocation {
+    Local,
+    SyncedFolder { provider: String },
+    NetworkShare,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageWarningKind {
+    SyncedFolder,
+    NetworkShare,
+    ConcurrentInstance,
+    ConflictCopy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageWarning {
+    pub kind: StorageWarningKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageHealth {
+    pub data_dir: String,
+    pub location: StorageLocation,
+    pub healthy: bool,
+    pub warnings: Vec<StorageWarning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_instance: Option<InstanceLock>,
+}
+
+/// 通过操作系统的文件锁保证同一数据目录只有一个实例写入；心跳文件只用于提示是哪台设备在使用。
+pub struct StorageGuard {
+    data_dir: PathBuf,
+    lock_file: PathBuf,
+    instance_id: String,
+    other_instance: async_runtime::Mutex<Option<InstanceLock>>,
+    /// 持有期间其他实例无法加锁；进程退出（包括崩溃）时由操作系统释放。
+    exclusive_lock: Option<File>,
+    conflict: Option<String>,
+}
+
+impl StorageGuard {
+    /// 应在迁移和各模块写入数据之前调用；没能加锁时 `conflict` 返回原因，调用方应切换到只读模式。
+    pub fn new(app: &AppHandle) -> Result<Self, StorageError> {
+        let data_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&data_dir)?;
+        let lock_file = data_dir.join(LOCK_FILENAME);
+        let instance_id = Uuid::new_v4().to_string();
+        let exclusive_lock = if is_read_only() {
+            None
+        } else {
+            try_lock_exclusive(&data_dir.join(EXCLUSIVE_LOCK_FILENAME))?
+        };
+        let other = if exclusive_lock.is_some() {
+            write_heartbeat(&lock_file, &instance_id)?
+        } else {
+            read_heartbeat(&lock_file, &instance_id, Local::now())
+        };
+        if let Some(other) = &other {
+            log::warn!(
+                "data directory is in use by another instance on {}",
+                other.hostname
+            );
+        }
+        // 本来就处于只读模式时不加锁，也不算冲突
+        let conflict = (exclusive_lock.is_none() && !is_read_only()).then(|| match &other {
+            Some(other) => format!("设备 {} 正在使用同一数据目录", other.hostname),
+            None => "另一个实例正在使用同一数据目录".to_string(),
+        });
+        Ok(Self {
+            data_dir,
+            lock_file,
+            instance_id,
+            other_instance: async_runtime::Mutex::new(other),
+            exclusive_lock,
+            conflict,
+        })
+    }
+
+    /// 数据目录的锁被其他实例持有时返回提示。
+    pub fn conflict(&self) -> Option<String> {
+        self.conflict.clone()
+    }
+
+    /// 启动后台心跳，定期刷新锁文件并记录其他实例的占用情况。
+    pub fn spawn_heartbeat_loop(app: AppHandle) {
+        async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+                app.state::<StorageGuard>().refresh_heartbeat().await;
+            }
+        });
+    }
+
+    /// 刷新锁文件；休眠期间锁文件会过期，唤醒后立即刷新，避免被其他实例当作无人使用。
+    pub async fn refresh_heartbeat(&self) {
+        if is_read_only() || self.exclusive_lock.is_none() {
+            return;
+        }
+        match write_heartbeat(&self.lock_file, &self.instance_id) {
+            Ok(other) => *self.other_instance.lock().await = other,
+            Err(err) => log::warn!("failed to refresh instance lock: {err}"),
+        }
+    }
+
+    pub async fn health(&self) -> StorageHealth {
+        let location = detect_location(&self.data_dir);
+        let mut warnings = Vec::new();
+        match &location {
+            StorageLocation::Local => {}
+            StorageLocation::SyncedFolder { provider } => warnings.push(StorageWarning {
+                kind: StorageWarningKind::SyncedFolder,
+                message: format!(
+                    "数据目录位于 {provider} 同步文件夹中，多台设备同时使用可能产生冲突"
+                ),
+                path: None,
+            }),
+            StorageLocation::NetworkShare => warnings.push(StorageWarning {
+                kind: StorageWarningKind::NetworkShare,
+                message: "数据目录位于网络共享磁盘，断网或多台设备同时写入可能损坏数据".into(),
+                path: None,
+            }),
+        }
+
+        let other_instance = self.other_instance.lock().await.clone();
+        if let Some(other) = &other_instance {
+            warnings.push(StorageWarning {
+                kind: StorageWarningKind::ConcurrentInstance,
+                message: format!(
+                    "设备 {} 正在使用同一数据目录，请关闭其中一端后再继续编辑",
+                    other.hostname
+                ),
+                path: None,
+            });
+        }
+
+        for path in find_conflict_copies(&self.data_dir) {
+            warnings.push(StorageWarning {
+                kind: StorageWarningKind::ConflictCopy,
+                message: "发现同步冲突副本，请确认后合并或删除".into(),
+                path: Some(path.to_string_lossy().into_owned()),
+            });
+        }
+
+        StorageHealth {
+            data_dir: self.data_dir.to_string_lossy().into_owned(),
+            location,
+            healthy: warnings.is_empty(),
+            warnings,
+            other_instance,
+        }
+    }
+}
+
+/// 非阻塞地给锁文件加排他锁；已被其他实例持有时返回 `None`。
+fn try_lock_exclusive(path: &Path) -> Result<Option<File>, StorageError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        // 部分网络文件系统不支持加锁，只能退回到心跳提示
+        Err(TryLockError::Error(err)) if err.kind() == io::ErrorKind::Unsupported => {
+            log::warn!("data directory does not support file locks: {err}");
+            Ok(Some(file))
+        }
+        Err(TryLockError::Error(err)) => Err(err.into()),
+    }
+}
+
+/// 心跳文件中其他仍在活动的实例。
+fn read_heartbeat(
+    lock_file: &Path,
+    instance_id: &str,
+    now: DateTime<Local>,
+) -> Option<InstanceLock> {
+    fs::read(lock_file)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<InstanceLock>(&content).ok())
+        .filter(|lock| lock.instance_id != instance_id && lock.is_fresh(now))
+}
+
+/// 读取现有锁，若被其他仍在活动的实例持有则返回它，然后写入本实例的心跳。
+fn write_heartbeat(
+    lock_file: &Path,
+    instance_id: &str,
+) -> Result<Option<InstanceLock>, StorageError> {
+    let now = Local::now();
+    let other = read_heartbeat(lock_file, instance_id, now);
+
+    let lock = InstanceLock {
+        instance_id: instance_id.to_string(),
+        hostname: hostname(),
+        pid: std::process::id(),
+        heartbeat_at: now.to_rfc3339(),
+    };
+    write_atomic(lock_file, &serde_json::to_vec_pretty(&lock)?)?;
+    Ok(other)
+}
+
+/// 启动时检查数据目录能否创建和写入，失败时调用方应切换到只读模式。
+pub fn probe_data_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".write-probe-{}", Uuid::new_v4()));
+    fs::write(&probe, b"ok")?;
+    fs::remove_file(&probe)
+}
+
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+pub fn ensure_writable() -> io::Result<()> {
+    if is_read_only() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "数据目录不可写，当前处于只读模式",
+        ));
+    }
+    Ok(())
+}
+
+/// 各模块初始化时创建目录；只读模式下跳过，缺失的文件按空数据处理。
+pub fn prepare_dir(dir: &Path) -> io::Result<()> {
+    if is_read_only() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir)
+}
+
+/// 先写临时文件再重命名，避免同步工具或另一台机器读到写了一半的文件。
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    ensure_writable()?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", Uuid::new_v4()));
+    fs::write(&temp_path, contents)?;
+    if let Err(err) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// 启动时读取数据文件，文件不存在时使用 `default`。文件无法读取或已损坏时立即切换到只读模式，
+/// 以 `default` 运行，避免之后的写入用空数据覆盖原文件；原因由 `take_load_failures` 取出。
+pub fn load_json<T: DeserializeOwned>(path: &Path, default: impl FnOnce() -> T) -> T {
+    if !path.exists() {
+        return default();
+    }
+    let loaded = fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| serde_json::from_slice(&content).map_err(|err| err.to_string()));
+    match loaded {
+        Ok(value) => value,
+        Err(err) => {
+            log::error!(
+                "failed to load {}, starting read-only: {err}",
+                path.display()
+            );
+            set_read_only(true);
+            LOAD_FAILURES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(format!("{} 无法读取：{err}", path.display()));
+            default()
+        }
+    }
+}
+
+/// 取出启动以来读取失败的数据文件及原因。
+pub fn take_load_failures() -> Vec<String> {
+    std::mem::take(&mut *LOAD_FAILURES.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn hostname() -> String {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok())
+        .or_else(|| {
+            fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn detect_location(dir: &Path) -> StorageLocation {
+    let lowered = dir.to_string_lossy().to_lowercase();
+    if let Some((_, provider)) = SYNC_PROVIDERS
+        .iter()
+        .find(|(marker, _)| lowered.contains(marker))
+    {
+        return StorageLocation::SyncedFolder {
+            provider: provider.to_string(),
+        };
+    }
+    if lowered.starts_with(r"\\") || lowered.starts_with("//") || is_network_mount(dir) {
+        return StorageLocation::NetworkShare;
+    }
+    StorageLocation::Local
+}
+
+/// 在 Linux 上根据 /proc/mounts 判断所在挂载点的文件系统类型。
+fn is_network_mount(dir: &Path) -> bool {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _device = parts.next()?;
+            let mount_point = parts.next()?;
+            let fs_type = parts.next()?;
+            Some((mount_point, fs_type))
+        })
+        .filter(|(mount_point, _)| dir.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.len())
+        .is_some_and(|(_, fs_type)| NETWORK_FILESYSTEMS.contains(&fs_type))
+}
+
+fn is_conflict_copy(path: &Path) -> bool {
+    let Some(name) = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+    else {
+        return false;
+    };
+    if CONFLICT_MARKERS.iter().any(|marker| name.contains(marker)) {
+        return true;
+    }
+    // OneDrive 会在文件名后追加 "-设备名"，如 sessions-DESKTOP-1234.json。
+    let (Some(stem), Some(extension)) = (
+        path.file_stem().map(|stem| stem.to_string_lossy()),
+        path.extension().map(|ext| ext.to_string_lossy()),
+    ) else {
+        return false;
+    };
+    extension == "json"
+        && stem.rsplit_once('-').is_some_and(|(original, suffix)| {
+            !suffix.is_empty()
+                && suffix.chars().any(|c| c.is_ascii_alphabetic())
+                && path
+                    .with_file_name(format!("{original}.{extension}"))
+                    .exists()
+        })
+}
+
+fn find_conflict_copies(data_dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![data_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if is_conflict_copy(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+#[tauri::command]
+pub async fn get_storage_health(
+    storage: tauri::State<'_, StorageGuard>,
+) -> Result<StorageHealth, String> {
+    Ok(storage.health().await)
+}
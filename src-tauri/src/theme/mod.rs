@@ -0,0 +1,205 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::utils::config::WindowEffectsConfig;
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::window::{Effect, EffectState, EffectsBuilder};
+use tauri::{AppHandle, Emitter, Manager, Theme, Webview, WebviewWindow};
+use thiserror::Error;
+
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::widgets::WidgetStore;
+
+const THEME_FILENAME: &str = "theme.json";
+const THEME_CHANGED_EVENT: &str = "theme://changed";
+const DEFAULT_ACCENT: &str = "#2563eb";
+
+#[derive(Debug, Error)]
+pub enum ThemeError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("强调色格式无效：{0}，应为 #RRGGBB")]
+    InvalidAccent(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppTheme {
+    #[serde(default)]
+    pub mode: ThemeMode,
+    #[serde(default = "default_accent")]
+    pub accent: String,
+    /// 在支持的平台上启用窗口毛玻璃（macOS vibrancy、Windows 11 Mica），前端需使用透明背景。
+    #[serde(default)]
+    pub translucent: bool,
+}
+
+fn default_accent() -> String {
+    DEFAULT_ACCENT.to_string()
+}
+
+impl Default for AppTheme {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::System,
+            accent: default_accent(),
+            translucent: false,
+        }
+    }
+}
+
+impl AppTheme {
+    fn validate(&self) -> Result<(), ThemeError> {
+        let valid = self
+            .accent
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        if valid {
+            Ok(())
+        } else {
+            Err(ThemeError::InvalidAccent(self.accent.clone()))
+        }
+    }
+
+    fn native_theme(&self) -> Option<Theme> {
+        match self.mode {
+            ThemeMode::System => None,
+            ThemeMode::Light => Some(Theme::Light),
+            ThemeMode::Dark => Some(Theme::Dark),
+        }
+    }
+
+    fn window_effects(&self) -> Option<WindowEffectsConfig> {
+        if !self.translucent {
+            return None;
+        }
+        let effect = if cfg!(target_os = "macos") {
+            Effect::UnderWindowBackground
+        } else if cfg!(target_os = "windows") {
+            match self.mode {
+                ThemeMode::System => Effect::Mica,
+                ThemeMode::Light => Effect::MicaLight,
+                ThemeMode::Dark => Effect::MicaDark,
+            }
+        } else {
+            return None;
+        };
+        Some(
+            EffectsBuilder::new()
+                .effect(effect)
+                .state(EffectState::FollowsWindowActiveState)
+                .build(),
+        )
+    }
+}
+
+pub struct ThemeStore {
+    theme_file: PathBuf,
+    theme: Mutex<AppTheme>,
+}
+
+impl ThemeStore {
+    pub fn new(app: &AppHandle) -> Result<Self, ThemeError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let theme_file = base_dir.join(THEME_FILENAME);
+        let theme = load_json(&theme_file, AppTheme::default);
+        Ok(Self {
+            theme_file,
+            theme: Mutex::new(theme),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, AppTheme> {
+        self.theme.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn current(&self) -> AppTheme {
+        self.lock().clone()
+    }
+
+    pub fn set(&self, theme: AppTheme) -> Result<AppTheme, ThemeError> {
+        theme.validate()?;
+        let mut guard = self.lock();
+        write_atomic(&self.theme_file, &serde_json::to_vec_pretty(&theme)?)?;
+        *guard = theme.clone();
+        Ok(theme)
+    }
+}
+
+/// 原生标题栏跟随明暗模式，并按设置开启或关闭毛玻璃（小组件使用各自的材质）；
+/// 不支持的平台只记录日志。
+fn apply_to_window(window: &WebviewWindow, theme: &AppTheme) {
+    if let Err(err) = window.set_theme(theme.native_theme()) {
+        log::debug!("failed to set theme for window {}: {err}", window.label());
+    }
+    let widget = window
+        .try_state::<WidgetStore>()
+        .and_then(|widgets| widgets.appearance(window.label()));
+    let effects = match widget {
+        Some(appearance) => appearance.material.window_effects(theme.mode),
+        None => theme.window_effects(),
+    };
+    if let Err(err) = window.set_effects(effects) {
+        log::debug!("failed to set effects for window {}: {err}", window.label());
+    }
+}
+
+pub fn apply_to_all(app: &AppHandle) {
+    let Some(store) = app.try_state::<ThemeStore>() else {
+        return;
+    };
+    let theme = store.current();
+    for window in app.webview_windows().values() {
+        apply_to_window(window, &theme);
+    }
+}
+
+/// 每个窗口（包括之后打开的小组件）开始加载页面时应用当前主题。
+pub fn on_page_load(webview: &Webview, payload: &PageLoadPayload<'_>) {
+    if !matches!(payload.event(), PageLoadEvent::Started) {
+        return;
+    }
+    let app = webview.app_handle();
+    let (Some(store), Some(window)) = (
+        app.try_state::<ThemeStore>(),
+        app.get_webview_window(webview.label()),
+    ) else {
+        return;
+    };
+    apply_to_window(&window, &store.current());
+}
+
+#[tauri::command]
+pub fn get_app_theme(themes: tauri::State<'_, ThemeStore>) -> Result<AppTheme, String> {
+    Ok(themes.current())
+}
+
+/// 保存主题并通知所有窗口，小组件据此与主窗口保持一致。
+#[tauri::command]
+pub fn set_app_theme(
+    app: AppHandle,
+    themes: tauri::State<'_, ThemeStore>,
+    theme: AppTheme,
+) -> Result<AppTheme, String> {
+    let theme = themes.set(theme).map_err(|e| e.to_string())?;
+    apply_to_all(&app);
+    let _ = app.emit(THEME_CHANGED_EVENT, &theme);
+    Ok(theme)
+}
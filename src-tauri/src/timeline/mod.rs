@@ -0,0 +1,155 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::CalendarManager;
+use crate::speech::SpeechManager;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineItemKind {
+    Session,
+    CalendarEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineItem {
+    pub kind: TimelineItemKind,
+    pub id: String,
+    pub title: String,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TimelineQuery {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub kinds: Option<Vec<String>>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelinePage {
+    pub items: Vec<TimelineItem>,
+    pub total: usize,
+    pub next_offset: Option<usize>,
+}
+
+fn parse_instant(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn summarize(text: &str) -> Option<String> {
+    let summary: String = text.chars().take(120).collect();
+    let summary = summary.trim().replace('\n', " ");
+    if summary.is_empty() {
+        None
+    } else {
+        Some(summary)
+    }
+}
+
+#[tauri::command]
+pub async fn get_timeline(
+    speech: tauri::State<'_, SpeechManager>,
+    calendar: tauri::State<'_, CalendarManager>,
+    query: Option<TimelineQuery>,
+) -> Result<TimelinePage, String> {
+    let query = query.unwrap_or_default();
+    let to = query
+        .to
+        .as_deref()
+        .and_then(parse_instant)
+        .unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .as_deref()
+        .and_then(parse_instant)
+        .unwrap_or(to - Duration::days(7));
+    if from > to {
+        return Err("时间范围无效".into());
+    }
+    let wants = |kind: &str| {
+        query
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.iter().any(|k| k == kind))
+    };
+
+    let mut items: Vec<(DateTime<Utc>, TimelineItem)> = Vec::new();
+
+    if wants("session") {
+        for session in speech.list_sessions().await {
+            let Some(created_at) = parse_instant(&session.created_at) else {
+                continue;
+            };
+            if created_at < from || created_at > to {
+                continue;
+            }
+            items.push((
+                created_at,
+                TimelineItem {
+                    kind: TimelineItemKind::Session,
+                    summary: summarize(&session.transcript),
+                    id: session.id,
+                    title: session.title,
+                    timestamp: session.created_at,
+                    end: None,
+                },
+            ));
+        }
+    }
+
+    if wants("calendar_event") {
+        for event in calendar.events_between(from, to).await {
+            let Some(start) = parse_instant(&event.start) else {
+                continue;
+            };
+            items.push((
+                start,
+                TimelineItem {
+                    kind: TimelineItemKind::CalendarEvent,
+                    id: event.uid,
+                    title: event.summary,
+                    timestamp: event.start,
+                    end: Some(event.end),
+                    summary: event.location,
+                },
+            ));
+        }
+    }
+
+    items.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let total = items.len();
+    let offset = query.offset.unwrap_or(0).min(total);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let end = (offset + limit).min(total);
+    Ok(TimelinePage {
+        items: items
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(_, item)| item)
+            .collect(),
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
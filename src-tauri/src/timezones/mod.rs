@@ -0,0 +1,227 @@
+use std::{io, path::PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use thiserror::Error;
+
+use crate::datetime::{parse_instant, to_utc_string};
+use crate::storage::{load_json, prepare_dir, write_atomic};
+
+const CITIES_FILENAME: &str = "world_cities.json";
+const CITIES_CHANGED_EVENT: &str = "timezones://changed";
+const MAX_CITIES: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum TimezoneError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未知的时区：{0}")]
+    UnknownTimezone(String),
+    #[error("无法解析时间：{0}")]
+    InvalidTime(String),
+    #[error("城市列表无效：{0}")]
+    InvalidCities(String),
+}
+
+/// 世界时钟中的一个城市，`timezone` 为 IANA 名称（如 America/New_York）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldCity {
+    pub name: String,
+    pub timezone: String,
+}
+
+/// 某一时刻在指定城市的本地时间。
+#[derive(Debug, Serialize)]
+pub struct CityTime {
+    pub name: String,
+    pub timezone: String,
+    /// 不带时区的本地时间，YYYY-MM-DDTHH:MM:SS。
+    pub local_time: String,
+    pub utc_offset_minutes: i32,
+    /// 与本机日期相差的天数，跨日时前端显示“+1”“-1”。
+    pub day_offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertedTime {
+    pub utc: String,
+    pub cities: Vec<CityTime>,
+}
+
+fn default_cities() -> Vec<WorldCity> {
+    [
+        ("北京", "Asia/Shanghai"),
+        ("东京", "Asia/Tokyo"),
+        ("伦敦", "Europe/London"),
+        ("纽约", "America/New_York"),
+        ("旧金山", "America/Los_Angeles"),
+    ]
+    .into_iter()
+    .map(|(name, timezone)| WorldCity {
+        name: name.into(),
+        timezone: timezone.into(),
+    })
+    .collect()
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, TimezoneError> {
+    name.trim()
+        .parse()
+        .map_err(|_| TimezoneError::UnknownTimezone(name.to_string()))
+}
+
+/// 把某个时区的墙上时间换算为 UTC；夏令时切换造成的重复时刻取较早的一个。
+pub fn local_to_utc(local: NaiveDateTime, tz: Tz) -> Result<DateTime<Utc>, TimezoneError> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|instant| instant.with_timezone(&Utc))
+        .ok_or_else(|| TimezoneError::InvalidTime(format!("{local} 在 {tz} 不存在（夏令时跳过）")))
+}
+
+/// 接受 RFC 3339 时间戳，或不带时区的 `YYYY-MM-DDTHH:MM[:SS]`（按 `timezone` 解释，缺省为本机时区）。
+pub fn parse_time_in(value: &str, timezone: Option<&str>) -> Result<DateTime<Utc>, TimezoneError> {
+    if let Some(instant) = parse_instant(value) {
+        return Ok(instant);
+    }
+    let local = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+        .ok_or_else(|| TimezoneError::InvalidTime(value.to_string()))?;
+    match timezone {
+        Some(timezone) => local_to_utc(local, parse_timezone(timezone)?),
+        None => Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|instant| instant.with_timezone(&Utc))
+            .ok_or_else(|| TimezoneError::InvalidTime(value.to_string())),
+    }
+}
+
+fn city_time(city: &WorldCity, instant: DateTime<Utc>) -> Result<CityTime, TimezoneError> {
+    let tz = parse_timezone(&city.timezone)?;
+    let local = instant.with_timezone(&tz);
+    let here = instant.with_timezone(&Local).date_naive();
+    Ok(CityTime {
+        name: city.name.clone(),
+        timezone: city.timezone.clone(),
+        local_time: local.naive_local().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        utc_offset_minutes: local.offset().fix().local_minus_utc() / 60,
+        day_offset: (local.date_naive() - here).num_days(),
+    })
+}
+
+pub struct TimezoneStore {
+    cities_file: PathBuf,
+    cities: async_runtime::Mutex<Vec<WorldCity>>,
+}
+
+impl TimezoneStore {
+    pub fn new(app: &AppHandle) -> Result<Self, TimezoneError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let cities_file = base_dir.join(CITIES_FILENAME);
+        let cities = load_json(&cities_file, default_cities);
+        Ok(Self {
+            cities_file,
+            cities: async_runtime::Mutex::new(cities),
+        })
+    }
+
+    pub async fn cities(&self) -> Vec<WorldCity> {
+        let guard = self.cities.lock().await;
+        guard.clone()
+    }
+
+    pub async fn save_cities(
+        &self,
+        cities: Vec<WorldCity>,
+    ) -> Result<Vec<WorldCity>, TimezoneError> {
+        if cities.len() > MAX_CITIES {
+            return Err(TimezoneError::InvalidCities(format!(
+                "最多添加 {MAX_CITIES} 个城市"
+            )));
+        }
+        let mut cleaned = Vec::with_capacity(cities.len());
+        for city in cities {
+            let name = city.name.trim().to_string();
+            if name.is_empty() {
+                return Err(TimezoneError::InvalidCities("城市名称不能为空".into()));
+            }
+            let timezone = parse_timezone(&city.timezone)?.name().to_string();
+            cleaned.push(WorldCity { name, timezone });
+        }
+        let mut guard = self.cities.lock().await;
+        write_atomic(&self.cities_file, &serde_json::to_vec_pretty(&cleaned)?)?;
+        *guard = cleaned.clone();
+        Ok(cleaned)
+    }
+
+    /// 某一时刻（缺省为现在）在各个已配置城市的时间。
+    pub async fn world_clock(
+        &self,
+        instant: DateTime<Utc>,
+    ) -> Result<Vec<CityTime>, TimezoneError> {
+        let guard = self.cities.lock().await;
+        guard.iter().map(|city| city_time(city, instant)).collect()
+    }
+}
+
+/// 世界时钟小组件每分钟调用一次。
+#[tauri::command]
+pub async fn get_world_clock(
+    timezones: tauri::State<'_, TimezoneStore>,
+) -> Result<Vec<CityTime>, String> {
+    timezones
+        .world_clock(Utc::now())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_world_cities(
+    timezones: tauri::State<'_, TimezoneStore>,
+) -> Result<Vec<WorldCity>, String> {
+    Ok(timezones.cities().await)
+}
+
+#[tauri::command]
+pub async fn save_world_cities(
+    app: AppHandle,
+    timezones: tauri::State<'_, TimezoneStore>,
+    cities: Vec<WorldCity>,
+) -> Result<Vec<WorldCity>, String> {
+    let saved = timezones
+        .save_cities(cities)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(CITIES_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// 把 `time`（`from_timezone` 的墙上时间或 RFC 3339 时间戳）换算到各个已配置城市，另附 UTC 时间戳。
+#[tauri::command]
+pub async fn convert_time(
+    timezones: tauri::State<'_, TimezoneStore>,
+    time: String,
+    from_timezone: Option<String>,
+) -> Result<ConvertedTime, String> {
+    let instant = parse_time_in(&time, from_timezone.as_deref()).map_err(|e| e.to_string())?;
+    Ok(ConvertedTime {
+        utc: to_utc_string(&instant),
+        cities: timezones
+            .world_clock(instant)
+            .await
+            .map_err(|e| e.to_string())?,
+    })
+}
+
+#[tauri::command]
+pub async fn list_timezones() -> Result<Vec<&'static str>, String> {
+    Ok(chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name()).collect())
+}
@@ -0,0 +1,312 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle};
+use uuid::Uuid;
+
+use super::{TodoChangeKind, TodoError, TodoStore};
+use crate::datetime::now_utc;
+use crate::speech::{SpeechLanguage, SpeechManager, VoiceMemo};
+use crate::storage::ensure_writable;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TodoAttachmentKind {
+    File,
+    VoiceMemo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoAttachment {
+    pub id: String,
+    pub kind: TodoAttachmentKind,
+    pub file_name: String,
+    /// 相对附件目录的路径，用 `get_todo_attachment_path` 取得完整路径。
+    pub path: String,
+    pub size_bytes: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+    pub created_at: String,
+}
+
+impl TodoStore {
+    /// 待办 id 来自前端，用作目录名前先排除路径分隔符等字符。
+    fn attachment_dir(&self, todo_id: &str) -> Result<PathBuf, TodoError> {
+        let safe = todo_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if todo_id.is_empty() || !safe {
+            return Err(TodoError::InvalidAttachment(todo_id.to_string()));
+        }
+        Ok(self.attachments_dir.join(todo_id))
+    }
+
+    async fn ensure_todo(&self, todo_id: &str) -> Result<(), TodoError> {
+        let guard = self.todos.lock().await;
+        if guard.iter().any(|todo| todo.id == todo_id) {
+            Ok(())
+        } else {
+            Err(TodoError::NotFound(todo_id.to_string()))
+        }
+    }
+
+    /// 登记附件；待办在写入文件期间被删除时清理文件并返回错误。
+    async fn push_attachment(
+        &self,
+        todo_id: &str,
+        attachment: TodoAttachment,
+    ) -> Result<TodoAttachment, TodoError> {
+        let mut guard = self.todos.lock().await;
+        let Some(todo) = guard.iter_mut().find(|todo| todo.id == todo_id) else {
+            let _ = fs::remove_file(self.attachments_dir.join(&attachment.path));
+            return Err(TodoError::NotFound(todo_id.to_string()));
+        };
+        let transcript = attachment.transcript.as_deref().map(str::trim);
+        if let Some(text) = transcript.filter(|text| !text.is_empty()) {
+            todo.description = Some(match todo.description.as_deref().map(str::trim) {
+                Some(existing) if !existing.is_empty() => format!("{existing}\n{text}"),
+                _ => text.to_string(),
+            });
+        }
+        todo.attachments.push(attachment.clone());
+        todo.updated_at = now_utc();
+        self.persist(&guard)?;
+        Ok(attachment)
+    }
+
+    fn write_attachment(
+        &self,
+        todo_id: &str,
+        file_name: &str,
+        contents: &[u8],
+    ) -> Result<(String, String), TodoError> {
+        ensure_writable()?;
+        let dir = self.attachment_dir(todo_id)?;
+        let id = Uuid::new_v4().to_string();
+        fs::create_dir_all(&dir)?;
+        let stored_name = format!("{id}-{file_name}");
+        fs::write(dir.join(&stored_name), contents)?;
+        Ok((id, format!("{todo_id}/{stored_name}")))
+    }
+
+    pub async fn attach_file(
+        &self,
+        todo_id: &str,
+        source: &Path,
+    ) -> Result<TodoAttachment, TodoError> {
+        self.ensure_todo(todo_id).await?;
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| TodoError::InvalidAttachment(source.display().to_string()))?;
+        let contents = {
+            let source = source.to_path_buf();
+            async_runtime::spawn_blocking(move || fs::read(source))
+                .await
+                .map_err(|e| TodoError::Join(e.to_string()))??
+        };
+        let (id, path) = self.write_attachment(todo_id, &file_name, &contents)?;
+        let attachment = TodoAttachment {
+            id,
+            kind: TodoAttachmentKind::File,
+            file_name,
+            path,
+            size_bytes: contents.len() as u64,
+            duration_seconds: None,
+            transcript: None,
+            created_at: now_utc(),
+        };
+        self.push_attachment(todo_id, attachment).await
+    }
+
+    /// 保存语音备忘；`transcribe` 时用语音模型转写，文本写入待办描述。
+    pub async fn attach_voice_memo(
+        &self,
+        speech: &SpeechManager,
+        todo_id: &str,
+        audio_base64: &str,
+        transcribe: Option<SpeechLanguage>,
+    ) -> Result<TodoAttachment, TodoError> {
+        self.ensure_todo(todo_id).await?;
+        let memo = VoiceMemo::from_base64(audio_base64)?;
+        let (id, path) = self.write_attachment(todo_id, "memo.wav", &memo.audio_bytes)?;
+        let size_bytes = memo.audio_bytes.len() as u64;
+        let duration_seconds = memo.duration_seconds;
+        let transcript = match transcribe {
+            Some(language) => match speech.transcribe_memo(memo, language).await {
+                Ok(text) => Some(text),
+                Err(err) => {
+                    log::warn!("failed to transcribe voice memo for todo {todo_id}: {err}");
+                    None
+                }
+            },
+            None => None,
+        };
+        let attachment = TodoAttachment {
+            id,
+            kind: TodoAttachmentKind::VoiceMemo,
+            file_name: "memo.wav".into(),
+            path,
+            size_bytes,
+            duration_seconds: Some(duration_seconds),
+            transcript,
+            created_at: now_utc(),
+        };
+        self.push_attachment(todo_id, attachment).await
+    }
+
+    pub async fn remove_attachment(
+        &self,
+        todo_id: &str,
+        attachment_id: &str,
+    ) -> Result<(), TodoError> {
+        let mut guard = self.todos.lock().await;
+        let todo = guard
+            .iter_mut()
+            .find(|todo| todo.id == todo_id)
+            .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+        let index = todo
+            .attachments
+            .iter()
+            .position(|attachment| attachment.id == attachment_id)
+            .ok_or_else(|| TodoError::AttachmentNotFound(attachment_id.to_string()))?;
+        let removed = todo.attachments.remove(index);
+        todo.updated_at = now_utc();
+        self.persist(&guard)?;
+        let _ = fs::remove_file(self.attachments_dir.join(removed.path));
+        Ok(())
+    }
+
+    /// 删除待办时一并删除它的附件目录。
+    pub(super) fn remove_attachment_dir(&self, todo_id: &str) {
+        let Ok(dir) = self.attachment_dir(todo_id) else {
+            return;
+        };
+        if dir.exists() {
+            if let Err(err) = fs::remove_dir_all(&dir) {
+                log::warn!("failed to remove attachments of todo {todo_id}: {err}");
+            }
+        }
+    }
+
+    /// 删除待办时把附件目录移到 `stash_dir`，撤销时再移回。
+    pub(super) fn stash_attachment_dir(&self, todo_id: &str, stash_dir: &Path) {
+        let Ok(dir) = self.attachment_dir(todo_id) else {
+            return;
+        };
+        if dir.exists() {
+            if let Err(err) = fs::rename(&dir, stash_dir) {
+                log::warn!("failed to stash attachments of todo {todo_id}: {err}");
+            }
+        }
+    }
+
+    pub(super) fn restore_attachment_dir(&self, todo_id: &str, stash_dir: &Path) {
+        let Ok(dir) = self.attachment_dir(todo_id) else {
+            return;
+        };
+        if stash_dir.exists() && !dir.exists() {
+            if let Err(err) = fs::rename(stash_dir, &dir) {
+                log::warn!("failed to restore attachments of todo {todo_id}: {err}");
+            }
+        }
+    }
+
+    pub async fn attachment_path(
+        &self,
+        todo_id: &str,
+        attachment_id: &str,
+    ) -> Result<PathBuf, TodoError> {
+        let guard = self.todos.lock().await;
+        guard
+            .iter()
+            .find(|todo| todo.id == todo_id)
+            .and_then(|todo| {
+                todo.attachments
+                    .iter()
+                    .find(|attachment| attachment.id == attachment_id)
+            })
+            .map(|attachment| self.attachments_dir.join(&attachment.path))
+            .ok_or_else(|| TodoError::AttachmentNotFound(attachment_id.to_string()))
+    }
+}
+
+#[tauri::command]
+pub async fn attach_todo_file(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    todo_id: String,
+    path: String,
+) -> Result<TodoAttachment, String> {
+    let attachment = todos
+        .attach_file(&todo_id, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![todo_id])
+        .await;
+    Ok(attachment)
+}
+
+/// `transcribe` 为 true 时转写备忘录，`language` 默认中文。
+#[tauri::command]
+pub async fn attach_voice_memo(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    speech: tauri::State<'_, SpeechManager>,
+    todo_id: String,
+    audio_base64: String,
+    transcribe: Option<bool>,
+    language: Option<String>,
+) -> Result<TodoAttachment, String> {
+    let language = if transcribe.unwrap_or(false) {
+        let language = language.as_deref().unwrap_or("zh");
+        Some(SpeechLanguage::try_from(language).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    let attachment = todos
+        .attach_voice_memo(&speech, &todo_id, &audio_base64, language)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![todo_id])
+        .await;
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn remove_todo_attachment(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    todo_id: String,
+    attachment_id: String,
+) -> Result<(), String> {
+    todos
+        .remove_attachment(&todo_id, &attachment_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![todo_id])
+        .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_todo_attachment_path(
+    todos: tauri::State<'_, TodoStore>,
+    todo_id: String,
+    attachment_id: String,
+) -> Result<String, String> {
+    todos
+        .attachment_path(&todo_id, &attachment_id)
+        .await
+        .map(|path| path.to_string_lossy().into_owned())
+        .map_err(|e| e.to_string())
+}
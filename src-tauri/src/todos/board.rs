@@ -0,0 +1,234 @@
+use std::{cmp::Ordering, collections::HashSet, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{TodoChangeKind, TodoError, TodoStatus, TodoStore, TodoTask};
+use crate::datetime::now_utc;
+use crate::storage::{load_json, write_atomic};
+
+pub(super) const COLUMNS_FILENAME: &str = "todo_columns.json";
+
+const BUILTIN_STATUSES: [TodoStatus; 4] = [
+    TodoStatus::NotStarted,
+    TodoStatus::InProgress,
+    TodoStatus::Submitted,
+    TodoStatus::Completed,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoColumn {
+    pub id: TodoStatus,
+    pub name: String,
+}
+
+/// 看板的一列及其中待办的顺序。
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoBoardColumn {
+    pub id: TodoStatus,
+    pub name: String,
+    pub todo_ids: Vec<String>,
+}
+
+fn builtin_columns() -> Vec<TodoColumn> {
+    BUILTIN_STATUSES
+        .iter()
+        .zip(["待开始", "进行中", "待确认", "已完成"])
+        .map(|(id, name)| TodoColumn {
+            id: id.clone(),
+            name: name.into(),
+        })
+        .collect()
+}
+
+pub(super) fn load_columns(path: &Path) -> Vec<TodoColumn> {
+    load_json(path, builtin_columns)
+}
+
+/// 与前端 `resolveTaskStatus` 一致：已完成的待办总在完成列，未设置状态的在待开始列。
+pub(super) fn column_of(todo: &TodoTask) -> TodoStatus {
+    if todo.completed {
+        TodoStatus::Completed
+    } else {
+        todo.status.clone().unwrap_or(TodoStatus::NotStarted)
+    }
+}
+
+/// 有顺序的排在前面，没有的（新建或刚改过状态）按列表顺序排在后面。
+fn cmp_position(a: Option<u32>, b: Option<u32>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn column_indices(todos: &[TodoTask], column: &TodoStatus) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..todos.len())
+        .filter(|&index| column_of(&todos[index]) == *column)
+        .collect();
+    indices.sort_by(|&a, &b| cmp_position(todos[a].position, todos[b].position));
+    indices
+}
+
+fn validate_columns(columns: &[TodoColumn]) -> Result<(), TodoError> {
+    let mut seen = HashSet::new();
+    for column in columns {
+        if column.name.trim().is_empty() {
+            return Err(TodoError::InvalidColumn("列名不能为空".into()));
+        }
+        if matches!(&column.id, TodoStatus::Custom(id) if id.trim().is_empty()) {
+            return Err(TodoError::InvalidColumn("列 id 不能为空".into()));
+        }
+        if !seen.insert(&column.id) {
+            return Err(TodoError::InvalidColumn(format!("列 {} 重复", column.name)));
+        }
+    }
+    if let Some(missing) = builtin_columns()
+        .into_iter()
+        .find(|column| !seen.contains(&column.id))
+    {
+        return Err(TodoError::InvalidColumn(format!(
+            "内置列「{}」不能删除",
+            missing.name
+        )));
+    }
+    Ok(())
+}
+
+impl TodoStore {
+    pub async fn board(&self) -> Vec<TodoBoardColumn> {
+        let columns = self.columns.lock().await.clone();
+        let guard = self.todos.lock().await;
+        columns
+            .into_iter()
+            .map(|column| TodoBoardColumn {
+                todo_ids: column_indices(&guard, &column.id)
+                    .into_iter()
+                    .map(|index| guard[index].id.clone())
+                    .collect(),
+                id: column.id,
+                name: column.name,
+            })
+            .collect()
+    }
+
+    /// 保存列的名称和顺序；被删除的自定义列中的待办移回待开始列。返回状态被重置的待办 id。
+    pub async fn save_columns(&self, columns: Vec<TodoColumn>) -> Result<Vec<String>, TodoError> {
+        validate_columns(&columns)?;
+        let mut columns_guard = self.columns.lock().await;
+        let mut guard = self.todos.lock().await;
+        let mut reset = Vec::new();
+        for todo in guard.iter_mut() {
+            if matches!(&todo.status, Some(status) if !columns.iter().any(|c| c.id == *status)) {
+                todo.status = Some(TodoStatus::NotStarted);
+                todo.position = None;
+                todo.updated_at = now_utc();
+                reset.push(todo.id.clone());
+            }
+        }
+        write_atomic(&self.columns_file, &serde_json::to_vec_pretty(&columns)?)?;
+        if !reset.is_empty() {
+            self.persist(&guard)?;
+        }
+        *columns_guard = columns;
+        Ok(reset)
+    }
+
+    /// 把待办移到 `status` 列的第 `index` 位并重排该列；移入或移出完成列时同步完成状态。
+    pub async fn move_todo(
+        &self,
+        todo_id: &str,
+        status: TodoStatus,
+        index: usize,
+    ) -> Result<Vec<String>, TodoError> {
+        if !self.columns.lock().await.iter().any(|c| c.id == status) {
+            return Err(TodoError::InvalidColumn(format!(
+                "未知的列：{}",
+                serde_json::to_string(&status)?
+            )));
+        }
+        let mut guard = self.todos.lock().await;
+        let moving = guard
+            .iter()
+            .position(|todo| todo.id == todo_id)
+            .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+
+        let now = now_utc();
+        let todo = &mut guard[moving];
+        let completed = status == TodoStatus::Completed;
+        if completed && !todo.completed {
+            todo.completed_at = Some(now.clone());
+        } else if !completed {
+            todo.completed_at = None;
+        }
+        todo.completed = completed;
+        todo.status = Some(status.clone());
+        todo.updated_at = now;
+
+        let mut order: Vec<usize> = column_indices(&guard, &status)
+            .into_iter()
+            .filter(|&other| other != moving)
+            .collect();
+        order.insert(index.min(order.len()), moving);
+        let mut changed = Vec::new();
+        for (position, other) in order.into_iter().enumerate() {
+            let todo = &mut guard[other];
+            let position = Some(position as u32);
+            if todo.position != position || other == moving {
+                todo.position = position;
+                changed.push(todo.id.clone());
+            }
+        }
+        self.persist(&guard)?;
+        Ok(changed)
+    }
+}
+
+#[tauri::command]
+pub async fn get_todo_board(
+    todos: tauri::State<'_, TodoStore>,
+) -> Result<Vec<TodoBoardColumn>, String> {
+    Ok(todos.board().await)
+}
+
+/// 设置看板列（增删自定义列、重命名、调整顺序），内置的四列不能删除。
+#[tauri::command]
+pub async fn save_todo_columns(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    columns: Vec<TodoColumn>,
+) -> Result<Vec<TodoBoardColumn>, String> {
+    let reset = todos
+        .save_columns(columns)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !reset.is_empty() {
+        todos
+            .notify_changed(&app, TodoChangeKind::Saved, reset)
+            .await;
+    }
+    Ok(todos.board().await)
+}
+
+/// 拖动到看板某列的指定位置，所有窗口通过 `todo://changed` 拿到同样的顺序。
+#[tauri::command]
+pub async fn move_todo(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    id: String,
+    status: TodoStatus,
+    index: usize,
+) -> Result<Vec<TodoBoardColumn>, String> {
+    let changed = todos
+        .move_todo(&id, status, index)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, changed)
+        .await;
+    Ok(todos.board().await)
+}
@@ -0,0 +1,438 @@
+use std::{ops::Range, time::Duration as StdDuration};
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+use uuid::Uuid;
+
+use super::{TodoChangeKind, TodoError, TodoPriority, TodoStore, TodoTask};
+use crate::datetime::{now_utc, to_utc_string};
+
+/// 在任意应用中选中文字后按下即可创建待办。
+pub const QUICK_CAPTURE_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+const QUICK_CAPTURED_EVENT: &str = "todo://quick-captured";
+/// 发送复制按键后等待剪贴板更新的总时长和轮询间隔。
+const COPY_TIMEOUT: StdDuration = StdDuration::from_millis(600);
+const COPY_POLL_INTERVAL: StdDuration = StdDuration::from_millis(50);
+
+const RELATIVE_DAYS: [(&str, u64); 7] = [
+    ("大后天", 3),
+    ("后天", 2),
+    ("明天", 1),
+    ("明日", 1),
+    ("今天", 0),
+    ("tomorrow", 1),
+    ("today", 0),
+];
+const WEEK_PREFIXES: [&str; 3] = ["星期", "礼拜", "周"];
+const AFTERNOON_PREFIXES: [&str; 4] = ["下午", "晚上", "傍晚", "今晚"];
+const MORNING_PREFIXES: [&str; 4] = ["上午", "早上", "中午", "凌晨"];
+
+/// 从一段文字中解析出的待办字段，`due_date` 为 UTC 时间戳；只写日期时截止到当天 23:59。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedTodo {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    pub priority: TodoPriority,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickCapturedEvent {
+    pub todo: TodoTask,
+}
+
+fn weekday_index(c: char) -> Option<u64> {
+    "一二三四五六日天"
+        .chars()
+        .position(|day| day == c)
+        .map(|index| index.min(6) as u64)
+}
+
+fn leading_number(text: &str) -> Option<(u32, usize)> {
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    (1..=2)
+        .contains(&digits)
+        .then(|| text[..digits].parse().ok().map(|n| (n, digits)))
+        .flatten()
+}
+
+fn trailing_number(text: &str) -> Option<(u32, usize)> {
+    let digits = text.chars().rev().take_while(char::is_ascii_digit).count();
+    (1..=2)
+        .contains(&digits)
+        .then(|| {
+            text[text.len() - digits..]
+                .parse()
+                .ok()
+                .map(|n| (n, digits))
+        })
+        .flatten()
+}
+
+/// 今天/明天/后天、周五/下周一、3天后、today/tomorrow。
+fn find_date(text: &str, today: NaiveDate) -> Option<(Range<usize>, NaiveDate)> {
+    let lower = text.to_ascii_lowercase();
+    for (word, days) in RELATIVE_DAYS {
+        if let Some(start) = lower.find(word) {
+            return Some((start..start + word.len(), today + Days::new(days)));
+        }
+    }
+
+    let from_monday = u64::from(today.weekday().num_days_from_monday());
+    for prefix in WEEK_PREFIXES {
+        for (start, _) in text.match_indices(prefix) {
+            let after = start + prefix.len();
+            let Some(day) = text[after..].chars().next().and_then(weekday_index) else {
+                continue;
+            };
+            let end = after + '一'.len_utf8();
+            let next_week = ["下", "下个"]
+                .iter()
+                .find(|marker| text[..start].ends_with(**marker));
+            return Some(match next_week {
+                Some(marker) => (
+                    start - marker.len()..end,
+                    today - Days::new(from_monday) + Days::new(7 + day),
+                ),
+                None => (start..end, today + Days::new((day + 7 - from_monday) % 7)),
+            });
+        }
+    }
+
+    let (start, _) = text
+        .match_indices("天后")
+        .find(|(start, _)| trailing_number(&text[..*start]).is_some())?;
+    let (days, digits) = trailing_number(&text[..start])?;
+    Some((
+        start - digits..start + "天后".len(),
+        today + Days::new(u64::from(days)),
+    ))
+}
+
+fn apply_period(text: &str, start: usize, hour: u32) -> (usize, u32) {
+    if let Some(prefix) = AFTERNOON_PREFIXES
+        .iter()
+        .find(|prefix| text[..start].ends_with(**prefix))
+    {
+        return (
+            start - prefix.len(),
+            if hour < 12 { hour + 12 } else { hour },
+        );
+    }
+    match MORNING_PREFIXES
+        .iter()
+        .find(|prefix| text[..start].ends_with(**prefix))
+    {
+        Some(prefix) => (start - prefix.len(), hour),
+        None => (start, hour),
+    }
+}
+
+/// 下午3点、9点半、10点15分、15:30。
+fn find_time(text: &str) -> Option<(Range<usize>, NaiveTime)> {
+    for (index, _) in text.match_indices('点') {
+        let Some((hour, digits)) = trailing_number(&text[..index]) else {
+            continue;
+        };
+        let mut end = index + '点'.len_utf8();
+        let rest = &text[end..];
+        let minute = if rest.starts_with('半') {
+            end += '半'.len_utf8();
+            30
+        } else if let Some((minute, digits)) = leading_number(rest) {
+            end += digits;
+            if text[end..].starts_with('分') {
+                end += '分'.len_utf8();
+            }
+            minute
+        } else {
+            0
+        };
+        let (start, hour) = apply_period(text, index - digits, hour);
+        if let Some(time) = NaiveTime::from_hms_opt(hour, minute, 0) {
+            return Some((start..end, time));
+        }
+    }
+
+    for (index, _) in text.match_indices(':') {
+        let Some((hour, digits)) = trailing_number(&text[..index]) else {
+            continue;
+        };
+        let Some((minute, 2)) = leading_number(&text[index + 1..]) else {
+            continue;
+        };
+        let (start, hour) = apply_period(text, index - digits, hour);
+        if let Some(time) = NaiveTime::from_hms_opt(hour, minute, 0) {
+            return Some((start..index + 3, time));
+        }
+    }
+    None
+}
+
+/// 英文单词之间留一个空格，中文直接拼接。
+fn remove_range(text: &mut String, range: Range<usize>) {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    let separator = match (before, after) {
+        (Some(before), Some(after)) if before.is_ascii() && after.is_ascii() => " ",
+        _ => "",
+    };
+    text.replace_range(range, separator);
+}
+
+/// `!` / `!!` / `!!!` 或 `!low` / `!medium` / `!high` 设置优先级，`#标签` 添加标签，其余文字作为标题。
+fn parse_markers(text: &str) -> (String, TodoPriority, Vec<String>) {
+    let mut priority = TodoPriority::None;
+    let mut tags = Vec::new();
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            if !tags.iter().any(|existing| existing == tag) {
+                tags.push(tag.to_string());
+            }
+            continue;
+        }
+        let marker = match word.to_ascii_lowercase().as_str() {
+            "!" | "!low" => Some(TodoPriority::Low),
+            "!!" | "!medium" => Some(TodoPriority::Medium),
+            "!!!" | "!high" => Some(TodoPriority::High),
+            _ => None,
+        };
+        match marker {
+            Some(marker) => priority = marker,
+            None => words.push(word),
+        }
+    }
+    if priority == TodoPriority::None && (text.contains("紧急") || text.contains("尽快")) {
+        priority = TodoPriority::High;
+    }
+    (words.join(" "), priority, tags)
+}
+
+/// 只有时间没有日期时取下一次到达该时刻的那天。
+pub fn parse_todo_text(text: &str, now: DateTime<Local>) -> ParsedTodo {
+    let mut rest = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let today = now.date_naive();
+
+    let time = find_time(&rest).map(|(range, time)| {
+        remove_range(&mut rest, range);
+        time
+    });
+    let date = find_date(&rest, today).map(|(range, date)| {
+        remove_range(&mut rest, range);
+        date
+    });
+    let due = match (date, time) {
+        (None, None) => None,
+        (Some(date), time) => Some(
+            date.and_time(time.unwrap_or(NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default())),
+        ),
+        (None, Some(time)) if today.and_time(time) > now.naive_local() => {
+            Some(today.and_time(time))
+        }
+        (None, Some(time)) => Some((today + Days::new(1)).and_time(time)),
+    };
+
+    let (title, priority, tags) = parse_markers(&rest);
+    ParsedTodo {
+        title: if title.is_empty() {
+            text.trim().to_string()
+        } else {
+            title
+        },
+        due_date: due
+            .and_then(|due| Local.from_local_datetime(&due).earliest())
+            .map(|due| to_utc_string(&due)),
+        priority,
+        tags,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn send_copy_keystroke() -> bool {
+    super::triggers::command_output(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to keystroke \"c\" using command down",
+        ],
+    )
+    .is_some()
+}
+
+#[cfg(target_os = "windows")]
+fn send_copy_keystroke() -> bool {
+    super::triggers::command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "(New-Object -ComObject WScript.Shell).SendKeys('^c')",
+        ],
+    )
+    .is_some()
+}
+
+#[cfg(target_os = "linux")]
+fn send_copy_keystroke() -> bool {
+    super::triggers::command_output("xdotool", &["key", "--clearmodifiers", "ctrl+c"]).is_some()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn send_copy_keystroke() -> bool {
+    false
+}
+
+/// 模拟复制读取当前选中的文字，读取后恢复原来的剪贴板内容；没有选中文字时返回 None。
+async fn read_selection(app: &AppHandle) -> Option<String> {
+    let clipboard = app.clipboard();
+    let previous = clipboard.read_text().ok();
+    let _ = clipboard.write_text(String::new());
+
+    let copied = tauri::async_runtime::spawn_blocking(send_copy_keystroke)
+        .await
+        .unwrap_or(false);
+    let mut selection = None;
+    if copied {
+        let mut waited = StdDuration::ZERO;
+        while waited < COPY_TIMEOUT {
+            tokio::time::sleep(COPY_POLL_INTERVAL).await;
+            waited += COPY_POLL_INTERVAL;
+            if let Some(text) = clipboard
+                .read_text()
+                .ok()
+                .filter(|text| !text.trim().is_empty())
+            {
+                selection = Some(text);
+                break;
+            }
+        }
+    }
+
+    if let Some(previous) = previous {
+        let _ = clipboard.write_text(previous);
+    }
+    selection
+}
+
+impl TodoStore {
+    async fn capture_text(&self, text: &str) -> Result<TodoTask, TodoError> {
+        let parsed = parse_todo_text(text, Local::now());
+        let now = now_utc();
+        let todo = TodoTask {
+            id: Uuid::new_v4().to_string(),
+            title: parsed.title,
+            description: None,
+            notes: None,
+            reflection: None,
+            completed: false,
+            priority: parsed.priority,
+            status: None,
+            position: None,
+            due_date: parsed.due_date,
+            due_date_end: None,
+            reminder: None,
+            tags: parsed.tags,
+            category: None,
+            created_at: now.clone(),
+            updated_at: now,
+            completed_at: None,
+            reminder_sent: None,
+            time_entries: Vec::new(),
+            quadrant: None,
+            attachments: Vec::new(),
+            triggers: Vec::new(),
+            parent_id: None,
+        };
+        let saved = self.save(todo).await?;
+        *self.last_capture.lock().await = Some(saved.id.clone());
+        Ok(saved)
+    }
+
+    /// 全局快捷键的处理：读取选中文字创建待办，弹出通知并通知前端显示可撤销的提示。
+    pub async fn quick_capture(&self, app: &AppHandle) {
+        let notify = |title: &str, body: &str| {
+            if let Err(err) = app.notification().builder().title(title).body(body).show() {
+                log::warn!("failed to show quick capture notification: {err}");
+            }
+        };
+        let Some(text) = read_selection(app).await else {
+            notify("未添加待办", "没有读取到选中的文字");
+            return;
+        };
+        match self.capture_text(&text).await {
+            Ok(todo) => {
+                notify("已添加待办", &todo.title);
+                self.notify_changed(app, TodoChangeKind::Saved, vec![todo.id.clone()])
+                    .await;
+                let _ = app.emit(QUICK_CAPTURED_EVENT, QuickCapturedEvent { todo });
+            }
+            Err(err) => {
+                log::error!("quick capture failed: {err}");
+                notify("未添加待办", &err.to_string());
+            }
+        }
+    }
+
+    /// 只撤销最近一次快速添加的待办，避免误删之后手动创建的待办。
+    pub async fn undo_quick_capture(&self, todo_id: &str) -> Result<(), TodoError> {
+        let mut last = self.last_capture.lock().await;
+        if last.as_deref() != Some(todo_id) {
+            return Err(TodoError::NotFound(todo_id.to_string()));
+        }
+        self.discard(todo_id).await?;
+        *last = None;
+        Ok(())
+    }
+}
+
+/// 注册快速添加待办的全局快捷键，快捷键被其他应用占用时只记录日志。
+#[cfg(desktop)]
+pub fn register_quick_capture(app: &AppHandle) {
+    use tauri::Manager;
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let registered =
+        app.global_shortcut()
+            .on_shortcut(QUICK_CAPTURE_SHORTCUT, |app, _shortcut, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    app.state::<TodoStore>().quick_capture(&app).await;
+                });
+            });
+    if let Err(err) = registered {
+        log::warn!("failed to register quick capture shortcut {QUICK_CAPTURE_SHORTCUT}: {err}");
+    }
+}
+
+/// 按快速添加的规则解析文字，供前端输入框预览截止时间、优先级和标签。
+#[tauri::command]
+pub async fn parse_todo_input(text: String) -> Result<ParsedTodo, String> {
+    Ok(parse_todo_text(&text, Local::now()))
+}
+
+/// 撤销快速添加，前端提示中的“撤销”按钮调用。
+#[tauri::command]
+pub async fn undo_quick_capture(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    id: String,
+) -> Result<(), String> {
+    todos
+        .undo_quick_capture(&id)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Deleted, vec![id])
+        .await;
+    Ok(())
+}
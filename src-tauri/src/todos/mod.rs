@@ -0,0 +1,468 @@
+mod attachments;
+mod board;
+mod capture;
+mod plan;
+mod reminders;
+mod schedule;
+mod stats;
+mod templates;
+mod triggers;
+
+pub use attachments::{
+    attach_todo_file, attach_voice_memo, get_todo_attachment_path, remove_todo_attachment,
+    TodoAttachment,
+};
+pub use board::{get_todo_board, move_todo, save_todo_columns, TodoColumn};
+#[cfg(desktop)]
+pub use capture::register_quick_capture;
+pub use capture::{parse_todo_input, undo_quick_capture};
+pub use plan::plan_today;
+pub use schedule::{reschedule_overdue, schedule_todo_in_timezone, snooze_todo};
+pub use stats::get_todo_stats;
+pub(crate) use stats::streaks;
+pub use templates::{
+    delete_todo_template, instantiate_template, list_todo_templates, save_todo_template,
+    TodoTemplate,
+};
+pub use triggers::{get_current_wifi_ssid, TodoTrigger};
+
+use board::{column_of, load_columns, COLUMNS_FILENAME};
+use templates::{load_templates, TEMPLATES_FILENAME};
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::badge;
+use crate::datetime::now_utc;
+use crate::journal::{JournalOperation, OperationJournal};
+use crate::links::{EntityLink, LinkStore};
+use crate::search::{SearchDocument, SearchEntityKind, SearchIndexHandle};
+use crate::speech::SpeechError;
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::timezones::TimezoneError;
+
+const TODO_CHANGED_EVENT: &str = "todo://changed";
+
+#[derive(Debug, Error)]
+pub enum TodoError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("task join error: {0}")]
+    Join(String),
+    #[error(transparent)]
+    Speech(#[from] SpeechError),
+    #[error("未找到指定的待办：{0}")]
+    NotFound(String),
+    #[error("未找到指定的附件：{0}")]
+    AttachmentNotFound(String),
+    #[error("无法添加附件：{0}")]
+    InvalidAttachment(String),
+    #[error("未找到指定的待办模板：{0}")]
+    TemplateNotFound(String),
+    #[error("待办模板无效：{0}")]
+    InvalidTemplate(String),
+    #[error("看板列无效：{0}")]
+    InvalidColumn(String),
+    #[error(transparent)]
+    Timezone(#[from] TimezoneError),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TodoPriority {
+    High,
+    Medium,
+    Low,
+    #[default]
+    None,
+}
+
+/// 看板列。也接受 backlog / doing / done 的写法，其他值视为用户自定义的列 id。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum TodoStatus {
+    #[serde(alias = "backlog")]
+    NotStarted,
+    #[serde(alias = "doing")]
+    InProgress,
+    Submitted,
+    #[serde(alias = "done")]
+    Completed,
+    #[serde(untagged)]
+    Custom(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TodoQuadrant {
+    UrgentImportant,
+    NotUrgentImportant,
+    UrgentNotImportant,
+    NotUrgentNotImportant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoTimeEntry {
+    pub id: String,
+    pub date: String,
+    pub duration_minutes: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// 与前端 `TodoTask` 字段一致（camelCase），前端可以直接读写。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoTask {
+    #[serde(default)]
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reflection: Option<String>,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TodoStatus>,
+    /// 在看板列中的顺序，只通过 `move_todo` 维护。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date_end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder_sent: Option<bool>,
+    #[serde(default)]
+    pub time_entries: Vec<TodoTimeEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quadrant: Option<TodoQuadrant>,
+    /// 只通过附件命令修改，保存待办时沿用已有的附件列表。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<TodoAttachment>,
+    /// 情境提醒，如连接到指定 Wi-Fi 时提醒。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<TodoTrigger>,
+    /// 由模板生成的子任务指向主待办。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoChangeKind {
+    Saved,
+    Deleted,
+    Replaced,
+}
+
+/// 每次待办变化后广播给所有窗口，携带完整列表，小组件和主窗口无需再轮询存储。
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoChangedEvent {
+    pub kind: TodoChangeKind,
+    pub ids: Vec<String>,
+    pub todos: Vec<TodoTask>,
+}
+
+pub struct TodoStore {
+    todos_file: PathBuf,
+    attachments_dir: PathBuf,
+    templates_file: PathBuf,
+    columns_file: PathBuf,
+    todos: async_runtime::Mutex<Vec<TodoTask>>,
+    templates: async_runtime::Mutex<Vec<TodoTemplate>>,
+    columns: async_runtime::Mutex<Vec<TodoColumn>>,
+    /// 最近一次快速添加的待办，只有它可以撤销。
+    last_capture: async_runtime::Mutex<Option<String>>,
+    indexer: SearchIndexHandle,
+}
+
+impl TodoStore {
+    pub fn new(app: &AppHandle, indexer: SearchIndexHandle) -> Result<Self, TodoError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let todos_file = base_dir.join("todos.json");
+        let attachments_dir = base_dir.join("todo_attachments");
+        let todos = load_json(&todos_file, Vec::new);
+        let templates_file = base_dir.join(TEMPLATES_FILENAME);
+        let templates = load_templates(&templates_file);
+        let columns_file = base_dir.join(COLUMNS_FILENAME);
+        let columns = load_columns(&columns_file);
+        Ok(Self {
+            todos_file,
+            attachments_dir,
+            templates_file,
+            columns_file,
+            todos: async_runtime::Mutex::new(todos),
+            templates: async_runtime::Mutex::new(templates),
+            columns: async_runtime::Mutex::new(columns),
+            last_capture: async_runtime::Mutex::new(None),
+            indexer,
+        })
+    }
+
+    pub async fn has_todo(&self, todo_id: &str) -> bool {
+        let guard = self.todos.lock().await;
+        guard.iter().any(|todo| todo.id == todo_id)
+    }
+
+    pub async fn all(&self) -> Vec<TodoTask> {
+        let guard = self.todos.lock().await;
+        guard.clone()
+    }
+
+    /// 没有 id 的视为新建，插入列表开头；已存在的保留创建时间并整体替换，换了看板列时排到新列末尾。
+    pub async fn save(&self, mut todo: TodoTask) -> Result<TodoTask, TodoError> {
+        let now = now_utc();
+        todo.updated_at = now.clone();
+        let mut guard = self.todos.lock().await;
+        match guard.iter_mut().find(|existing| existing.id == todo.id) {
+            Some(existing) if !todo.id.is_empty() => {
+                todo.created_at = existing.created_at.clone();
+                todo.attachments = existing.attachments.clone();
+                todo.position = if column_of(&todo) == column_of(existing) {
+                    existing.position
+                } else {
+                    None
+                };
+                *existing = todo.clone();
+            }
+            _ => {
+                if todo.id.is_empty() {
+                    todo.id = Uuid::new_v4().to_string();
+                }
+                if todo.created_at.is_empty() {
+                    todo.created_at = now;
+                }
+                todo.attachments.clear();
+                todo.position = None;
+                guard.insert(0, todo.clone());
+            }
+        }
+        self.persist(&guard)?;
+        Ok(todo)
+    }
+
+    /// 删除待办，附件目录移到 `stash_dir` 以便撤销；返回待办原来的位置和内容。
+    pub async fn delete(
+        &self,
+        todo_id: &str,
+        stash_dir: &Path,
+    ) -> Result<(usize, TodoTask), TodoError> {
+        let mut guard = self.todos.lock().await;
+        let index = guard
+            .iter()
+            .position(|todo| todo.id == todo_id)
+            .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+        let todo = guard.remove(index);
+        self.persist(&guard)?;
+        self.stash_attachment_dir(todo_id, stash_dir);
+        Ok((index, todo))
+    }
+
+    /// 直接删除待办和附件，不进入撤销日志。
+    pub(super) async fn discard(&self, todo_id: &str) -> Result<(), TodoError> {
+        let mut guard = self.todos.lock().await;
+        let before = guard.len();
+        guard.retain(|todo| todo.id != todo_id);
+        if guard.len() == before {
+            return Err(TodoError::NotFound(todo_id.to_string()));
+        }
+        self.persist(&guard)?;
+        self.remove_attachment_dir(todo_id);
+        Ok(())
+    }
+
+    /// 撤销删除：待办放回原来的位置并移回附件。
+    pub async fn restore(
+        &self,
+        index: usize,
+        todo: TodoTask,
+        stash_dir: &Path,
+    ) -> Result<(), TodoError> {
+        let mut guard = self.todos.lock().await;
+        if guard.iter().any(|existing| existing.id == todo.id) {
+            return Ok(());
+        }
+        self.restore_attachment_dir(&todo.id, stash_dir);
+        let index = index.min(guard.len());
+        guard.insert(index, todo);
+        self.persist(&guard)
+    }
+
+    /// 用修改前的版本覆盖同 id 的待办，已被删除的放回列表开头；返回恢复的待办 id。
+    pub async fn restore_versions(
+        &self,
+        versions: Vec<TodoTask>,
+    ) -> Result<Vec<String>, TodoError> {
+        let mut guard = self.todos.lock().await;
+        let mut ids = Vec::with_capacity(versions.len());
+        for version in versions {
+            ids.push(version.id.clone());
+            match guard.iter_mut().find(|existing| existing.id == version.id) {
+                Some(existing) => *existing = version,
+                None => guard.insert(0, version),
+            }
+        }
+        self.persist(&guard)?;
+        Ok(ids)
+    }
+
+    /// 合并数据包中的待办，已存在的 id 保持不变，返回新增的 id。
+    /// 附件文件不在数据包中，导入的待办不带附件。
+    pub async fn merge(&self, todos: Vec<TodoTask>) -> Result<Vec<String>, TodoError> {
+        let mut guard = self.todos.lock().await;
+        let mut added = Vec::new();
+        for mut todo in todos {
+            if todo.id.is_empty() || guard.iter().any(|existing| existing.id == todo.id) {
+                continue;
+            }
+            todo.attachments.clear();
+            added.push(todo.id.clone());
+            guard.push(todo);
+        }
+        if !added.is_empty() {
+            self.persist(&guard)?;
+        }
+        Ok(added)
+    }
+
+    /// 整体替换列表，用于从前端本地存储迁移旧数据。
+    pub async fn replace(&self, todos: Vec<TodoTask>) -> Result<(), TodoError> {
+        let mut guard = self.todos.lock().await;
+        *guard = todos;
+        self.persist(&guard)
+    }
+
+    /// 所有修改都经过这里，搜索索引随之更新。
+    fn persist(&self, todos: &[TodoTask]) -> Result<(), TodoError> {
+        write_atomic(&self.todos_file, &serde_json::to_vec_pretty(todos)?)?;
+        self.indexer.sync(
+            SearchEntityKind::Todo,
+            todos.iter().map(todo_search_document).collect(),
+        );
+        Ok(())
+    }
+
+    pub async fn search_documents(&self) -> Vec<SearchDocument> {
+        let guard = self.todos.lock().await;
+        guard.iter().map(todo_search_document).collect()
+    }
+
+    pub(crate) async fn notify_changed(
+        &self,
+        app: &AppHandle,
+        kind: TodoChangeKind,
+        ids: Vec<String>,
+    ) {
+        let event = TodoChangedEvent {
+            kind,
+            ids,
+            todos: self.all().await,
+        };
+        let _ = app.emit(TODO_CHANGED_EVENT, event);
+        self.sync_reminders(app).await;
+        badge::refresh(app).await;
+    }
+}
+
+fn todo_search_document(todo: &TodoTask) -> SearchDocument {
+    let body = [&todo.description, &todo.notes, &todo.reflection]
+        .into_iter()
+        .flatten()
+        .chain(&todo.tags)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n");
+    SearchDocument {
+        kind: SearchEntityKind::Todo,
+        id: todo.id.clone(),
+        title: todo.title.clone(),
+        body,
+        updated_at: todo.updated_at.clone(),
+    }
+}
+
+#[tauri::command]
+pub async fn list_todos(todos: tauri::State<'_, TodoStore>) -> Result<Vec<TodoTask>, String> {
+    Ok(todos.all().await)
+}
+
+#[tauri::command]
+pub async fn save_todo(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    todo: TodoTask,
+) -> Result<TodoTask, String> {
+    let saved = todos.save(todo).await.map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![saved.id.clone()])
+        .await;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_todo(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    links: tauri::State<'_, LinkStore>,
+    journal: tauri::State<'_, OperationJournal>,
+    todo_id: String,
+) -> Result<Vec<EntityLink>, String> {
+    let removed = journal
+        .delete_todo(&todos, &links, &todo_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Deleted, vec![todo_id])
+        .await;
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn replace_todos(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    journal: tauri::State<'_, OperationJournal>,
+    items: Vec<TodoTask>,
+) -> Result<(), String> {
+    let ids = items.iter().map(|todo| todo.id.clone()).collect();
+    let before = todos.all().await;
+    todos.replace(items).await.map_err(|e| e.to_string())?;
+    journal
+        .record(JournalOperation::ReplaceTodos { before })
+        .await;
+    todos
+        .notify_changed(&app, TodoChangeKind::Replaced, ids)
+        .await;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Days, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::schedule::{due_instant, ReschedulePolicy};
+use super::{TodoChangeKind, TodoError, TodoStore, TodoTask};
+use crate::calendar::CalendarManager;
+use crate::datetime::{cmp_instants, to_utc_string};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanItemKind {
+    Todo,
+    CalendarEvent,
+}
+
+/// 日程中的一项：待办取截止时间（有开始时间时取开始时间），日历事件取开始时间。
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanItem {
+    pub kind: PlanItemKind,
+    pub id: String,
+    pub title: String,
+    pub start: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyPlan {
+    /// 本地日期，YYYY-MM-DD。
+    pub date: String,
+    /// 之前到期但未完成的待办；顺延时已改为今天截止。
+    pub carried_over: Vec<TodoTask>,
+    /// 原本就在今天截止的未完成待办。
+    pub due_today: Vec<TodoTask>,
+    /// 今天的待办与日历事件按时间交错排列。
+    pub agenda: Vec<PlanItem>,
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|instant| instant.with_timezone(&Utc))
+}
+
+/// 本地今天的起止时间。
+fn today_bounds(now: DateTime<Local>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let today = now.date_naive();
+    let start = local_midnight(today).unwrap_or_else(|| now.with_timezone(&Utc));
+    let end = local_midnight(today + Days::new(1)).unwrap_or(start + Duration::days(1));
+    (start, end)
+}
+
+fn todo_item(todo: &TodoTask) -> Option<PlanItem> {
+    let due = todo.due_date_end.as_ref().or(todo.due_date.as_ref())?;
+    Some(PlanItem {
+        kind: PlanItemKind::Todo,
+        id: todo.id.clone(),
+        title: todo.title.clone(),
+        start: todo.due_date.clone().unwrap_or_else(|| due.clone()),
+        end: todo.due_date_end.clone(),
+    })
+}
+
+impl TodoStore {
+    /// `carry_over` 为 true 时把今天之前到期、未完成的待办移到今天（保留原来的时刻），返回被移动的待办 id。
+    pub async fn plan_today(
+        &self,
+        carry_over: bool,
+    ) -> Result<(DailyPlan, Vec<String>), TodoError> {
+        let now = Local::now();
+        let (start, end) = today_bounds(now);
+        let moved = if carry_over {
+            self.reschedule_due_before(start, ReschedulePolicy::Today)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let guard = self.todos.lock().await;
+        let mut carried_over = Vec::new();
+        let mut due_today = Vec::new();
+        for todo in guard.iter().filter(|todo| !todo.completed) {
+            if moved.contains(&todo.id) {
+                carried_over.push(todo.clone());
+                continue;
+            }
+            match due_instant(todo) {
+                Some(due) if due < start => carried_over.push(todo.clone()),
+                Some(due) if due < end => due_today.push(todo.clone()),
+                _ => {}
+            }
+        }
+        drop(guard);
+        due_today.sort_by_key(due_instant);
+
+        let agenda = carried_over
+            .iter()
+            .filter(|todo| moved.contains(&todo.id))
+            .chain(&due_today)
+            .filter_map(todo_item)
+            .collect();
+        let plan = DailyPlan {
+            date: now.format("%Y-%m-%d").to_string(),
+            carried_over,
+            due_today,
+            agenda,
+        };
+        Ok((plan, moved))
+    }
+}
+
+/// 生成今天的计划：顺延逾期未完成的待办，列出今天截止的待办，`include_calendar` 时把当天的日历事件排进日程。
+#[tauri::command]
+pub async fn plan_today(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    calendar: tauri::State<'_, CalendarManager>,
+    carry_over: Option<bool>,
+    include_calendar: Option<bool>,
+) -> Result<DailyPlan, String> {
+    let (mut plan, moved) = todos
+        .plan_today(carry_over.unwrap_or(true))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !moved.is_empty() {
+        todos
+            .notify_changed(&app, TodoChangeKind::Saved, moved)
+            .await;
+    }
+
+    if include_calendar.unwrap_or(true) {
+        let (start, end) = today_bounds(Local::now());
+        plan.agenda.extend(
+            calendar
+                .events_between(start, end)
+                .await
+                .into_iter()
+                .map(|event| PlanItem {
+                    kind: PlanItemKind::CalendarEvent,
+                    id: event.uid,
+                    title: event.summary,
+                    // 跨天的事件从今天零点开始排
+                    start: if cmp_instants(&event.start, &to_utc_string(&start)).is_lt() {
+                        to_utc_string(&start)
+                    } else {
+                        event.start
+                    },
+                    end: Some(event.end),
+                }),
+        );
+    }
+    plan.agenda.sort_by(|a, b| cmp_instants(&a.start, &b.start));
+    Ok(plan)
+}
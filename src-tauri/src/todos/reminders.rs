@@ -0,0 +1,101 @@
+use tauri::{AppHandle, Manager};
+
+use super::{TodoChangeKind, TodoError, TodoStatus, TodoStore, TodoTask};
+use crate::datetime::{now_utc, parse_instant};
+use crate::reminders::{ReminderAction, ReminderEngine, ReminderSource, ReminderSpec};
+
+/// 未完成且尚未提醒过的待办，按 `reminder` 时间提醒。
+fn reminder_spec(todo: &TodoTask) -> Option<ReminderSpec> {
+    if todo.completed || todo.reminder_sent == Some(true) {
+        return None;
+    }
+    Some(ReminderSpec {
+        entity_id: todo.id.clone(),
+        title: "待办提醒".into(),
+        body: todo.title.clone(),
+        fire_at: parse_instant(todo.reminder.as_deref()?)?,
+        repeat: None,
+        expires_at: None,
+        actions: vec![ReminderAction::Complete, ReminderAction::Snooze],
+    })
+}
+
+impl TodoStore {
+    /// 把待办的提醒时间同步到提醒引擎，每次待办变化后调用。
+    pub(crate) async fn sync_reminders(&self, app: &AppHandle) {
+        let Some(engine) = app.try_state::<ReminderEngine>() else {
+            return;
+        };
+        let specs = self.all().await.iter().filter_map(reminder_spec).collect();
+        if let Err(err) = engine.sync_source(ReminderSource::Todo, specs).await {
+            log::warn!("failed to sync todo reminders: {err}");
+        }
+    }
+
+    pub(crate) async fn reminder_due(&self, todo_id: &str) -> bool {
+        let guard = self.todos.lock().await;
+        guard
+            .iter()
+            .any(|todo| todo.id == todo_id && !todo.completed)
+    }
+
+    /// 与前端一致地记录 `reminder_sent`，避免两边重复提醒。
+    pub(crate) async fn mark_reminder_sent(&self, app: &AppHandle, todo_id: &str) {
+        let result = {
+            let mut guard = self.todos.lock().await;
+            match guard.iter_mut().find(|todo| todo.id == todo_id) {
+                Some(todo) => {
+                    todo.reminder_sent = Some(true);
+                    self.persist(&guard)
+                }
+                None => return,
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.notify_changed(app, TodoChangeKind::Saved, vec![todo_id.to_string()])
+                    .await
+            }
+            Err(err) => log::warn!("failed to mark reminder sent for todo {todo_id}: {err}"),
+        }
+    }
+
+    pub(crate) async fn complete_from_reminder(
+        &self,
+        app: &AppHandle,
+        todo_id: &str,
+    ) -> Result<(), TodoError> {
+        {
+            let mut guard = self.todos.lock().await;
+            let todo = guard
+                .iter_mut()
+                .find(|todo| todo.id == todo_id)
+                .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+            let now = now_utc();
+            if !todo.completed {
+                todo.completed = true;
+                todo.completed_at = Some(now.clone());
+            }
+            if todo.status.is_some() {
+                todo.status = Some(TodoStatus::Completed);
+            }
+            todo.updated_at = now;
+            self.persist(&guard)?;
+        }
+        self.notify_changed(app, TodoChangeKind::Saved, vec![todo_id.to_string()])
+            .await;
+        Ok(())
+    }
+
+    pub(crate) async fn snooze_from_reminder(
+        &self,
+        app: &AppHandle,
+        todo_id: &str,
+        minutes: u32,
+    ) -> Result<(), TodoError> {
+        self.snooze(todo_id, minutes).await?;
+        self.notify_changed(app, TodoChangeKind::Saved, vec![todo_id.to_string()])
+            .await;
+        Ok(())
+    }
+}
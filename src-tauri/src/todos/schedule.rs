@@ -0,0 +1,201 @@
+use chrono::{DateTime, Days, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use super::{TodoChangeKind, TodoError, TodoStore, TodoTask};
+use crate::datetime::{parse_instant, to_utc_string};
+use crate::journal::{JournalOperation, OperationJournal};
+use crate::timezones::parse_time_in;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReschedulePolicy {
+    Today,
+    Tomorrow,
+}
+
+fn shift(value: &mut Option<String>, delta: Duration) {
+    if let Some(instant) = value.as_deref().and_then(parse_instant) {
+        *value = Some(to_utc_string(&(instant + delta)));
+    }
+}
+
+/// 截止时间、截止结束时间和提醒整体平移，提醒重新设为未发送。
+fn shift_schedule(todo: &mut TodoTask, delta: Duration) {
+    shift(&mut todo.due_date, delta);
+    shift(&mut todo.due_date_end, delta);
+    if todo.reminder.is_some() {
+        shift(&mut todo.reminder, delta);
+        todo.reminder_sent = Some(false);
+    }
+}
+
+pub(super) fn due_instant(todo: &TodoTask) -> Option<DateTime<Utc>> {
+    todo.due_date_end
+        .as_deref()
+        .or(todo.due_date.as_deref())
+        .and_then(parse_instant)
+}
+
+/// 保留原来的时刻移到目标日期；移到今天但该时刻已过时改为今天结束前。
+fn rescheduled_due(
+    due: DateTime<Utc>,
+    policy: ReschedulePolicy,
+    now: DateTime<Local>,
+) -> DateTime<Utc> {
+    let days = match policy {
+        ReschedulePolicy::Today => Days::new(0),
+        ReschedulePolicy::Tomorrow => Days::new(1),
+    };
+    let target_date = now.date_naive() + days;
+    let local_due = due.with_timezone(&Local);
+    let at_time = |time: NaiveTime| {
+        Local
+            .from_local_datetime(&target_date.and_time(time))
+            .earliest()
+            .map(|instant| instant.with_timezone(&Utc))
+    };
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default();
+    match at_time(local_due.time()) {
+        Some(target) if target > now => target,
+        _ => at_time(end_of_day).unwrap_or_else(|| now.with_timezone(&Utc)),
+    }
+}
+
+impl TodoStore {
+    /// 提醒推迟到当前时间之后 `minutes` 分钟；已过截止时间的待办连同截止时间一起顺延。
+    pub async fn snooze(&self, todo_id: &str, minutes: u32) -> Result<TodoTask, TodoError> {
+        let now = Utc::now();
+        let until = now + Duration::minutes(i64::from(minutes));
+        let mut guard = self.todos.lock().await;
+        let todo = guard
+            .iter_mut()
+            .find(|todo| todo.id == todo_id)
+            .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+        if let Some(due) = due_instant(todo).filter(|due| *due < now) {
+            shift_schedule(todo, until - due);
+        }
+        todo.reminder = Some(to_utc_string(&until));
+        todo.reminder_sent = Some(false);
+        todo.updated_at = to_utc_string(&now);
+        let snoozed = todo.clone();
+        self.persist(&guard)?;
+        Ok(snoozed)
+    }
+
+    /// 按 `timezone` 的墙上时间设置截止时间，用于和其他地区约定的时间；已有截止时间时连同结束时间和提醒一起平移。
+    pub async fn schedule_in_timezone(
+        &self,
+        todo_id: &str,
+        time: &str,
+        timezone: Option<&str>,
+    ) -> Result<TodoTask, TodoError> {
+        let due = parse_time_in(time, timezone)?;
+        let mut guard = self.todos.lock().await;
+        let todo = guard
+            .iter_mut()
+            .find(|todo| todo.id == todo_id)
+            .ok_or_else(|| TodoError::NotFound(todo_id.to_string()))?;
+        match todo.due_date.as_deref().and_then(parse_instant) {
+            Some(current) => shift_schedule(todo, due - current),
+            None => todo.due_date = Some(to_utc_string(&due)),
+        }
+        todo.updated_at = to_utc_string(&Utc::now());
+        let scheduled = todo.clone();
+        self.persist(&guard)?;
+        Ok(scheduled)
+    }
+
+    /// 把所有未完成且已逾期的待办移到今天或明天，返回被调整的待办 id。
+    pub async fn reschedule_overdue(
+        &self,
+        policy: ReschedulePolicy,
+    ) -> Result<Vec<String>, TodoError> {
+        self.reschedule_due_before(Utc::now(), policy).await
+    }
+
+    /// 把截止时间早于 `cutoff` 的未完成待办移到今天或明天。
+    pub(super) async fn reschedule_due_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        policy: ReschedulePolicy,
+    ) -> Result<Vec<String>, TodoError> {
+        let now = Local::now();
+        let mut guard = self.todos.lock().await;
+        let mut ids = Vec::new();
+        for todo in guard.iter_mut().filter(|todo| !todo.completed) {
+            let Some(due) = due_instant(todo).filter(|due| *due < cutoff) else {
+                continue;
+            };
+            shift_schedule(todo, rescheduled_due(due, policy, now) - due);
+            todo.updated_at = to_utc_string(&now);
+            ids.push(todo.id.clone());
+        }
+        if !ids.is_empty() {
+            self.persist(&guard)?;
+        }
+        Ok(ids)
+    }
+}
+
+/// `duration` 为推迟的分钟数。
+#[tauri::command]
+pub async fn snooze_todo(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    id: String,
+    duration: u32,
+) -> Result<TodoTask, String> {
+    let snoozed = todos
+        .snooze(&id, duration)
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![id])
+        .await;
+    Ok(snoozed)
+}
+
+/// `time` 为 `timezone`（IANA 名称，缺省为本机时区）的本地时间，如 2024-05-01T09:00。
+#[tauri::command]
+pub async fn schedule_todo_in_timezone(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    id: String,
+    time: String,
+    timezone: Option<String>,
+) -> Result<TodoTask, String> {
+    let scheduled = todos
+        .schedule_in_timezone(&id, &time, timezone.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    todos
+        .notify_changed(&app, TodoChangeKind::Saved, vec![id])
+        .await;
+    Ok(scheduled)
+}
+
+#[tauri::command]
+pub async fn reschedule_overdue(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    journal: tauri::State<'_, OperationJournal>,
+    policy: ReschedulePolicy,
+) -> Result<Vec<String>, String> {
+    let before = todos.all().await;
+    let ids = todos
+        .reschedule_overdue(policy)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !ids.is_empty() {
+        let before = before
+            .into_iter()
+            .filter(|todo| ids.contains(&todo.id))
+            .collect();
+        journal.record(JournalOperation::EditTodos { before }).await;
+        todos
+            .notify_changed(&app, TodoChangeKind::Saved, ids.clone())
+            .await;
+    }
+    Ok(ids)
+}
@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{TodoStore, TodoTask};
+use crate::datetime::parse_instant;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatsRange {
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl TodoStatsRange {
+    fn days(self) -> i64 {
+        match self {
+            TodoStatsRange::Week => 7,
+            TodoStatsRange::Month => 30,
+            TodoStatsRange::Quarter => 90,
+            TodoStatsRange::Year => 365,
+        }
+    }
+}
+
+/// 热力图中的一天（本地时区）。
+#[derive(Debug, Serialize)]
+pub struct TodoHeatmapDay {
+    pub date: String,
+    pub completed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TodoStats {
+    pub from: String,
+    pub to: String,
+    pub created: usize,
+    pub completed: usize,
+    /// 范围内新建的待办中已完成的比例。
+    pub completion_rate: f64,
+    /// 当前未完成且已过截止时间的待办数。
+    pub overdue: usize,
+    /// 截至今天连续有完成记录的天数；今天尚未完成时从昨天算起。
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub heatmap: Vec<TodoHeatmapDay>,
+}
+
+fn local_date(value: Option<&str>) -> Option<NaiveDate> {
+    parse_instant(value?).map(|instant| instant.with_timezone(&Local).date_naive())
+}
+
+fn is_overdue(todo: &TodoTask) -> bool {
+    let due = todo.due_date_end.as_deref().or(todo.due_date.as_deref());
+    !todo.completed
+        && due
+            .and_then(parse_instant)
+            .is_some_and(|due| due < Utc::now())
+}
+
+/// `days` 为有记录的周期起始日，`step` 为周期长度（按天或按周）；当前周期尚无记录时从上一个周期算起。
+pub(crate) fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate, step: Duration) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(prev) if day - prev == step => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - step
+    };
+    while days.contains(&day) {
+        current += 1;
+        day -= step;
+    }
+    (current, longest)
+}
+
+fn compute_stats(todos: &[TodoTask], range: TodoStatsRange) -> TodoStats {
+    let today = Local::now().date_naive();
+    let from = today - Duration::days(range.days() - 1);
+    let in_range = |date: NaiveDate| date >= from && date <= today;
+
+    let completion_days: Vec<NaiveDate> = todos
+        .iter()
+        .filter(|todo| todo.completed)
+        .filter_map(|todo| local_date(todo.completed_at.as_deref()))
+        .collect();
+    let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for &day in completion_days.iter().filter(|day| in_range(**day)) {
+        *per_day.entry(day).or_default() += 1;
+    }
+
+    let created: Vec<&TodoTask> = todos
+        .iter()
+        .filter(|todo| local_date(Some(todo.created_at.as_str())).is_some_and(in_range))
+        .collect();
+    let created_completed = created.iter().filter(|todo| todo.completed).count();
+    let (current_streak, longest_streak) = streaks(
+        &completion_days.iter().copied().collect(),
+        today,
+        Duration::days(1),
+    );
+
+    TodoStats {
+        from: from.format("%Y-%m-%d").to_string(),
+        to: today.format("%Y-%m-%d").to_string(),
+        created: created.len(),
+        completed: per_day.values().sum(),
+        completion_rate: if created.is_empty() {
+            0.0
+        } else {
+            created_completed as f64 / created.len() as f64
+        },
+        overdue: todos.iter().filter(|todo| is_overdue(todo)).count(),
+        current_streak,
+        longest_streak,
+        heatmap: from
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| TodoHeatmapDay {
+                date: day.format("%Y-%m-%d").to_string(),
+                completed: per_day.get(&day).copied().unwrap_or(0),
+            })
+            .collect(),
+    }
+}
+
+impl TodoStore {
+    /// 已过截止时间仍未完成的待办数量，用于应用图标角标。
+    pub(crate) async fn overdue_count(&self) -> usize {
+        let guard = self.todos.lock().await;
+        guard.iter().filter(|todo| is_overdue(todo)).count()
+    }
+}
+
+/// 统计最近一周 / 月 / 季度 / 年的完成率、连续完成天数和逾期数，并给出每日完成数供热力图使用。
+#[tauri::command]
+pub async fn get_todo_stats(
+    todos: tauri::State<'_, TodoStore>,
+    range: TodoStatsRange,
+) -> Result<TodoStats, String> {
+    Ok(compute_stats(&todos.all().await, range))
+}
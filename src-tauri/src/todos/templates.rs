@@ -0,0 +1,272 @@
+use std::path::Path;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::{TodoChangeKind, TodoError, TodoPriority, TodoStore, TodoTask};
+use crate::datetime::{now_utc, parse_instant, to_utc_string};
+use crate::storage::{load_json, write_atomic};
+
+pub(super) const TEMPLATES_FILENAME: &str = "todo_templates.json";
+
+/// 模板中的一项子任务，截止时间相对实例化时给出的截止时间偏移。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoTemplateItem {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<TodoPriority>,
+    /// 负数表示在截止时间之前。
+    #[serde(default)]
+    pub offset_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoTemplate {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub items: Vec<TodoTemplateItem>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+fn item(title: &str, offset_days: i64) -> TodoTemplateItem {
+    TodoTemplateItem {
+        title: title.into(),
+        description: None,
+        priority: None,
+        offset_days,
+    }
+}
+
+fn builtin_templates() -> Vec<TodoTemplate> {
+    let now = now_utc();
+    vec![TodoTemplate {
+        id: "release".into(),
+        name: "发布流程".into(),
+        description: Some("版本发布前后的检查清单，截止时间为发布日。".into()),
+        priority: TodoPriority::High,
+        tags: vec!["发布".into()],
+        category: None,
+        items: vec![
+            item("冻结功能分支", -7),
+            item("更新版本号和更新日志", -5),
+            item("完成回归测试", -3),
+            item("修复阻塞问题", -2),
+            item("构建并签名安装包", -1),
+            item("准备发布说明", -1),
+            item("发布并通知用户", 0),
+            item("跟踪发布后的反馈", 2),
+        ],
+        created_at: now.clone(),
+        updated_at: now,
+    }]
+}
+
+/// 没有模板文件时提供内置模板，首次修改时才写入磁盘。
+pub(super) fn load_templates(path: &Path) -> Vec<TodoTemplate> {
+    load_json(path, builtin_templates)
+}
+
+fn shift_days(due: &str, days: i64) -> Option<String> {
+    parse_instant(due).map(|instant| to_utc_string(&(instant + Duration::days(days))))
+}
+
+/// 模板本身生成一条主待办，每个子任务生成一条以 `parentId` 关联的待办。
+fn expand(template: &TodoTemplate, due: Option<&str>) -> Vec<TodoTask> {
+    let now = now_utc();
+    let task = |title: &str, description: Option<String>, priority, due_date| TodoTask {
+        id: Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        description,
+        notes: None,
+        reflection: None,
+        completed: false,
+        priority,
+        status: None,
+        position: None,
+        due_date,
+        due_date_end: None,
+        reminder: None,
+        tags: template.tags.clone(),
+        category: template.category.clone(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+        completed_at: None,
+        reminder_sent: None,
+        time_entries: Vec::new(),
+        quadrant: None,
+        attachments: Vec::new(),
+        triggers: Vec::new(),
+        parent_id: None,
+    };
+
+    let parent = task(
+        &template.name,
+        template.description.clone(),
+        template.priority,
+        due.map(str::to_string),
+    );
+    let children = template.items.iter().map(|item| TodoTask {
+        parent_id: Some(parent.id.clone()),
+        ..task(
+            &item.title,
+            item.description.clone(),
+            item.priority.unwrap_or(template.priority),
+            due.and_then(|due| shift_days(due, item.offset_days)),
+        )
+    });
+    std::iter::once(parent.clone()).chain(children).collect()
+}
+
+impl TodoStore {
+    pub async fn templates(&self) -> Vec<TodoTemplate> {
+        let guard = self.templates.lock().await;
+        guard.clone()
+    }
+
+    fn persist_templates(&self, templates: &[TodoTemplate]) -> Result<(), TodoError> {
+        write_atomic(&self.templates_file, &serde_json::to_vec_pretty(templates)?)?;
+        Ok(())
+    }
+
+    /// 没有 id 的视为新建；已存在的保留创建时间并整体替换。
+    pub async fn save_template(
+        &self,
+        mut template: TodoTemplate,
+    ) -> Result<TodoTemplate, TodoError> {
+        template.name = template.name.trim().to_string();
+        if template.name.is_empty() {
+            return Err(TodoError::InvalidTemplate("名称不能为空".into()));
+        }
+        if let Some(item) = template
+            .items
+            .iter()
+            .find(|item| item.title.trim().is_empty())
+        {
+            return Err(TodoError::InvalidTemplate(format!(
+                "子任务标题不能为空（偏移 {} 天）",
+                item.offset_days
+            )));
+        }
+        let now = now_utc();
+        template.updated_at = now.clone();
+        let mut guard = self.templates.lock().await;
+        match guard
+            .iter_mut()
+            .find(|existing| !template.id.is_empty() && existing.id == template.id)
+        {
+            Some(existing) => {
+                template.created_at = existing.created_at.clone();
+                *existing = template.clone();
+            }
+            None => {
+                if template.id.is_empty() {
+                    template.id = Uuid::new_v4().to_string();
+                }
+                template.created_at = now;
+                guard.push(template.clone());
+            }
+        }
+        self.persist_templates(&guard)?;
+        Ok(template)
+    }
+
+    pub async fn delete_template(&self, template_id: &str) -> Result<(), TodoError> {
+        let mut guard = self.templates.lock().await;
+        let before = guard.len();
+        guard.retain(|template| template.id != template_id);
+        if guard.len() == before {
+            return Err(TodoError::TemplateNotFound(template_id.to_string()));
+        }
+        self.persist_templates(&guard)
+    }
+
+    /// 按模板创建待办，返回主待办和子任务（主待办在前）。
+    pub async fn instantiate_template(
+        &self,
+        template_id: &str,
+        due: Option<&str>,
+    ) -> Result<Vec<TodoTask>, TodoError> {
+        if let Some(due) = due.filter(|due| parse_instant(due).is_none()) {
+            return Err(TodoError::InvalidTemplate(format!(
+                "无法识别的截止时间：{due}"
+            )));
+        }
+        let created = {
+            let guard = self.templates.lock().await;
+            let template = guard
+                .iter()
+                .find(|template| template.id == template_id)
+                .ok_or_else(|| TodoError::TemplateNotFound(template_id.to_string()))?;
+            expand(template, due)
+        };
+        let mut guard = self.todos.lock().await;
+        guard.splice(0..0, created.iter().cloned());
+        self.persist(&guard)?;
+        Ok(created)
+    }
+}
+
+#[tauri::command]
+pub async fn list_todo_templates(
+    todos: tauri::State<'_, TodoStore>,
+) -> Result<Vec<TodoTemplate>, String> {
+    Ok(todos.templates().await)
+}
+
+#[tauri::command]
+pub async fn save_todo_template(
+    todos: tauri::State<'_, TodoStore>,
+    template: TodoTemplate,
+) -> Result<TodoTemplate, String> {
+    todos
+        .save_template(template)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_todo_template(
+    todos: tauri::State<'_, TodoStore>,
+    template_id: String,
+) -> Result<(), String> {
+    todos
+        .delete_template(&template_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `due` 为 RFC 3339 时间，子任务按各自的偏移天数计算截止时间；不传时都不设截止时间。
+#[tauri::command]
+pub async fn instantiate_template(
+    app: AppHandle,
+    todos: tauri::State<'_, TodoStore>,
+    id: String,
+    due: Option<String>,
+) -> Result<Vec<TodoTask>, String> {
+    let created = todos
+        .instantiate_template(&id, due.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let ids = created.iter().map(|todo| todo.id.clone()).collect();
+    todos.notify_changed(&app, TodoChangeKind::Saved, ids).await;
+    Ok(created)
+}
@@ -0,0 +1,143 @@
+use std::time::Duration as StdDuration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use super::{TodoStore, TodoTask};
+
+const TRIGGER_POLL_INTERVAL: StdDuration = StdDuration::from_secs(30);
+
+/// 提醒的情境触发条件，满足时由后台弹出系统通知。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TodoTrigger {
+    /// 连接到指定 Wi-Fi 时提醒，如到公司后提醒提交报销。
+    #[serde(rename_all = "camelCase")]
+    WifiConnected { ssid: String },
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+pub(super) fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW，避免每次轮询闪出控制台窗口
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 当前连接的 Wi-Fi 名称；未连接或平台不支持时为空。
+#[cfg(target_os = "macos")]
+fn current_ssid() -> Option<String> {
+    let output = command_output("networksetup", &["-getairportnetwork", "en0"])?;
+    output
+        .trim()
+        .strip_prefix("Current Wi-Fi Network:")
+        .map(|ssid| ssid.trim().to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn current_ssid() -> Option<String> {
+    let output = command_output("netsh", &["wlan", "show", "interfaces"])?;
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "SSID").then(|| value.trim().to_string())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn current_ssid() -> Option<String> {
+    let output = command_output("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])?;
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("yes:"))
+        .map(|ssid| ssid.replace("\\:", ":"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn current_ssid() -> Option<String> {
+    None
+}
+
+async fn detect_ssid() -> Option<String> {
+    async_runtime::spawn_blocking(current_ssid)
+        .await
+        .ok()
+        .flatten()
+        .filter(|ssid| !ssid.is_empty())
+}
+
+fn matches_wifi(todo: &TodoTask, ssid: &str) -> bool {
+    !todo.completed
+        && todo.triggers.iter().any(|trigger| match trigger {
+            TodoTrigger::WifiConnected { ssid: expected } => expected.trim() == ssid,
+        })
+}
+
+impl TodoStore {
+    async fn has_pending_triggers(&self) -> bool {
+        let guard = self.todos.lock().await;
+        guard
+            .iter()
+            .any(|todo| !todo.completed && !todo.triggers.is_empty())
+    }
+
+    /// 启动后台任务轮询当前网络，连接到新的 Wi-Fi 时提醒对应的待办；没有设置触发条件的待办时不检测。
+    pub fn spawn_trigger_watcher(app: AppHandle) {
+        async_runtime::spawn(async move {
+            let mut previous: Option<String> = None;
+            loop {
+                let store = app.state::<TodoStore>();
+                if store.has_pending_triggers().await {
+                    let current = detect_ssid().await;
+                    if current != previous {
+                        if let Some(ssid) = &current {
+                            store.notify_wifi_triggers(&app, ssid).await;
+                        }
+                        previous = current;
+                    }
+                }
+                tokio::time::sleep(TRIGGER_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn notify_wifi_triggers(&self, app: &AppHandle, ssid: &str) {
+        let matched: Vec<TodoTask> = {
+            let guard = self.todos.lock().await;
+            guard
+                .iter()
+                .filter(|todo| matches_wifi(todo, ssid))
+                .cloned()
+                .collect()
+        };
+        for todo in matched {
+            if let Err(err) = app
+                .notification()
+                .builder()
+                .title(format!("已连接到 {ssid}"))
+                .body(&todo.title)
+                .show()
+            {
+                log::warn!(
+                    "failed to show trigger reminder for todo {}: {err}",
+                    todo.id
+                );
+            }
+        }
+    }
+}
+
+/// 当前连接的 Wi-Fi 名称，供设置触发条件时直接选用；无法检测时返回空。
+#[tauri::command]
+pub async fn get_current_wifi_ssid() -> Result<Option<String>, String> {
+    Ok(detect_ssid().await)
+}
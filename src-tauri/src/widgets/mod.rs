@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::utils::config::WindowEffectsConfig;
+use tauri::window::{Effect, EffectState, EffectsBuilder, Monitor};
+use tauri::{async_runtime, AppHandle, Emitter, Manager, PhysicalPosition, Window, WindowEvent};
+use thiserror::Error;
+
+use crate::storage::{load_json, prepare_dir, write_atomic};
+use crate::theme::{self, ThemeMode};
+
+const WIDGETS_FILENAME: &str = "widgets.json";
+const WIDGET_APPEARANCE_EVENT: &str = "widgets://appearance-changed";
+pub const TODO_WIDGET_LABEL: &str = "todo-widget";
+pub const COUNTDOWN_WIDGET_LABEL: &str = "countdown-widget";
+const WIDGET_LABELS: [&str; 2] = [TODO_WIDGET_LABEL, COUNTDOWN_WIDGET_LABEL];
+/// 窗口边缘距工作区边缘不超过该距离（逻辑像素）时吸附。
+const SNAP_DISTANCE: f64 = 24.0;
+/// 拖动时会连续触发移动事件，停下后再吸附，避免和拖动抢位置。
+const SNAP_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Error)]
+pub enum WidgetError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("未知的小组件：{0}")]
+    UnknownWidget(String),
+}
+
+/// 小组件窗口的背景材质；平台不支持时按不透明处理。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetMaterial {
+    #[default]
+    Opaque,
+    /// macOS vibrancy、Windows acrylic。
+    Blur,
+    /// Windows 11 Mica，其他平台同 `Blur`。
+    Mica,
+}
+
+impl WidgetMaterial {
+    pub(crate) fn window_effects(self, mode: ThemeMode) -> Option<WindowEffectsConfig> {
+        if self == Self::Opaque {
+            return None;
+        }
+        let effect = if cfg!(target_os = "macos") {
+            Effect::HudWindow
+        } else if cfg!(target_os = "windows") {
+            match (self, mode) {
+                (Self::Mica, ThemeMode::Light) => Effect::MicaLight,
+                (Self::Mica, ThemeMode::Dark) => Effect::MicaDark,
+                (Self::Mica, _) => Effect::Mica,
+                _ => Effect::Acrylic,
+            }
+        } else {
+            return None;
+        };
+        Some(
+            EffectsBuilder::new()
+                .effect(effect)
+                .state(EffectState::Active)
+                .build(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HorizontalEdge {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalEdge {
+    Top,
+    Bottom,
+}
+
+/// 小组件停靠的边或角（两个方向都有值时为角）以及所在显示器。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WidgetDock {
+    #[serde(default)]
+    pub horizontal: Option<HorizontalEdge>,
+    #[serde(default)]
+    pub vertical: Option<VerticalEdge>,
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WidgetAppearance {
+    #[serde(default)]
+    pub material: WidgetMaterial,
+    /// 拖到屏幕边缘或角落附近时自动贴靠。
+    #[serde(default = "default_snap_to_edges")]
+    pub snap_to_edges: bool,
+    /// 由后端在吸附时记录，下次打开时恢复到同一位置；前端传入的值会被忽略。
+    #[serde(default)]
+    pub dock: Option<WidgetDock>,
+}
+
+fn default_snap_to_edges() -> bool {
+    true
+}
+
+impl Default for WidgetAppearance {
+    fn default() -> Self {
+        Self {
+            material: WidgetMaterial::default(),
+            snap_to_edges: default_snap_to_edges(),
+            dock: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetAppearanceEvent {
+    pub widget: String,
+    pub appearance: WidgetAppearance,
+}
+
+pub struct WidgetStore {
+    widgets_file: PathBuf,
+    appearances: Mutex<HashMap<String, WidgetAppearance>>,
+    /// 每个小组件最近一次移动的序号，用于判断拖动是否已停止。
+    moves: Mutex<HashMap<String, u64>>,
+}
+
+impl WidgetStore {
+    pub fn new(app: &AppHandle) -> Result<Self, WidgetError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let widgets_file = base_dir.join(WIDGETS_FILENAME);
+        let appearances = load_json(&widgets_file, HashMap::new);
+        Ok(Self {
+            widgets_file,
+            appearances: Mutex::new(appearances),
+            moves: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, WidgetAppearance>> {
+        self.appearances.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 窗口不是小组件时返回 `None`，由全局主题决定窗口效果。
+    pub fn appearance(&self, label: &str) -> Option<WidgetAppearance> {
+        WIDGET_LABELS
+            .contains(&label)
+            .then(|| self.lock().get(label).cloned().unwrap_or_default())
+    }
+
+    pub fn set(
+        &self,
+        widget: &str,
+        appearance: WidgetAppearance,
+    ) -> Result<WidgetAppearance, WidgetError> {
+        if !WIDGET_LABELS.contains(&widget) {
+            return Err(WidgetError::UnknownWidget(widget.to_string()));
+        }
+        let dock = self
+            .appearance(widget)
+            .and_then(|current| current.dock)
+            .filter(|_| appearance.snap_to_edges);
+        self.save(widget, WidgetAppearance { dock, ..appearance })
+    }
+
+    fn save(
+        &self,
+        widget: &str,
+        appearance: WidgetAppearance,
+    ) -> Result<WidgetAppearance, WidgetError> {
+        let mut guard = self.lock();
+        let mut updated = guard.clone();
+        updated.insert(widget.to_string(), appearance.clone());
+        write_atomic(&self.widgets_file, &serde_json::to_vec_pretty(&updated)?)?;
+        *guard = updated;
+        Ok(appearance)
+    }
+
+    fn record_move(&self, widget: &str) -> u64 {
+        let mut moves = self.moves.lock().unwrap_or_else(|e| e.into_inner());
+        let serial = moves.entry(widget.to_string()).or_default();
+        *serial += 1;
+        *serial
+    }
+
+    fn last_move(&self, widget: &str) -> u64 {
+        let moves = self.moves.lock().unwrap_or_else(|e| e.into_inner());
+        moves.get(widget).copied().unwrap_or_default()
+    }
+}
+
+/// 把窗口贴到工作区（不含任务栏、程序坞）中靠近的边或角，返回停靠位置；离边缘都较远时返回 `None`。
+fn snap(window: &Window) -> Result<Option<WidgetDock>, WidgetError> {
+    let Some(monitor) = window.current_monitor()? else {
+        return Ok(None);
+    };
+    let area = monitor.work_area();
+    let position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let threshold = (SNAP_DISTANCE * monitor.scale_factor()).round() as i32;
+    let (left, top) = (area.position.x, area.position.y);
+    let right = left + area.size.width as i32 - size.width as i32;
+    let bottom = top + area.size.height as i32 - size.height as i32;
+
+    let horizontal = if (position.x - left).abs() <= threshold {
+        Some(HorizontalEdge::Left)
+    } else if (position.x - right).abs() <= threshold {
+        Some(HorizontalEdge::Right)
+    } else {
+        None
+    };
+    let vertical = if (position.y - top).abs() <= threshold {
+        Some(VerticalEdge::Top)
+    } else if (position.y - bottom).abs() <= threshold {
+        Some(VerticalEdge::Bottom)
+    } else {
+        None
+    };
+    if horizontal.is_none() && vertical.is_none() {
+        return Ok(None);
+    }
+    let dock = WidgetDock {
+        horizontal,
+        vertical,
+        monitor: monitor.name().cloned(),
+    };
+    move_to_dock(window, &monitor, &dock)?;
+    Ok(Some(dock))
+}
+
+fn move_to_dock(window: &Window, monitor: &Monitor, dock: &WidgetDock) -> Result<(), WidgetError> {
+    let area = monitor.work_area();
+    let position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let x = match dock.horizontal {
+        Some(HorizontalEdge::Left) => area.position.x,
+        Some(HorizontalEdge::Right) => area.position.x + area.size.width as i32 - size.width as i32,
+        None => position.x,
+    };
+    let y = match dock.vertical {
+        Some(VerticalEdge::Top) => area.position.y,
+        Some(VerticalEdge::Bottom) => {
+            area.position.y + area.size.height as i32 - size.height as i32
+        }
+        None => position.y,
+    };
+    let target = PhysicalPosition::new(x, y);
+    // 吸附本身也会触发移动事件，位置不变时不再设置，避免循环
+    if target != position {
+        window.set_position(target)?;
+    }
+    Ok(())
+}
+
+/// 小组件停止拖动后吸附到附近的边缘，并保存停靠位置（拖离边缘时清除）。
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if !matches!(event, WindowEvent::Moved(_)) {
+        return;
+    }
+    let Some(widgets) = window.try_state::<WidgetStore>() else {
+        return;
+    };
+    let label = window.label().to_string();
+    if !widgets
+        .appearance(&label)
+        .is_some_and(|appearance| appearance.snap_to_edges)
+    {
+        return;
+    }
+    let serial = widgets.record_move(&label);
+    let window = window.clone();
+    async_runtime::spawn(async move {
+        tokio::time::sleep(SNAP_DELAY).await;
+        let widgets = window.state::<WidgetStore>();
+        if widgets.last_move(&label) != serial {
+            return;
+        }
+        let Some(current) = widgets.appearance(&label) else {
+            return;
+        };
+        let result = snap(&window).and_then(|dock| {
+            if dock == current.dock {
+                return Ok(());
+            }
+            widgets.save(&label, WidgetAppearance { dock, ..current })?;
+            Ok(())
+        });
+        if let Err(err) = result {
+            log::debug!("failed to snap widget {label}: {err}");
+        }
+    });
+}
+
+/// 打开小组件时恢复上次的停靠位置；保存的显示器已断开时停靠到当前显示器。
+pub fn restore_dock(window: &Window) {
+    let Some(widgets) = window.try_state::<WidgetStore>() else {
+        return;
+    };
+    let Some(dock) = widgets
+        .appearance(window.label())
+        .filter(|appearance| appearance.snap_to_edges)
+        .and_then(|appearance| appearance.dock)
+    else {
+        return;
+    };
+    let result = window.available_monitors().and_then(|monitors| {
+        let saved = monitors
+            .into_iter()
+            .find(|monitor| dock.monitor.is_some() && monitor.name() == dock.monitor.as_ref());
+        match saved {
+            Some(monitor) => Ok(Some(monitor)),
+            None => window.current_monitor(),
+        }
+    });
+    let restored = match result {
+        Ok(Some(monitor)) => move_to_dock(window, &monitor, &dock),
+        Ok(None) => Ok(()),
+        Err(err) => Err(err.into()),
+    };
+    if let Err(err) = restored {
+        log::debug!(
+            "failed to restore dock for widget {}: {err}",
+            window.label()
+        );
+    }
+}
+
+#[tauri::command]
+pub fn get_widget_appearance(
+    widgets: tauri::State<'_, WidgetStore>,
+    widget: String,
+) -> Result<WidgetAppearance, String> {
+    widgets
+        .appearance(&widget)
+        .ok_or_else(|| WidgetError::UnknownWidget(widget).to_string())
+}
+
+/// 保存小组件材质并立即应用到已打开的窗口；小组件页面据此切换为透明背景。
+#[tauri::command]
+pub fn set_widget_appearance(
+    app: AppHandle,
+    widgets: tauri::State<'_, WidgetStore>,
+    widget: String,
+    appearance: WidgetAppearance,
+) -> Result<WidgetAppearance, String> {
+    let appearance = widgets
+        .set(&widget, appearance)
+        .map_err(|e| e.to_string())?;
+    theme::apply_to_all(&app);
+    let _ = app.emit(
+        WIDGET_APPEARANCE_EVENT,
+        WidgetAppearanceEvent {
+            widget,
+            appearance: appearance.clone(),
+        },
+    );
+    Ok(appearance)
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{AppHandle, Emitter, Manager, Webview, WebviewWindow};
+use thiserror::Error;
+
+use crate::storage::{load_json, prepare_dir, write_atomic};
+
+const WINDOW_PREFS_FILENAME: &str = "window-prefs.json";
+const WINDOW_PREFS_CHANGED_EVENT: &str = "window://prefs-changed";
+/// 过低的不透明度会让窗口几乎看不见，难以再调回来。
+const MIN_OPACITY: f64 = 0.2;
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+
+#[derive(Debug, Error)]
+pub enum WindowPrefsError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+    #[error("窗口不存在：{0}")]
+    WindowNotFound(String),
+    #[error("不透明度无效：{0}，应在 0.2 到 1 之间")]
+    InvalidOpacity(f64),
+    #[error("缩放比例无效：{0}，应在 0.5 到 3 之间")]
+    InvalidZoom(f64),
+    #[error("当前平台不支持该窗口设置")]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowPrefs {
+    /// 未设置时保持窗口创建时的置顶状态（小组件默认置顶）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+    #[serde(default = "default_scale")]
+    pub opacity: f64,
+    /// 页面缩放比例，文字和界面一起缩放。
+    #[serde(default = "default_scale")]
+    pub zoom: f64,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl Default for WindowPrefs {
+    fn default() -> Self {
+        Self {
+            pinned: None,
+            opacity: default_scale(),
+            zoom: default_scale(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowPrefsEvent {
+    pub label: String,
+    pub prefs: WindowPrefs,
+}
+
+pub struct WindowPrefsStore {
+    prefs_file: PathBuf,
+    prefs: Mutex<HashMap<String, WindowPrefs>>,
+}
+
+impl WindowPrefsStore {
+    pub fn new(app: &AppHandle) -> Result<Self, WindowPrefsError> {
+        let base_dir = app.path().app_local_data_dir()?;
+        prepare_dir(&base_dir)?;
+        let prefs_file = base_dir.join(WINDOW_PREFS_FILENAME);
+        let prefs = load_json(&prefs_file, HashMap::new);
+        Ok(Self {
+            prefs_file,
+            prefs: Mutex::new(prefs),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, WindowPrefs>> {
+        self.prefs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(&self, label: &str) -> WindowPrefs {
+        self.lock().get(label).cloned().unwrap_or_default()
+    }
+
+    fn update(
+        &self,
+        label: &str,
+        change: impl FnOnce(&mut WindowPrefs),
+    ) -> Result<WindowPrefs, WindowPrefsError> {
+        let mut guard = self.lock();
+        let mut updated = guard.clone();
+        let prefs = updated.entry(label.to_string()).or_default();
+        change(prefs);
+        let prefs = prefs.clone();
+        write_atomic(&self.prefs_file, &serde_json::to_vec_pretty(&updated)?)?;
+        *guard = updated;
+        Ok(prefs)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_native_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), WindowPrefsError> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = window.hwnd()?.0 as isize;
+    // 分层窗口才能设置整体透明度
+    let applied = unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA)
+    };
+    if applied == 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn set_native_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), WindowPrefsError> {
+    use objc2::runtime::AnyObject;
+
+    let target = window.clone();
+    window.run_on_main_thread(move || {
+        let Ok(ns_window) = target.ns_window() else {
+            return;
+        };
+        unsafe {
+            let _: () = objc2::msg_send![ns_window as *mut AnyObject, setAlphaValue: opacity];
+        }
+    })?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_native_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), WindowPrefsError> {
+    use gtk::prelude::WidgetExt;
+
+    let target = window.clone();
+    window.run_on_main_thread(move || {
+        if let Ok(gtk_window) = target.gtk_window() {
+            gtk_window.set_opacity(opacity);
+        }
+    })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn set_native_opacity(_window: &WebviewWindow, _opacity: f64) -> Result<(), WindowPrefsError> {
+    Err(WindowPrefsError::Unsupported)
+}
+
+#[cfg(desktop)]
+fn set_pinned(window: &WebviewWindow, pinned: bool) -> Result<(), WindowPrefsError> {
+    Ok(window.set_always_on_top(pinned)?)
+}
+
+#[cfg(mobile)]
+fn set_pinned(_window: &WebviewWindow, _pinned: bool) -> Result<(), WindowPrefsError> {
+    Err(WindowPrefsError::Unsupported)
+}
+
+fn apply(window: &WebviewWindow, prefs: &WindowPrefs) -> Result<(), WindowPrefsError> {
+    if let Some(pinned) = prefs.pinned {
+        set_pinned(window, pinned)?;
+    }
+    window.set_zoom(prefs.zoom)?;
+    set_native_opacity(window, prefs.opacity)
+}
+
+/// 窗口（包括之后打开的小组件）开始加载页面时恢复保存的置顶、缩放和不透明度。
+pub fn on_page_load(webview: &Webview, payload: &PageLoadPayload<'_>) {
+    if !matches!(payload.event(), PageLoadEvent::Started) {
+        return;
+    }
+    let app = webview.app_handle();
+    let (Some(store), Some(window)) = (
+        app.try_state::<WindowPrefsStore>(),
+        app.get_webview_window(webview.label()),
+    ) else {
+        return;
+    };
+    let prefs = store.get(window.label());
+    // 从未调整过的窗口保持原样
+    if prefs.pinned.is_none() && prefs.opacity >= 1.0 && prefs.zoom == 1.0 {
+        return;
+    }
+    if let Err(err) = apply(&window, &prefs) {
+        log::debug!("failed to apply prefs for window {}: {err}", window.label());
+    }
+}
+
+fn update_window(
+    app: &AppHandle,
+    store: &WindowPrefsStore,
+    label: &str,
+    change: impl FnOnce(&mut WindowPrefs),
+) -> Result<WindowPrefs, WindowPrefsError> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| WindowPrefsError::WindowNotFound(label.to_string()))?;
+    let mut prefs = store.get(label);
+    change(&mut prefs);
+    apply(&window, &prefs)?;
+    let prefs = store.update(label, |saved| *saved = prefs)?;
+    let _ = app.emit(
+        WINDOW_PREFS_CHANGED_EVENT,
+        WindowPrefsEvent {
+            label: label.to_string(),
+            prefs: prefs.clone(),
+        },
+    );
+    Ok(prefs)
+}
+
+#[tauri::command]
+pub fn get_window_prefs(
+    store: tauri::State<'_, WindowPrefsStore>,
+    label: String,
+) -> Result<WindowPrefs, String> {
+    Ok(store.get(&label))
+}
+
+/// 置顶或取消置顶任意窗口（包括主窗口），下次打开时沿用。
+#[tauri::command]
+pub fn set_window_pin(
+    app: AppHandle,
+    store: tauri::State<'_, WindowPrefsStore>,
+    label: String,
+    pinned: bool,
+) -> Result<WindowPrefs, String> {
+    update_window(&app, &store, &label, |prefs| prefs.pinned = Some(pinned))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_window_opacity(
+    app: AppHandle,
+    store: tauri::State<'_, WindowPrefsStore>,
+    label: String,
+    value: f64,
+) -> Result<WindowPrefs, String> {
+    if !(MIN_OPACITY..=1.0).contains(&value) {
+        return Err(WindowPrefsError::InvalidOpacity(value).to_string());
+    }
+    update_window(&app, &store, &label, |prefs| prefs.opacity = value).map_err(|e| e.to_string())
+}
+
+/// 设置窗口的页面缩放，适合高分屏或需要更大字号的用户，下次打开时沿用。
+#[tauri::command]
+pub fn set_window_zoom(
+    app: AppHandle,
+    store: tauri::State<'_, WindowPrefsStore>,
+    label: String,
+    value: f64,
+) -> Result<WindowPrefs, String> {
+    if !(MIN_ZOOM..=MAX_ZOOM).contains(&value) {
+        return Err(WindowPrefsError::InvalidZoom(value).to_string());
+    }
+    update_window(&app, &store, &label, |prefs| prefs.zoom = value).map_err(|e| e.to_string())
+}
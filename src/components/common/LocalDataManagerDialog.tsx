@@ -32,7 +32,7 @@ import {
   tagsAtom,
   todosAtom,
 } from '../../stores/todoStore';
-import type { SpeechSession } from '../../types/speech';
+import type { ImportSessionsReport, SpeechSession } from '../../types/speech';
 
 type LocalDataCategory = 'tasks' | 'excel' | 'speech' | 'other';
 
@@ -451,9 +451,13 @@ const LocalDataManagerDialog: React.FC<LocalDataManagerDialogProps> = ({
       if (payload.speechSessions && payload.speechSessions.length > 0) {
         if (isTauriEnvironment()) {
           try {
-            importedSpeechCount = await invoke<number>('import_speech_sessions', {
+            const report = await invoke<ImportSessionsReport>('import_speech_sessions', {
               sessions: payload.speechSessions,
             });
+            importedSpeechCount = report.imported;
+            if (report.failed.length > 0) {
+              console.warn('部分语音记录导入失败:', report.failed);
+            }
             await refreshSpeechSessions();
           } catch (error) {
             console.error('导入语音数据失败:', error);
@@ -17,8 +17,18 @@ import DownloadIcon from '@mui/icons-material/Download';
 import FiberManualRecordIcon from '@mui/icons-material/FiberManualRecord';
 import AutoAwesomeIcon from '@mui/icons-material/AutoAwesome';
 import UploadFileIcon from '@mui/icons-material/UploadFile';
+import { invoke } from '@tauri-apps/api/core';
 
-import type { ModelDownloadProgress, SpeechLanguage } from '../../types/speech';
+import type {
+  ModelDownloadProgress,
+  SpeechLanguage,
+  SpeechLanguageOption,
+} from '../../types/speech';
+
+const FALLBACK_LANGUAGES: SpeechLanguageOption[] = [
+  { code: 'zh', name: 'chinese', display_name: '中文', supported: true },
+  { code: 'en', name: 'english', display_name: '英语', supported: true },
+];
 
 const formatSeconds = (value: number) => {
   const totalSeconds = Math.max(0, Math.floor(value));
@@ -69,6 +79,21 @@ const RecorderControls: React.FC<RecorderControlsProps> = ({
   lastTranscriptionDuration,
 }) => {
   const fileInputRef = React.useRef<HTMLInputElement>(null);
+  const [languages, setLanguages] = React.useState<SpeechLanguageOption[]>(FALLBACK_LANGUAGES);
+
+  React.useEffect(() => {
+    let active = true;
+    invoke<SpeechLanguageOption[]>('list_speech_languages')
+      .then(options => {
+        if (active && options.length > 0) {
+          setLanguages(options);
+        }
+      })
+      .catch(error => console.error('Failed to load speech languages', error));
+    return () => {
+      active = false;
+    };
+  }, [modelReady]);
 
   const handleFileUpload = React.useCallback((event: React.ChangeEvent<HTMLInputElement>) => {
     const file = event.target.files?.[0];
@@ -160,8 +185,11 @@ const RecorderControls: React.FC<RecorderControlsProps> = ({
               },
             }}
           >
-            <MenuItem value="zh">🇨🇳 中文</MenuItem>
-            <MenuItem value="en">🇺🇸 英语</MenuItem>
+            {languages.map(option => (
+              <MenuItem key={option.code} value={option.code} disabled={!option.supported}>
+                {option.display_name}
+              </MenuItem>
+            ))}
           </Select>
         </FormControl>
 
@@ -68,7 +68,7 @@ const TranscriptView: React.FC<TranscriptViewProps> = ({
           </Typography>
         </Box>
         <Chip 
-          label={language === 'zh' ? '🇨🇳 中文' : '🇺🇸 English'} 
+          label={language === 'zh' ? '🇨🇳 中文' : language === 'en' ? '🇺🇸 English' : language.toUpperCase()} 
           size="medium" 
           variant="outlined"
           sx={{ 
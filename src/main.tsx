@@ -5,12 +5,15 @@ import { routeTree } from './routeTree.gen'
 import { ThemeProvider } from '@mui/material/styles'
 import CssBaseline from '@mui/material/CssBaseline'
 import { theme } from './theme'
+import { forwardNotificationActions } from './utils/notificationUtils'
 
 import '@fontsource-variable/plus-jakarta-sans'
 import './styles/global.scss'
 
 const router = createRouter({ routeTree })
 
+void forwardNotificationActions()
+
 declare module '@tanstack/react-router' {
   interface Register {
     router: typeof router
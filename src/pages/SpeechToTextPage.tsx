@@ -17,9 +17,13 @@ import type {
   SpeechLanguage,
   SpeechSession,
   TranscribeAudioResponse,
+  TranscriptDraft,
   TranscriptSegment,
 } from '../types/speech';
 
+/** 编辑停止这么久后把未保存的文案写入草稿，崩溃后可以恢复。 */
+const DRAFT_AUTOSAVE_DELAY_MS = 3000;
+
 const SpeechToTextPage: React.FC = () => {
   const [language, setLanguage] = React.useState<SpeechLanguage>('zh');
   const [isRecording, setIsRecording] = React.useState(false);
@@ -236,6 +240,15 @@ const SpeechToTextPage: React.FC = () => {
     setTranscriptDraft(session?.transcript ?? '');
     setSegments(session?.segments ?? []);
     void prepareAudioSource(session);
+    if (session) {
+      invoke<TranscriptDraft | null>('get_transcript_draft', { sessionId: session.id })
+        .then(draft => {
+          if (draft && selectedSessionIdRef.current === session.id) {
+            setTranscriptDraft(draft.text);
+          }
+        })
+        .catch(err => console.error('Failed to load transcript draft', err));
+    }
   }, [prepareAudioSource]);
 
   const loadSessions = React.useCallback(async (preferredId?: string | null) => {
@@ -653,6 +666,19 @@ const SpeechToTextPage: React.FC = () => {
     return transcriptDraft !== currentSession.transcript;
   }, [currentSession, transcriptDraft]);
 
+  React.useEffect(() => {
+    if (!currentSession || !hasTranscriptChanges) {
+      return;
+    }
+    const timer = window.setTimeout(() => {
+      invoke('autosave_transcript_draft', {
+        sessionId: currentSession.id,
+        text: transcriptDraft,
+      }).catch(err => console.error('Failed to autosave transcript draft', err));
+    }, DRAFT_AUTOSAVE_DELAY_MS);
+    return () => window.clearTimeout(timer);
+  }, [currentSession, hasTranscriptChanges, transcriptDraft]);
+
   const transcriptLanguage = currentSession?.language ?? language;
 
   React.useEffect(() => {
@@ -21,6 +21,7 @@ import {
 	Tooltip,
 	Typography,
 } from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { useAtomValue, useSetAtom } from "jotai";
 import type { FC } from "react";
@@ -429,7 +430,10 @@ const TodoWidgetPage: FC = () => {
 	const handleTogglePin = useCallback(async () => {
 		try {
 			const next = !isPinned;
-			await currentWindow.setAlwaysOnTop(next);
+			await invoke("set_window_pin", {
+				label: currentWindow.label,
+				pinned: next,
+			});
 			setIsPinned(next);
 		} catch (error) {
 			console.error("Failed to toggle always-on-top", error);
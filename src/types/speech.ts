@@ -1,11 +1,64 @@
-export type SpeechLanguage = 'en' | 'zh';
+/** whisper 语言代码，如 'en'、'zh'、'ja'，可选值由 list_speech_languages 返回。 */
+export type SpeechLanguage = string;
+
+export interface SpeechLanguageOption {
+  code: SpeechLanguage;
+  name: string;
+  display_name: string;
+  /** 当前模型能否转写该语言。 */
+  supported: boolean;
+}
+
+export interface CalendarEvent {
+  uid: string;
+  summary: string;
+  start: string;
+  end: string;
+  attendees: string[];
+  location?: string | null;
+}
+
+export interface AudioWarning {
+  kind:
+    | 'clipping'
+    | 'near_silence'
+    | 'low_volume'
+    | 'low_sample_rate'
+    | 'phase_cancellation'
+    | 'too_short';
+  message: string;
+}
+
+export interface AudioAnalysis {
+  duration_seconds: number;
+  sample_rate: number;
+  channels: number;
+  peak: number;
+  rms_dbfs: number;
+  clipping_ratio: number;
+  voiced_seconds: number;
+  warnings: AudioWarning[];
+}
 
 export interface TranscriptSegment {
   start: number;
   end: number;
   text: string;
+  speaker?: string | null;
+  /** 逐词时间，旧会话和实时转写事件中没有。 */
+  words?: WordTiming[];
+  /** 文字 token 的平均概率（0 到 1），旧会话和实时转写事件中没有。 */
+  confidence?: number | null;
 }
 
+export interface WordTiming {
+  text: string;
+  start: number;
+  end: number;
+}
+
+export type ChannelMode = 'mix' | 'left' | 'right' | 'speakers';
+
 export interface SpeechSession {
   id: string;
   title: string;
@@ -14,21 +67,179 @@ export interface SpeechSession {
   segments: TranscriptSegment[];
   audio_path: string;
   created_at: string;
+  tags: string[];
+  calendar_event?: CalendarEvent | null;
+  audio_warnings?: AudioWarning[];
+  audio_removed_at?: string | null;
+  gpu_fallback?: string | null;
+  /** 文字稿是翻译成的英文，`language` 为录音的原始语言。 */
+  translated?: boolean;
+  /** 转写时使用的自定义提示（含词汇表）。 */
+  initial_prompt?: string | null;
+  /** 转写时实际使用的解码参数。 */
+  transcription_options?: TranscriptionOptions | null;
+}
+
+export interface GpuFallbackEvent {
+  session_id: string;
+  reason: string;
+}
+
+/** 转写过程中逐段推送；`pass` 变化表示改用 CPU 从头重转。 */
+export interface TranscribeSegmentEvent {
+  session_id: string;
+  pass: number;
+  segment: TranscriptSegment;
+}
+
+/** 长音频分块转写的进度，`chunk` 从 1 开始，完成时等于 `total_chunks`。 */
+export interface TranscribeProgressEvent {
+  session_id: string;
+  pass: number;
+  percent: number;
+  chunk: number;
+  total_chunks: number;
+}
+
+/** 后端原生录音的状态，音频直接写入会话目录。 */
+export interface RecordingStatus {
+  session_id: string;
+  started_at: string;
+  paused: boolean;
+  duration_seconds: number;
+  /** 按 `max_part_minutes` 切分出的文件数。 */
+  parts: number;
+}
+
+export interface RecordedAudio {
+  session_id: string;
+  audio_path: string;
+  duration_seconds: number;
+  /** 开启自动增益时每秒的增益倍数。 */
+  gain_curve?: number[];
+  /** 切分出的后续文件，各自是一个待转写的会话，前后以“下一段”链接。 */
+  parts?: RecordedAudio[];
+}
+
+/** `get_recording_settings` / `set_recording_settings`，下次开始录音时生效。 */
+export interface RecordingSettings {
+  auto_gain: boolean;
+  /** 以扬声器的回环声音为参考消除回声，目前只支持 Windows。 */
+  echo_cancellation: boolean;
+  /** 8000、16000、22050、32000、44100 或 48000，不能超过麦克风支持的最高采样率。 */
+  sample_rate: number;
+  /** 16、24 位整数或 32 位浮点。 */
+  bits_per_sample: 16 | 24 | 32;
+  /** 目前只支持 `wav`，`flac` 和 `opus` 会被拒绝。 */
+  container: "wav" | "flac" | "opus";
+  /** 录音控件打开后（`arm_recording`）缓存的秒数，最多 30，0 表示不预录。 */
+  pre_roll_seconds: number;
+  /** 单个文件的最长分钟数，0 表示不切分。 */
+  max_part_minutes: number;
+}
+
+export interface TranscribeRecordingPayload {
+  session_id: string;
+  language: SpeechLanguage;
+  session_title?: string | null;
+  project?: string | null;
+  /** 转写前跳过较长的静音。 */
+  vad_enabled?: boolean;
+  /** 翻译成英文。 */
+  translate?: boolean;
+  /** 自定义初始提示。 */
+  initial_prompt?: string | null;
+  /** 希望优先识别的人名、术语和产品名。 */
+  vocabulary?: string[];
+  /** 覆盖全局解码设置的高级参数。 */
+  options?: TranscriptionOptions;
+}
+
+/** 模型 id，如 'small'、'small-q5_1'，导入的本地模型以 'custom-' 开头。 */
+export type SpeechModel = string;
+
+export interface ModelSpec {
+  id: SpeechModel;
+  size_bytes: number;
+  ram_bytes: number;
+  quantization?: string | null;
+  languages: 'multilingual' | 'english';
+  sha256?: string | null;
+}
+
+export interface ModelRegistryEntry extends ModelSpec {
+  url: string;
+}
+
+export interface CustomModel {
+  id: SpeechModel;
+  name: string;
+  source_path: string;
+  size_bytes: number;
+  linked: boolean;
+  english_only: boolean;
+  imported_at: string;
+}
+
+export interface InstalledModel {
+  id: SpeechModel;
+  spec?: ModelSpec | null;
+  custom?: CustomModel | null;
+  downloaded: boolean;
+  active: boolean;
+  on_disk_bytes?: number | null;
+  model_path: string;
 }
 
 export interface ModelStatusResponse {
   ready: boolean;
   downloaded: boolean;
+  model: SpeechModel;
   model_path?: string | null;
+  acceleration: Acceleration;
+}
+
+export type Acceleration = 'cpu' | 'metal' | 'cuda' | 'vulkan' | 'core_ml';
+
+export type TranscriptionBackend = 'cpu' | 'metal' | 'cuda' | 'vulkan';
+
+export interface BackendCapability {
+  backend: TranscriptionBackend;
+  available: boolean;
+  selected: boolean;
+}
+
+export interface ModelSource {
+  mirror_url?: string | null;
+  proxy?: string | null;
+  max_retries?: number | null;
+}
+
+/** Hugging Face 访问令牌保存在系统钥匙串中，前端只能看到是否已设置。 */
+export interface HfTokenStatus {
+  configured: boolean;
+}
+
+export interface ModelRecommendation {
+  model: SpeechModel;
+  total_memory_bytes: number;
+  available_memory_bytes: number;
+  cpu_cores: number;
+  /** 所选后端可用 GPU 加速，此时不按 CPU 核心数限制大模型。 */
+  gpu: boolean;
 }
 
 export interface ModelDownloadProgress {
   downloaded_bytes: number;
   total_bytes: number | null;
+  resumed_from_bytes: number;
+  bytes_per_second: number;
+  eta_seconds?: number | null;
 }
 
 export interface ModelStatusEvent {
-  status: 'exists' | 'downloading' | 'finished' | 'failed';
+  status: 'exists' | 'downloading' | 'finished' | 'failed' | 'cancelled' | 'paused' | 'retrying';
+  model: SpeechModel;
   model_path?: string | null;
   message?: string | null;
 }
@@ -36,3 +247,243 @@ export interface ModelStatusEvent {
 export interface TranscribeAudioResponse {
   session: SpeechSession;
 }
+
+export interface ImportFailure {
+  session_id: string;
+  title: string;
+  reason: string;
+}
+
+export interface ImportSessionsReport {
+  total: number;
+  imported: number;
+  skipped: number;
+  failed: ImportFailure[];
+}
+
+export interface ImportProgressEvent {
+  current: number;
+  total: number;
+  session_id: string;
+  title: string;
+  status: 'imported' | 'skipped' | 'failed';
+  message?: string | null;
+}
+
+export interface UntranscribedRecording {
+  id: string;
+  language: SpeechLanguage;
+  title?: string | null;
+  project?: string | null;
+  created_at: string;
+  reason: string;
+  calendar_event?: CalendarEvent | null;
+  channel_mode?: ChannelMode;
+  vad_enabled?: boolean;
+  translate?: boolean;
+  initial_prompt?: string | null;
+  options?: TranscriptionOptions;
+  size_bytes: number;
+}
+
+export type RetentionScope =
+  | { kind: 'all' }
+  | { kind: 'tag'; tag: string }
+  | { kind: 'untagged' };
+
+export type RetentionAction = 'delete_audio' | 'delete_session';
+
+export interface RetentionPolicy {
+  id: string;
+  name: string;
+  scope: RetentionScope;
+  older_than_days: number;
+  action: RetentionAction;
+  enabled: boolean;
+  created_at: string;
+}
+
+export interface RetentionCandidate {
+  policy_id: string;
+  policy_name: string;
+  session_id: string;
+  session_title: string;
+  created_at: string;
+  action: RetentionAction;
+  freed_bytes: number;
+}
+
+export interface RetentionReport {
+  dry_run: boolean;
+  evaluated_at: string;
+  candidates: RetentionCandidate[];
+  freed_bytes: number;
+}
+
+export interface DecodingSettings {
+  no_speech_threshold: number;
+  entropy_threshold: number;
+  logprob_threshold: number;
+  temperature_increment: number;
+  suppress_hallucinations: boolean;
+}
+
+/** 单次转写覆盖的解码参数，未填写的项沿用全局设置。 */
+export interface TranscriptionOptions {
+  temperature?: number | null;
+  entropy_threshold?: number | null;
+  no_speech_threshold?: number | null;
+  /** 每段最多生成的 token 数，0 表示不限制。 */
+  max_tokens_per_segment?: number | null;
+  suppress_blanks?: boolean | null;
+}
+
+/** 可用变量：{date} {time} {weekday} {language} {event} {project}。 */
+export interface TitleTemplate {
+  template: string;
+}
+
+export type TimestampStyle = 'date_time' | 'date' | 'time';
+
+export interface PreloadModelResponse {
+  model: SpeechModel;
+  already_loaded: boolean;
+  gpu: boolean;
+}
+
+export interface RemoteModelInfo {
+  etag?: string | null;
+  content_length?: number | null;
+  downloaded_at: string;
+}
+
+export interface ModelUpdateCheck {
+  model: SpeechModel;
+  update_available: boolean;
+  pinned: boolean;
+  local?: RemoteModelInfo | null;
+  remote_etag?: string | null;
+  remote_content_length?: number | null;
+  redownloading: boolean;
+}
+
+export interface ModelBenchmark {
+  model: SpeechModel;
+  gpu: boolean;
+  audio_seconds: number;
+  load_seconds: number;
+  transcribe_seconds: number;
+  realtime_factor: number;
+  peak_memory_bytes?: number | null;
+}
+
+export type GroupGranularity = 'day' | 'week' | 'month';
+
+export interface SessionListItem {
+  id: string;
+  title: string;
+  language: SpeechLanguage;
+  created_at: string;
+  duration_seconds: number;
+  tags: string[];
+}
+
+export interface SessionGroup {
+  start: string;
+  end: string;
+  session_count: number;
+  duration_seconds: number;
+  sessions: SessionListItem[];
+}
+
+export type PanelKind = 'session_detail' | 'transcript_editor';
+
+/** 分离到独立窗口的面板；`state` 为交接的界面状态，收回时随 `panels://attached` 交还。 */
+export interface DetachedPanel {
+  label: string;
+  panel: PanelKind;
+  session_id: string;
+  state: unknown;
+  opened_at: string;
+}
+
+/** 编辑中尚未保存的文字稿草稿。 */
+export interface TranscriptDraft {
+  session_id: string;
+  text: string;
+  saved_at: string;
+}
+
+export type JournalOperationKind = 'delete_session' | 'delete_todo' | 'edit_todos' | 'replace_todos';
+
+/** 可通过 `undo_last_operation` 撤销的操作，30 分钟后过期。 */
+export interface JournalEntry {
+  id: string;
+  summary: string;
+  operation: { kind: JournalOperationKind } & Record<string, unknown>;
+  recorded_at: string;
+}
+
+export type SegmentIssueKind = 'invalid' | 'out_of_range' | 'reversed' | 'overlap';
+
+export interface SegmentIssue {
+  index: number;
+  kind: SegmentIssueKind;
+  /** 修复前的时间。 */
+  start: number;
+  end: number;
+}
+
+/** `validate_segments` 的结果，`session` 为修复后的会话。 */
+export interface SegmentValidationReport {
+  audio_seconds: number | null;
+  issues: SegmentIssue[];
+  session: SpeechSession;
+}
+
+export interface EntityRef {
+  kind: 'session' | 'todo' | 'note';
+  id: string;
+}
+
+export interface EntityLink {
+  id: string;
+  from: EntityRef;
+  to: EntityRef;
+  label?: string | null;
+  created_at: string;
+}
+
+/** `export_session_json` 的结果，`format_version` 只在字段含义改变时递增。 */
+export interface SessionArchive {
+  format: 'kk-session';
+  format_version: number;
+  generator: string;
+  exported_at: string;
+  session: {
+    id: string;
+    title: string;
+    language: SpeechLanguage;
+    translated: boolean;
+    created_at: string;
+    duration_seconds: number;
+    transcript: string;
+    initial_prompt: string | null;
+    transcription_options: TranscriptionOptions | null;
+    gpu_fallback: string | null;
+    calendar_event: CalendarEvent | null;
+  };
+  segments: TranscriptSegment[];
+  annotations: {
+    tags: string[];
+    speakers: string[];
+    links: EntityLink[];
+    audio_warnings: AudioWarning[];
+  };
+  /** 小写十六进制 SHA-256；`segments_sha256` 针对 segments 的紧凑 JSON。 */
+  checksums: {
+    transcript_sha256: string;
+    segments_sha256: string;
+    audio: { file_name: string; size_bytes: number; sha256: string } | null;
+  };
+}
@@ -1,9 +1,12 @@
 import type { Options as NotificationOptions } from "@tauri-apps/plugin-notification";
 import {
 	isPermissionGranted,
+	onAction,
 	requestPermission,
 	sendNotification,
 } from "@tauri-apps/plugin-notification";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import { debugError, debugLog } from "./logger";
 
 export const ensureNotificationPermission = async (): Promise<boolean> => {
@@ -34,3 +37,35 @@ export const sendNativeNotification = async (
 		throw error;
 	}
 };
+
+interface NotificationActionPayload {
+	actionId: string;
+	notification: NotificationOptions;
+}
+
+/** 把系统通知按钮的回调转发给后端处理，只有移动端的通知支持按钮。 */
+export const forwardNotificationActions = async (): Promise<void> => {
+	// 每个窗口都会加载入口脚本，只由主窗口转发，避免同一操作执行多次
+	if (getCurrentWindow().label !== "main") {
+		return;
+	}
+	try {
+		await onAction((payload) => {
+			const { actionId, notification } =
+				payload as unknown as NotificationActionPayload;
+			const target = notification.extra?.target;
+			if (!notification.actionTypeId || typeof target !== "string") {
+				return;
+			}
+			invoke("handle_notification_action", {
+				actionTypeId: notification.actionTypeId,
+				actionId,
+				target,
+			}).catch((error) => {
+				debugError("Failed to handle notification action", error);
+			});
+		});
+	} catch (error) {
+		debugLog("Notification actions are not supported here", error);
+	}
+};